@@ -0,0 +1,40 @@
+use criterion::criterion_group;
+use criterion::criterion_main;
+use criterion::Criterion;
+use ministark_gpu::fields::p3618502788666131213697322783095070105623107215331596699973092056135872020481::ark::Fp;
+use sandstorm_binary::Memory;
+use sandstorm_binary::MAX_ADDRESS;
+use std::hint::black_box;
+
+/// Number of memory cells in a trace of roughly 2^18 steps
+const NUM_CELLS: u64 = 1 << 20;
+
+fn memory_from_addresses(addresses: impl IntoIterator<Item = u64>) -> Memory<Fp> {
+    let bytes = addresses
+        .into_iter()
+        .flat_map(|address| [address.to_le_bytes(), [0; 8], [0; 8], [0; 8], [0; 8]])
+        .flatten()
+        .collect::<Vec<u8>>();
+    Memory::from_reader(&*bytes).unwrap()
+}
+
+fn bench_lookups(c: &mut Criterion, memory: &Memory<Fp>, id: &str) {
+    c.bench_function(&format!("memory/get/{id}"), |b| {
+        b.iter(|| {
+            for address in 1..=NUM_CELLS as usize {
+                black_box(memory.get(black_box(address)));
+            }
+        })
+    });
+}
+
+fn lookup_benches(c: &mut Criterion) {
+    let memory = memory_from_addresses(1..=NUM_CELLS);
+    bench_lookups(c, &memory, "contiguous");
+    // an address far past the others is stored sparsely
+    let memory = memory_from_addresses((1..=NUM_CELLS).chain([MAX_ADDRESS]));
+    bench_lookups(c, &memory, "outlying_address");
+}
+
+criterion_group!(benches, lookup_benches);
+criterion_main!(benches);
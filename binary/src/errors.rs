@@ -1,5 +1,6 @@
+use crate::vm::Relocatable;
+use crate::MAX_ADDRESS;
 use ruint::aliases::U256;
-use std::error::Error;
 use std::fmt::Display;
 
 #[derive(Debug, Clone, Copy)]
@@ -18,4 +19,178 @@ impl Display for InvalidFieldElementError {
     }
 }
 
-impl Error for InvalidFieldElementError {}
+impl std::error::Error for InvalidFieldElementError {}
+
+/// Errors encountered while parsing the binary files outputted by `cairo-run`
+/// (i.e. `trace.bin` and `memory.bin`). Offsets are the byte offset of the
+/// start of the offending record.
+#[derive(Debug)]
+pub enum Error {
+    /// The reader failed for reasons other than reaching the end of input
+    Io(std::io::Error),
+    /// A record ended before all of its fields could be read. `expected_bytes`
+    /// is the size of the field that was cut short.
+    TruncatedRecord {
+        offset: usize,
+        expected_bytes: usize,
+        actual_bytes: usize,
+    },
+    /// A memory word was only partially present. This usually indicates the
+    /// file was generated for a field with a different word width.
+    MisalignedWord {
+        offset: usize,
+        word_bytes: usize,
+        actual_bytes: usize,
+    },
+    /// A memory word is outside the range of the field
+    InvalidFieldElement {
+        offset: usize,
+        error: InvalidFieldElementError,
+    },
+    /// The same memory address was assigned two different values
+    ConflictingAddress {
+        offset: usize,
+        address: u64,
+        existing: U256,
+        value: U256,
+    },
+    /// A memory address is larger than the supported address space
+    AddressOutOfRange {
+        offset: usize,
+        address: u64,
+        max_address: u64,
+    },
+}
+
+impl Display for Error {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Io(error) => write!(f, "IO error: {error}"),
+            Self::TruncatedRecord {
+                offset,
+                expected_bytes,
+                actual_bytes,
+            } => write!(
+                f,
+                "truncated record at byte {offset}: expected {expected_bytes} bytes but only \
+                 {actual_bytes} remain"
+            ),
+            Self::MisalignedWord {
+                offset,
+                word_bytes,
+                actual_bytes,
+            } => write!(
+                f,
+                "misaligned word at byte {offset}: expected a {word_bytes} byte word but only \
+                 {actual_bytes} remain"
+            ),
+            Self::InvalidFieldElement { offset, error } => {
+                write!(f, "invalid word at byte {offset}: {error}")
+            }
+            Self::ConflictingAddress {
+                offset,
+                address,
+                existing,
+                value,
+            } => write!(
+                f,
+                "conflicting value at byte {offset}: address {address} was assigned {existing} \
+                 and {value}"
+            ),
+            Self::AddressOutOfRange {
+                offset,
+                address,
+                max_address,
+            } => write!(
+                f,
+                "address {address} at byte {offset} exceeds the maximum address {max_address}"
+            ),
+        }
+    }
+}
+
+impl std::error::Error for Error {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            Self::Io(error) => Some(error),
+            Self::InvalidFieldElement { error, .. } => Some(error),
+            _ => None,
+        }
+    }
+}
+
+impl From<std::io::Error> for Error {
+    fn from(error: std::io::Error) -> Self {
+        Self::Io(error)
+    }
+}
+
+/// Errors encountered while running a program with the Cairo VM
+#[derive(Debug)]
+pub enum VmError {
+    /// The program is missing a label needed to run in proof mode
+    MissingLabel(String),
+    /// The program uses a builtin that isn't supported by the VM or the layout.
+    /// This includes builtins that are out of order.
+    UnsupportedBuiltin(String),
+    UnsupportedHint {
+        pc: Relocatable,
+        code: String,
+    },
+    InvalidInstruction(Relocatable),
+    /// An operand could neither be read from memory nor deduced
+    UnknownOperand {
+        pc: Relocatable,
+        operand: &'static str,
+    },
+    /// Arithmetic on incompatible values (e.g. adding two addresses) or an
+    /// address outside of its segment
+    InvalidOperation(Relocatable),
+    /// An `assert_eq` or `call` instruction failed
+    AssertionFailed(Relocatable),
+    /// The same memory address was assigned two different values
+    InconsistentMemory(Relocatable),
+    InvalidAddress(Relocatable),
+    /// A value in a builtin segment is invalid for the builtin
+    InvalidBuiltinValue(Relocatable),
+    /// The builtin pointer returned by `main` is invalid
+    InvalidStopPointer(&'static str),
+    StepLimitExceeded(usize),
+    /// The relocated memory is larger than the supported address space
+    AddressOutOfRange(u64),
+}
+
+impl Display for VmError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::MissingLabel(label) => write!(f, "program is missing the label {label}"),
+            Self::UnsupportedBuiltin(name) => write!(f, "builtin {name} is not supported"),
+            Self::UnsupportedHint { pc, code } => {
+                write!(f, "unsupported hint at pc {pc}: {code}")
+            }
+            Self::InvalidInstruction(pc) => write!(f, "invalid instruction at pc {pc}"),
+            Self::UnknownOperand { pc, operand } => {
+                write!(f, "couldn't deduce {operand} at pc {pc}")
+            }
+            Self::InvalidOperation(pc) => write!(f, "invalid operation at pc {pc}"),
+            Self::AssertionFailed(pc) => write!(f, "assertion failed at pc {pc}"),
+            Self::InconsistentMemory(address) => {
+                write!(f, "address {address} was assigned two different values")
+            }
+            Self::InvalidAddress(address) => write!(f, "invalid address {address}"),
+            Self::InvalidBuiltinValue(address) => {
+                write!(f, "invalid builtin value at address {address}")
+            }
+            Self::InvalidStopPointer(name) => write!(f, "invalid stop pointer for {name}"),
+            Self::StepLimitExceeded(max_steps) => {
+                write!(f, "execution exceeded the limit of {max_steps} steps")
+            }
+            Self::AddressOutOfRange(address) => write!(
+                f,
+                "relocated address {address} exceeds the maximum address {MAX_ADDRESS}"
+            ),
+        }
+    }
+}
+
+impl std::error::Error for VmError {}
@@ -1,4 +1,4 @@
-#![feature(buf_read_has_data_left, int_roundings)]
+#![feature(int_roundings)]
 
 extern crate alloc;
 
@@ -13,20 +13,30 @@ use ruint::aliases::U256;
 use ruint::uint;
 use serde::Deserialize;
 use serde::Serialize;
+use std::collections::BTreeMap;
 use std::fmt::Display;
-use std::io::BufRead;
 use std::io::BufReader;
+use std::io::BufWriter;
 use std::io::Read;
+use std::io::Write;
 use std::marker::PhantomData;
 use std::ops::Deref;
 use std::path::PathBuf;
-use utils::deserialize_hex_str;
-use utils::deserialize_hex_str_memory_entries;
+use utils::deserialize_hex_str_public_memory;
 use utils::deserialize_vec_hex_str;
 use utils::field_bytes;
+use utils::hex_str;
+use utils::read_until_full;
+use utils::serialize_hex_str_public_memory;
+use utils::write_json;
 
 mod errors;
 mod utils;
+pub mod vm;
+
+pub use errors::Error;
+pub use errors::InvalidFieldElementError;
+pub use errors::VmError;
 
 // https://eprint.iacr.org/2021/1063.pdf figure 3
 /// Word offset of `off_DST`
@@ -47,6 +57,10 @@ pub const OFF_MASK: usize = 0xFFFF;
 pub const _OFFSET: usize = 2usize.pow(16);
 pub const HALF_OFFSET: usize = 2usize.pow(15);
 
+/// Largest memory address supported by the prover. Addresses are stored as
+/// `u32` in [MemoryEntry].
+pub const MAX_ADDRESS: u64 = u32::MAX as u64;
+
 /// Holds register values
 #[derive(Clone, Copy, Debug, PartialEq, Serialize, Deserialize)]
 pub struct RegisterState {
@@ -67,6 +81,7 @@ pub enum Layout {
     RecursiveLargeOutput = 5,
     AllSolidity = 6,
     StarknetWithKeccak = 7,
+    Dynamic = 8,
 }
 
 impl Display for Layout {
@@ -83,29 +98,116 @@ impl Display for Layout {
                 Self::RecursiveLargeOutput => "recursive_large_output",
                 Self::AllSolidity => "all_solidity",
                 Self::StarknetWithKeccak => "starknet_with_keccak",
+                Self::Dynamic => "dynamic",
             }
         )
     }
 }
 
 impl Layout {
-    const SHARP_CODE_STARKNET: u128 = 8319381555716711796;
-    const SHARP_CODE_RECURSIVE: u128 = 2110234636557836973669;
-
-    // Returns the unique code used by SHARP associated to this layout
-    pub const fn sharp_code(&self) -> u128 {
+    const SHARP_CODE_SMALL: U256 = uint!(495756340332_U256);
+    const SHARP_CODE_DEX: U256 = uint!(6579576_U256);
+    const SHARP_CODE_STARKNET: U256 = uint!(8319381555716711796_U256);
+    const SHARP_CODE_RECURSIVE: U256 = uint!(2110234636557836973669_U256);
+    const SHARP_CODE_STARKNET_WITH_KECCAK: U256 =
+        uint!(659129313914496913248946332623959439379884368235_U256);
+    const SHARP_CODE_RECURSIVE_LARGE_OUTPUT: U256 =
+        uint!(42800643258479064999893963318903811951182475189843316_U256);
+    const SHARP_CODE_ALL_SOLIDITY: U256 = uint!(30151121717527674777951106169_U256);
+    const SHARP_CODE_DYNAMIC: U256 = uint!(28281012658989411_U256);
+
+    // Returns the unique code used by SHARP associated to this layout. The code
+    // is the layout name interpreted as a big-endian ASCII string. `None` for
+    // layouts SHARP doesn't support.
+    pub const fn sharp_code(&self) -> Option<U256> {
         match self {
-            Self::Starknet => Self::SHARP_CODE_STARKNET,
-            Self::Recursive => Self::SHARP_CODE_RECURSIVE,
-            _ => unimplemented!(),
+            Self::Small => Some(Self::SHARP_CODE_SMALL),
+            Self::Dex => Some(Self::SHARP_CODE_DEX),
+            Self::Starknet => Some(Self::SHARP_CODE_STARKNET),
+            Self::Recursive => Some(Self::SHARP_CODE_RECURSIVE),
+            Self::StarknetWithKeccak => Some(Self::SHARP_CODE_STARKNET_WITH_KECCAK),
+            Self::RecursiveLargeOutput => Some(Self::SHARP_CODE_RECURSIVE_LARGE_OUTPUT),
+            Self::AllSolidity => Some(Self::SHARP_CODE_ALL_SOLIDITY),
+            Self::Dynamic => Some(Self::SHARP_CODE_DYNAMIC),
+            _ => None,
         }
     }
 
-    pub const fn from_sharp_code(code: u128) -> Self {
+    pub fn from_sharp_code(code: U256) -> Option<Self> {
         match code {
-            Self::SHARP_CODE_STARKNET => Self::Starknet,
-            Self::SHARP_CODE_RECURSIVE => Self::Recursive,
-            _ => unimplemented!(),
+            Self::SHARP_CODE_SMALL => Some(Self::Small),
+            Self::SHARP_CODE_DEX => Some(Self::Dex),
+            Self::SHARP_CODE_STARKNET => Some(Self::Starknet),
+            Self::SHARP_CODE_RECURSIVE => Some(Self::Recursive),
+            Self::SHARP_CODE_STARKNET_WITH_KECCAK => Some(Self::StarknetWithKeccak),
+            Self::SHARP_CODE_RECURSIVE_LARGE_OUTPUT => Some(Self::RecursiveLargeOutput),
+            Self::SHARP_CODE_ALL_SOLIDITY => Some(Self::AllSolidity),
+            Self::SHARP_CODE_DYNAMIC => Some(Self::Dynamic),
+            _ => None,
+        }
+    }
+
+    /// Layouts that have a SHARP code and a public input encoding. Only these
+    /// layouts can be serialized. The `dynamic` layout has a SHARP code but its
+    /// parameters aren't part of the public input yet.
+    const SHARP_LAYOUTS: [Self; 7] = [
+        Self::Small,
+        Self::Dex,
+        Self::Starknet,
+        Self::Recursive,
+        Self::StarknetWithKeccak,
+        Self::RecursiveLargeOutput,
+        Self::AllSolidity,
+    ];
+
+    /// 16 byte code used to serialize the layout. This is the low 128 bits of
+    /// the SHARP code (i.e. the last 16 characters of the layout name) so it
+    /// matches the SHARP code for layouts with names of up to 16 characters.
+    fn serialization_code(&self) -> Option<u128> {
+        if !Self::SHARP_LAYOUTS.contains(self) {
+            return None;
+        }
+        let bytes = self.sharp_code()?.to_be_bytes::<32>();
+        Some(u128::from_be_bytes(bytes[16..].try_into().unwrap()))
+    }
+
+    /// Names of the builtins included in the layout (in the order their memory
+    /// segments are allocated)
+    pub const fn builtins(&self) -> &'static [&'static str] {
+        match self {
+            Self::Plain => &[],
+            Self::Small | Self::Dex => &["output", "pedersen", "range_check", "ecdsa"],
+            Self::Recursive => &["output", "pedersen", "range_check", "bitwise"],
+            Self::RecursiveLargeOutput => {
+                &["output", "pedersen", "range_check", "bitwise", "poseidon"]
+            }
+            Self::Starknet => &[
+                "output",
+                "pedersen",
+                "range_check",
+                "ecdsa",
+                "bitwise",
+                "ec_op",
+                "poseidon",
+            ],
+            Self::AllSolidity => &[
+                "output",
+                "pedersen",
+                "range_check",
+                "ecdsa",
+                "bitwise",
+                "ec_op",
+            ],
+            Self::StarknetWithKeccak | Self::Dynamic => &[
+                "output",
+                "pedersen",
+                "range_check",
+                "ecdsa",
+                "bitwise",
+                "ec_op",
+                "keccak",
+                "poseidon",
+            ],
         }
     }
 }
@@ -116,9 +218,10 @@ impl CanonicalSerialize for Layout {
         writer: W,
         compress: ark_serialize::Compress,
     ) -> Result<(), ark_serialize::SerializationError> {
-        self.sharp_code()
-            .to_be_bytes()
-            .serialize_with_mode(writer, compress)
+        let code = self
+            .serialization_code()
+            .ok_or(ark_serialize::SerializationError::InvalidData)?;
+        code.to_be_bytes().serialize_with_mode(writer, compress)
     }
 
     fn serialized_size(&self, _compress: ark_serialize::Compress) -> usize {
@@ -138,9 +241,13 @@ impl CanonicalDeserialize for Layout {
         compress: ark_serialize::Compress,
         validate: ark_serialize::Validate,
     ) -> Result<Self, ark_serialize::SerializationError> {
-        Ok(Self::from_sharp_code(u128::from_be_bytes(
-            <[u8; 16]>::deserialize_with_mode(reader, compress, validate)?,
-        )))
+        let code = u128::from_be_bytes(<[u8; 16]>::deserialize_with_mode(
+            reader, compress, validate,
+        )?);
+        Self::SHARP_LAYOUTS
+            .into_iter()
+            .find(|layout| layout.serialization_code() == Some(code))
+            .ok_or(ark_serialize::SerializationError::InvalidData)
     }
 }
 
@@ -148,16 +255,49 @@ impl CanonicalDeserialize for Layout {
 pub struct RegisterStates(Vec<RegisterState>);
 
 impl RegisterStates {
+    /// Number of bytes used to store a single register state in `trace.bin`
+    const RECORD_BYTES: usize = 3 * core::mem::size_of::<u64>();
+
     /// Parses trace data in the format outputted by a `cairo-run`.
-    pub fn from_reader(r: impl Read) -> Self {
-        // TODO: errors
+    /// Each record is of the form `(ap, fp, pc)` stored as little-endian u64s.
+    pub fn from_reader(r: impl Read) -> Result<Self, Error> {
         let mut reader = BufReader::new(r);
         let mut register_states = Vec::new();
-        while reader.has_data_left().unwrap() {
-            let entry: RegisterState = bincode::deserialize_from(&mut reader).unwrap();
-            register_states.push(entry);
+        let mut record = [0; Self::RECORD_BYTES];
+        let mut offset = 0;
+        loop {
+            match read_until_full(&mut reader, &mut record)? {
+                0 => break,
+                Self::RECORD_BYTES => {}
+                actual_bytes => {
+                    return Err(Error::TruncatedRecord {
+                        offset,
+                        expected_bytes: Self::RECORD_BYTES,
+                        actual_bytes,
+                    })
+                }
+            }
+
+            let [ap, fp, pc] = [0, 1, 2].map(|i| {
+                let bytes = record[i * 8..(i + 1) * 8].try_into().unwrap();
+                u64::from_le_bytes(bytes) as usize
+            });
+            register_states.push(RegisterState { ap, fp, pc });
+            offset += Self::RECORD_BYTES;
+        }
+        Ok(RegisterStates(register_states))
+    }
+
+    /// Writes trace data in the format outputted by `cairo-run`. Inverse of
+    /// [RegisterStates::from_reader].
+    pub fn to_writer(&self, writer: impl Write) -> Result<(), Error> {
+        let mut writer = BufWriter::new(writer);
+        for &RegisterState { ap, fp, pc } in &self.0 {
+            for register in [ap, fp, pc] {
+                writer.write_all(&(register as u64).to_le_bytes())?;
+            }
         }
-        RegisterStates(register_states)
+        Ok(writer.flush()?)
     }
 }
 
@@ -169,16 +309,29 @@ impl Deref for RegisterStates {
     }
 }
 
+/// Cairo memory. Words are stored densely by address so lookups during trace
+/// generation are a single index. Addresses far past the assigned cells are
+/// kept in a sparse map so an outlying address doesn't allocate every address
+/// below it.
 #[derive(Debug)]
-pub struct Memory<F>(Vec<Option<Word<F>>>);
+pub struct Memory<F> {
+    dense: Vec<Option<Word<F>>>,
+    /// Assigned addresses at or above `dense.len()`
+    sparse: BTreeMap<usize, Word<F>>,
+    len: usize,
+}
 
 impl<F: Field> Memory<F> {
+    /// Number of addresses that are always stored densely
+    const MIN_DENSE_SIZE: usize = 1 << 16;
+
     /// Parses the partial memory data outputted by a `cairo-run`.
-    pub fn from_reader(r: impl Read) -> Self
+    /// Each record is of the form `(address, value)` where the address is a
+    /// little-endian u64 and the value is a little-endian field element.
+    pub fn from_reader(r: impl Read) -> Result<Self, Error>
     where
         F: PrimeField,
     {
-        // TODO: errors
         // TODO: each builtin has its own memory segment.
         // check it also contains other builtins
         // this file contains the contiguous memory segments:
@@ -188,35 +341,146 @@ impl<F: Field> Memory<F> {
         // - builtin 1
         // - ...
         let mut reader = BufReader::new(r);
-        let mut partial_memory = Vec::new();
-        let mut max_address = 0;
-        let mut word_bytes = Vec::new();
-        word_bytes.resize(field_bytes::<F>(), 0);
-        while reader.has_data_left().unwrap() {
-            // TODO: ensure always deserializes u64 and both are always little-endian
-            let address = bincode::deserialize_from(&mut reader).unwrap();
-            reader.read_exact(&mut word_bytes).unwrap();
-            let word = U256::try_from_le_slice(&word_bytes).unwrap();
-            partial_memory.push((address, Word::new(word)));
-            max_address = std::cmp::max(max_address, address);
+        let modulus = U256::from::<BigUint>(F::MODULUS.into());
+        let word_size = field_bytes::<F>();
+        let record_size = core::mem::size_of::<u64>() + word_size;
+        let mut address_bytes = [0; core::mem::size_of::<u64>()];
+        let mut word_bytes = vec![0; word_size];
+        let mut memory = Memory::default();
+        let mut offset = 0;
+        loop {
+            match read_until_full(&mut reader, &mut address_bytes)? {
+                0 => break,
+                8 => {}
+                actual_bytes => {
+                    return Err(Error::TruncatedRecord {
+                        offset,
+                        expected_bytes: address_bytes.len(),
+                        actual_bytes,
+                    })
+                }
+            }
+
+            let actual_bytes = read_until_full(&mut reader, &mut word_bytes)?;
+            if actual_bytes != word_size {
+                return Err(Error::MisalignedWord {
+                    offset,
+                    word_bytes: word_size,
+                    actual_bytes,
+                });
+            }
+
+            let address = u64::from_le_bytes(address_bytes);
+            if address > MAX_ADDRESS {
+                return Err(Error::AddressOutOfRange {
+                    offset,
+                    address,
+                    max_address: MAX_ADDRESS,
+                });
+            }
+
+            let value = U256::try_from_le_slice(&word_bytes).unwrap();
+            if value >= modulus {
+                let error = InvalidFieldElementError { value, modulus };
+                return Err(Error::InvalidFieldElement { offset, error });
+            }
+
+            match memory.get(address as usize) {
+                Some(Word(existing, _)) if existing != value => {
+                    return Err(Error::ConflictingAddress {
+                        offset,
+                        address,
+                        existing,
+                        value,
+                    })
+                }
+                Some(_) => {}
+                None => memory.insert(address as usize, Word::new(value)),
+            }
+
+            offset += record_size;
+        }
+
+        Ok(memory)
+    }
+
+    /// Writes memory data in the format outputted by `cairo-run`. Inverse of
+    /// [Memory::from_reader]. Records are written in address order.
+    pub fn to_writer(&self, writer: impl Write) -> Result<(), Error>
+    where
+        F: PrimeField,
+    {
+        let mut writer = BufWriter::new(writer);
+        let word_size = field_bytes::<F>();
+        for (address, Word(value, _)) in self.iter() {
+            writer.write_all(&(address as u64).to_le_bytes())?;
+            writer.write_all(&value.to_le_bytes::<32>()[0..word_size])?;
         }
+        Ok(writer.flush()?)
+    }
 
-        // TODO: DOC: None used for nondeterministic values?
-        let mut memory = vec![None; max_address + 1];
-        for (address, word) in partial_memory {
-            // TODO: once arkworks v4 release remove num_bigint
-            memory[address] = Some(word);
+    /// Returns the word at `address` or `None` if the address was never
+    /// assigned
+    pub fn get(&self, address: usize) -> Option<Word<F>> {
+        match self.dense.get(address) {
+            Some(word) => *word,
+            None => self.sparse.get(&address).copied(),
         }
+    }
 
-        Memory(memory)
+    /// Iterates over all assigned `(address, word)` pairs in address order
+    pub fn iter(&self) -> impl Iterator<Item = (usize, Word<F>)> + '_ {
+        let dense = self.dense.iter().enumerate();
+        let dense = dense.filter_map(|(address, word)| Some((address, (*word)?)));
+        dense.chain(self.sparse.iter().map(|(&address, &word)| (address, word)))
     }
-}
 
-impl<F: Field> Deref for Memory<F> {
-    type Target = Vec<Option<Word<F>>>;
+    /// Returns the largest assigned address
+    pub fn max_address(&self) -> Option<usize> {
+        match self.sparse.last_key_value() {
+            Some((&address, _)) => Some(address),
+            None => self.dense.iter().rposition(Option::is_some),
+        }
+    }
 
-    fn deref(&self) -> &Self::Target {
-        &self.0
+    /// Number of assigned memory cells
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// Assigns `word` to an unassigned `address`. The dense storage is grown to
+    /// cover the address as long as it stays at least half full.
+    fn insert(&mut self, address: usize, word: Word<F>) {
+        debug_assert!(self.get(address).is_none());
+        self.len += 1;
+        if address >= self.dense.len() && address < 2 * self.len + Self::MIN_DENSE_SIZE {
+            self.dense.resize(address + 1, None);
+            // move sparse words now covered by the dense storage
+            let sparse = self.sparse.split_off(&self.dense.len());
+            for (address, word) in std::mem::replace(&mut self.sparse, sparse) {
+                self.dense[address] = Some(word);
+            }
+        }
+        match self.dense.get_mut(address) {
+            Some(cell) => *cell = Some(word),
+            None => {
+                self.sparse.insert(address, word);
+            }
+        }
+    }
+}
+
+impl<F> Default for Memory<F> {
+    fn default() -> Self {
+        Self {
+            dense: Vec::new(),
+            sparse: BTreeMap::new(),
+            len: 0,
+        }
     }
 }
 
@@ -292,29 +556,223 @@ pub struct Segment {
     pub stop_ptr: u32,
 }
 
-#[derive(Deserialize, Clone, Copy, Debug, CanonicalDeserialize, CanonicalSerialize)]
+#[derive(
+    Serialize,
+    Deserialize,
+    Clone,
+    Copy,
+    Debug,
+    PartialEq,
+    Eq,
+    CanonicalDeserialize,
+    CanonicalSerialize,
+)]
 pub struct MemorySegments {
     pub program: Segment,
     pub execution: Segment,
+    #[serde(skip_serializing_if = "Option::is_none")]
     pub output: Option<Segment>,
+    #[serde(skip_serializing_if = "Option::is_none")]
     pub pedersen: Option<Segment>,
+    #[serde(skip_serializing_if = "Option::is_none")]
     pub range_check: Option<Segment>,
+    #[serde(skip_serializing_if = "Option::is_none")]
     pub ecdsa: Option<Segment>,
+    #[serde(skip_serializing_if = "Option::is_none")]
     pub bitwise: Option<Segment>,
+    #[serde(skip_serializing_if = "Option::is_none")]
     pub ec_op: Option<Segment>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub keccak: Option<Segment>,
+    #[serde(skip_serializing_if = "Option::is_none")]
     pub poseidon: Option<Segment>,
 }
 
-#[derive(Deserialize, Clone, Debug, CanonicalDeserialize, CanonicalSerialize)]
-#[serde(bound = "F: PrimeField")]
+/// Builtin ratios used by the `dynamic` layout. Ratios are the number of
+/// cycles per builtin instance and are `None` for builtins that aren't used.
+#[derive(
+    Serialize,
+    Deserialize,
+    Clone,
+    Copy,
+    Debug,
+    Default,
+    PartialEq,
+    Eq,
+    CanonicalSerialize,
+    CanonicalDeserialize,
+)]
+pub struct DynamicParams {
+    pub pedersen_ratio: Option<u32>,
+    pub range_check_ratio: Option<u32>,
+    pub ecdsa_ratio: Option<u32>,
+    pub bitwise_ratio: Option<u32>,
+    pub ec_op_ratio: Option<u32>,
+    pub keccak_ratio: Option<u32>,
+    pub poseidon_ratio: Option<u32>,
+}
+
+/// Page of the public memory. Page `0` is the main page and holds arbitrary
+/// (address, value) pairs. All other pages are continuous and hold the values
+/// at addresses `start_address..start_address + size`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, CanonicalSerialize, CanonicalDeserialize)]
+pub struct MemoryPage {
+    pub id: u32,
+    /// Not used by the main page
+    pub start_address: u32,
+    pub size: u32,
+}
+
+impl MemoryPage {
+    pub const fn is_main(&self) -> bool {
+        self.id == 0
+    }
+
+    pub fn contains(&self, address: u32) -> bool {
+        // pages can end at the top of the address space
+        let end = self.start_address.checked_add(self.size);
+        !self.is_main() && address >= self.start_address && end.map_or(true, |end| address < end)
+    }
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, CanonicalDeserialize, CanonicalSerialize)]
+#[serde(
+    bound = "F: PrimeField",
+    try_from = "AirPublicInputJson<F>",
+    into = "AirPublicInputJson<F>"
+)]
 pub struct AirPublicInput<F: Field> {
     pub rc_min: u16,
     pub rc_max: u16,
     pub n_steps: u64,
     pub layout: Layout,
     pub memory_segments: MemorySegments,
-    #[serde(deserialize_with = "deserialize_hex_str_memory_entries")]
+    /// Entries of all public memory pages
     pub public_memory: Vec<MemoryEntry<F>>,
+    /// Public memory pages ordered by page id (the main page is first)
+    pub public_memory_pages: Vec<MemoryPage>,
+    /// Only present for the `dynamic` layout
+    pub dynamic_params: Option<DynamicParams>,
+}
+
+/// Format of the AIR public input outputted by `cairo-run`
+#[derive(Serialize, Deserialize)]
+#[serde(bound = "F: PrimeField")]
+struct AirPublicInputJson<F: Field> {
+    layout: Layout,
+    rc_min: u16,
+    rc_max: u16,
+    n_steps: u64,
+    memory_segments: MemorySegments,
+    #[serde(
+        deserialize_with = "deserialize_hex_str_public_memory",
+        serialize_with = "serialize_hex_str_public_memory"
+    )]
+    public_memory: Vec<(MemoryEntry<F>, u32)>,
+    #[serde(default)]
+    dynamic_params: Option<DynamicParams>,
+}
+
+impl<F: Field> From<AirPublicInput<F>> for AirPublicInputJson<F> {
+    fn from(public_input: AirPublicInput<F>) -> Self {
+        let pages = &public_input.public_memory_pages;
+        let public_memory = public_input
+            .public_memory
+            .iter()
+            .map(|&entry| {
+                let page = pages.iter().find(|p| p.contains(entry.address));
+                (entry, page.map_or(0, |p| p.id))
+            })
+            .collect();
+        AirPublicInputJson {
+            layout: public_input.layout,
+            rc_min: public_input.rc_min,
+            rc_max: public_input.rc_max,
+            n_steps: public_input.n_steps,
+            memory_segments: public_input.memory_segments,
+            public_memory,
+            dynamic_params: public_input.dynamic_params,
+        }
+    }
+}
+
+impl<F: Field> TryFrom<AirPublicInputJson<F>> for AirPublicInput<F> {
+    type Error = String;
+
+    fn try_from(json: AirPublicInputJson<F>) -> Result<Self, Self::Error> {
+        // group the addresses of each page
+        let mut page_addresses = Vec::<Vec<u32>>::new();
+        for &(entry, page) in &json.public_memory {
+            let page = page as usize;
+            if page >= page_addresses.len() {
+                page_addresses.resize(page + 1, Vec::new());
+            }
+            page_addresses[page].push(entry.address);
+        }
+
+        let mut public_memory_pages = Vec::new();
+        for (id, mut addresses) in page_addresses.into_iter().enumerate() {
+            let id = id as u32;
+            let size = addresses.len() as u32;
+            if id == 0 {
+                public_memory_pages.push(MemoryPage {
+                    id,
+                    start_address: 0,
+                    size,
+                });
+                continue;
+            }
+
+            addresses.sort_unstable();
+            let start_address = *addresses
+                .first()
+                .ok_or_else(|| format!("public memory page {id} is empty"))?;
+            if addresses
+                .iter()
+                .zip(start_address..)
+                .any(|(&a, expected)| a != expected)
+            {
+                return Err(format!("public memory page {id} is not continuous"));
+            }
+            public_memory_pages.push(MemoryPage {
+                id,
+                start_address,
+                size,
+            });
+        }
+
+        // the main page can't contain addresses from the continuous pages
+        for &(entry, page) in &json.public_memory {
+            if page == 0
+                && public_memory_pages
+                    .iter()
+                    .any(|p| p.contains(entry.address))
+            {
+                return Err(format!(
+                    "address {} is in the main page and a continuous page",
+                    entry.address
+                ));
+            }
+        }
+
+        Ok(AirPublicInput {
+            rc_min: json.rc_min,
+            rc_max: json.rc_max,
+            n_steps: json.n_steps,
+            layout: json.layout,
+            memory_segments: json.memory_segments,
+            public_memory: json.public_memory.into_iter().map(|(e, _)| e).collect(),
+            public_memory_pages,
+            dynamic_params: json.dynamic_params,
+        })
+    }
+}
+
+impl<F: PrimeField> AirPublicInput<F> {
+    /// Writes the public input in the format outputted by `cairo-run`
+    pub fn to_writer(&self, writer: impl Write) -> Result<(), Error> {
+        Ok(write_json(writer, self)?)
+    }
 }
 
 impl<F: Field> AirPublicInput<F> {
@@ -337,22 +795,63 @@ impl<F: Field> AirPublicInput<F> {
     pub fn public_memory_padding(&self) -> MemoryEntry<F> {
         *self.public_memory.iter().find(|e| e.address == 1).unwrap()
     }
+
+    /// Returns the public memory entries of the output segment ordered by
+    /// address
+    pub fn output_entries(&self) -> Vec<MemoryEntry<F>> {
+        let Some(output) = self.memory_segments.output else {
+            return Vec::new();
+        };
+        let mut entries = self
+            .public_memory
+            .iter()
+            .filter(|e| (output.begin_addr..output.stop_ptr).contains(&e.address))
+            .copied()
+            .collect::<Vec<MemoryEntry<F>>>();
+        entries.sort_unstable_by_key(|e| e.address);
+        entries.dedup_by_key(|e| e.address);
+        entries
+    }
+
+    /// Returns the entries of a public memory page. Entries of continuous pages
+    /// are ordered by address.
+    pub fn page_entries(&self, page: &MemoryPage) -> Vec<MemoryEntry<F>> {
+        if page.is_main() {
+            let pages = &self.public_memory_pages;
+            let in_continuous_page =
+                |e: &MemoryEntry<F>| pages.iter().any(|p| p.contains(e.address));
+            self.public_memory
+                .iter()
+                .filter(|e| !in_continuous_page(e))
+                .copied()
+                .collect()
+        } else {
+            let mut entries = self
+                .public_memory
+                .iter()
+                .filter(|e| page.contains(e.address))
+                .copied()
+                .collect::<Vec<MemoryEntry<F>>>();
+            entries.sort_unstable_by_key(|e| e.address);
+            entries
+        }
+    }
 }
 
-#[derive(Deserialize, Clone, Copy, Debug)]
+#[derive(Serialize, Deserialize, Clone, Copy, Debug)]
 pub struct Signature {
-    #[serde(deserialize_with = "deserialize_hex_str")]
+    #[serde(with = "hex_str")]
     pub r: U256,
-    #[serde(deserialize_with = "deserialize_hex_str")]
+    #[serde(with = "hex_str")]
     pub w: U256,
 }
 
-#[derive(Deserialize, Clone, Copy, Debug)]
+#[derive(Serialize, Deserialize, Clone, Copy, Debug)]
 pub struct EcdsaInstance {
     pub index: u32,
-    #[serde(rename = "pubkey", deserialize_with = "deserialize_hex_str")]
+    #[serde(rename = "pubkey", with = "hex_str")]
     pub pubkey_x: U256,
-    #[serde(rename = "msg", deserialize_with = "deserialize_hex_str")]
+    #[serde(rename = "msg", with = "hex_str")]
     pub message: U256,
     #[serde(rename = "signature_input")]
     pub signature: Signature,
@@ -367,12 +866,12 @@ impl EcdsaInstance {
     }
 }
 
-#[derive(Deserialize, Clone, Copy, Debug)]
+#[derive(Serialize, Deserialize, Clone, Copy, Debug)]
 pub struct PedersenInstance {
     pub index: u32,
-    #[serde(rename = "x", deserialize_with = "deserialize_hex_str")]
+    #[serde(rename = "x", with = "hex_str")]
     pub a: U256,
-    #[serde(rename = "y", deserialize_with = "deserialize_hex_str")]
+    #[serde(rename = "y", with = "hex_str")]
     pub b: U256,
 }
 
@@ -393,10 +892,10 @@ impl PedersenInstance {
     }
 }
 
-#[derive(Deserialize, Clone, Copy, Debug)]
+#[derive(Serialize, Deserialize, Clone, Copy, Debug)]
 pub struct RangeCheckInstance {
     pub index: u32,
-    #[serde(deserialize_with = "deserialize_hex_str")]
+    #[serde(with = "hex_str")]
     pub value: U256,
 }
 
@@ -414,12 +913,12 @@ impl RangeCheckInstance {
     }
 }
 
-#[derive(Deserialize, Clone, Copy, Debug)]
+#[derive(Serialize, Deserialize, Clone, Copy, Debug)]
 pub struct BitwiseInstance {
     pub index: u32,
-    #[serde(deserialize_with = "deserialize_hex_str")]
+    #[serde(with = "hex_str")]
     pub x: U256,
-    #[serde(deserialize_with = "deserialize_hex_str")]
+    #[serde(with = "hex_str")]
     pub y: U256,
 }
 
@@ -448,18 +947,18 @@ impl BitwiseInstance {
 }
 
 /// Elliptic Curve operation instance for `p + m * q` on an elliptic curve
-#[derive(Deserialize, Clone, Copy, Debug)]
+#[derive(Serialize, Deserialize, Clone, Copy, Debug)]
 pub struct EcOpInstance {
     pub index: u32,
-    #[serde(deserialize_with = "deserialize_hex_str")]
+    #[serde(with = "hex_str")]
     pub p_x: U256,
-    #[serde(deserialize_with = "deserialize_hex_str")]
+    #[serde(with = "hex_str")]
     pub p_y: U256,
-    #[serde(deserialize_with = "deserialize_hex_str")]
+    #[serde(with = "hex_str")]
     pub q_x: U256,
-    #[serde(deserialize_with = "deserialize_hex_str")]
+    #[serde(with = "hex_str")]
     pub q_y: U256,
-    #[serde(deserialize_with = "deserialize_hex_str")]
+    #[serde(with = "hex_str")]
     pub m: U256,
 }
 
@@ -481,14 +980,14 @@ impl EcOpInstance {
     }
 }
 
-#[derive(Deserialize, Clone, Copy, Debug)]
+#[derive(Serialize, Deserialize, Clone, Copy, Debug)]
 pub struct PoseidonInstance {
     pub index: u32,
-    #[serde(rename = "input_s0", deserialize_with = "deserialize_hex_str")]
+    #[serde(rename = "input_s0", with = "hex_str")]
     pub input0: U256,
-    #[serde(rename = "input_s1", deserialize_with = "deserialize_hex_str")]
+    #[serde(rename = "input_s1", with = "hex_str")]
     pub input1: U256,
-    #[serde(rename = "input_s2", deserialize_with = "deserialize_hex_str")]
+    #[serde(rename = "input_s2", with = "hex_str")]
     pub input2: U256,
 }
 
@@ -518,11 +1017,79 @@ impl PoseidonInstance {
     }
 }
 
-#[derive(Debug, Deserialize)]
+/// Keccak-f[1600] permutation instance. The 1600 bit state is split into 8
+/// little-endian field elements of 200 bits each.
+#[derive(Serialize, Deserialize, Clone, Copy, Debug)]
+pub struct KeccakInstance {
+    pub index: u32,
+    #[serde(rename = "input_s0", with = "hex_str")]
+    pub input0: U256,
+    #[serde(rename = "input_s1", with = "hex_str")]
+    pub input1: U256,
+    #[serde(rename = "input_s2", with = "hex_str")]
+    pub input2: U256,
+    #[serde(rename = "input_s3", with = "hex_str")]
+    pub input3: U256,
+    #[serde(rename = "input_s4", with = "hex_str")]
+    pub input4: U256,
+    #[serde(rename = "input_s5", with = "hex_str")]
+    pub input5: U256,
+    #[serde(rename = "input_s6", with = "hex_str")]
+    pub input6: U256,
+    #[serde(rename = "input_s7", with = "hex_str")]
+    pub input7: U256,
+}
+
+impl KeccakInstance {
+    /// Number of memory cells used by a single instance (8 inputs and 8
+    /// outputs)
+    pub const CELLS_PER_INSTANCE: u32 = 16;
+
+    pub fn new_empty(index: u32) -> Self {
+        Self {
+            index,
+            input0: U256::ZERO,
+            input1: U256::ZERO,
+            input2: U256::ZERO,
+            input3: U256::ZERO,
+            input4: U256::ZERO,
+            input5: U256::ZERO,
+            input6: U256::ZERO,
+            input7: U256::ZERO,
+        }
+    }
+
+    pub fn inputs(&self) -> [U256; 8] {
+        [
+            self.input0,
+            self.input1,
+            self.input2,
+            self.input3,
+            self.input4,
+            self.input5,
+            self.input6,
+            self.input7,
+        ]
+    }
+
+    /// Get the memory address for this instance
+    /// Output is of the form (input_addrs, output_addrs)
+    pub fn mem_addr(&self, keccak_segment_addr: u32) -> ([u32; 8], [u32; 8]) {
+        let instance_offset = keccak_segment_addr + self.index * Self::CELLS_PER_INSTANCE;
+        (
+            core::array::from_fn(|i| instance_offset + i as u32),
+            core::array::from_fn(|i| instance_offset + 8 + i as u32),
+        )
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize)]
 pub struct AirPrivateInput {
     pub trace_path: PathBuf,
     pub memory_path: PathBuf,
+    #[serde(default)]
     pub pedersen: Vec<PedersenInstance>,
+    #[serde(default)]
     pub range_check: Vec<RangeCheckInstance>,
     #[serde(default)]
     pub ecdsa: Vec<EcdsaInstance>,
@@ -531,15 +1098,87 @@ pub struct AirPrivateInput {
     #[serde(default)]
     pub ec_op: Vec<EcOpInstance>,
     #[serde(default)]
+    pub keccak: Vec<KeccakInstance>,
+    #[serde(default)]
     pub poseidon: Vec<PoseidonInstance>,
 }
 
+impl AirPrivateInput {
+    /// Writes the private input in the format outputted by `cairo-run`. Only
+    /// the builtins of `layout` are written.
+    pub fn to_writer(&self, writer: impl Write, layout: Layout) -> Result<(), Error> {
+        /// Format of the AIR private input outputted by `cairo-run`
+        #[derive(Serialize)]
+        struct AirPrivateInputJson<'a> {
+            trace_path: &'a PathBuf,
+            memory_path: &'a PathBuf,
+            #[serde(skip_serializing_if = "Option::is_none")]
+            pedersen: Option<&'a [PedersenInstance]>,
+            #[serde(skip_serializing_if = "Option::is_none")]
+            range_check: Option<&'a [RangeCheckInstance]>,
+            #[serde(skip_serializing_if = "Option::is_none")]
+            ecdsa: Option<&'a [EcdsaInstance]>,
+            #[serde(skip_serializing_if = "Option::is_none")]
+            bitwise: Option<&'a [BitwiseInstance]>,
+            #[serde(skip_serializing_if = "Option::is_none")]
+            ec_op: Option<&'a [EcOpInstance]>,
+            #[serde(skip_serializing_if = "Option::is_none")]
+            keccak: Option<&'a [KeccakInstance]>,
+            #[serde(skip_serializing_if = "Option::is_none")]
+            poseidon: Option<&'a [PoseidonInstance]>,
+        }
+
+        let builtins = layout.builtins();
+        let include = |name: &str| builtins.contains(&name);
+        let json = AirPrivateInputJson {
+            trace_path: &self.trace_path,
+            memory_path: &self.memory_path,
+            pedersen: include("pedersen").then_some(&self.pedersen[..]),
+            range_check: include("range_check").then_some(&self.range_check[..]),
+            ecdsa: include("ecdsa").then_some(&self.ecdsa[..]),
+            bitwise: include("bitwise").then_some(&self.bitwise[..]),
+            ec_op: include("ec_op").then_some(&self.ec_op[..]),
+            keccak: include("keccak").then_some(&self.keccak[..]),
+            poseidon: include("poseidon").then_some(&self.poseidon[..]),
+        };
+        Ok(write_json(writer, &json)?)
+    }
+}
+
 #[derive(Clone, Deserialize, Debug)]
 #[serde(bound = "F: PrimeField")]
 pub struct CompiledProgram<F: Field> {
     #[serde(deserialize_with = "deserialize_vec_hex_str")]
     pub data: Vec<F>,
     pub prime: String,
+    /// Builtins used by the program (i.e. the `%builtins` directive)
+    #[serde(default)]
+    pub builtins: Vec<String>,
+    /// Hints keyed by the pc they run at
+    #[serde(default)]
+    pub hints: BTreeMap<usize, Vec<Hint>>,
+    #[serde(default)]
+    pub identifiers: BTreeMap<String, Identifier>,
+    #[serde(default = "default_main_scope")]
+    pub main_scope: String,
+}
+
+fn default_main_scope() -> String {
+    "__main__".to_string()
+}
+
+/// Python code executed by the prover before running an instruction
+#[derive(Clone, Deserialize, Debug)]
+pub struct Hint {
+    pub code: String,
+}
+
+/// Identifier of a compiled program. Only labels and functions have a pc.
+#[derive(Clone, Deserialize, Debug)]
+pub struct Identifier {
+    #[serde(rename = "type")]
+    pub kind: String,
+    pub pc: Option<usize>,
 }
 
 impl<F: Field> CompiledProgram<F> {
@@ -578,14 +1217,26 @@ impl<F> Word<F> {
     }
 
     pub fn get_op0_addr(&self, ap: usize, fp: usize) -> usize {
-        // TODO: put the if statement first good for rust quiz
-        self.get_off_op0() as usize + if self.get_flag(Flag::Op0Reg) { fp } else { ap }
-            - HALF_OFFSET
+        self.checked_op0_addr(ap, fp).unwrap()
     }
 
     pub fn get_dst_addr(&self, ap: usize, fp: usize) -> usize {
-        self.get_off_dst() as usize + if self.get_flag(Flag::DstReg) { fp } else { ap }
-            - HALF_OFFSET
+        self.checked_dst_addr(ap, fp).unwrap()
+    }
+
+    /// Like [`Self::get_op0_addr`] but returns `None` if the address is
+    /// negative
+    pub fn checked_op0_addr(&self, ap: usize, fp: usize) -> Option<usize> {
+        // TODO: put the if statement first good for rust quiz
+        (self.get_off_op0() as usize + if self.get_flag(Flag::Op0Reg) { fp } else { ap })
+            .checked_sub(HALF_OFFSET)
+    }
+
+    /// Like [`Self::get_dst_addr`] but returns `None` if the address is
+    /// negative
+    pub fn checked_dst_addr(&self, ap: usize, fp: usize) -> Option<usize> {
+        (self.get_off_dst() as usize + if self.get_flag(Flag::DstReg) { fp } else { ap })
+            .checked_sub(HALF_OFFSET)
     }
 
     pub fn get_flag(&self, flag: Flag) -> bool {
@@ -647,17 +1298,17 @@ impl<F: PrimeField> Word<F> {
     }
 
     pub fn get_op0(&self, ap: usize, fp: usize, mem: &Memory<F>) -> F {
-        mem[self.get_op0_addr(ap, fp)].unwrap().into_felt()
+        mem.get(self.get_op0_addr(ap, fp)).unwrap().into_felt()
     }
 
     pub fn get_dst(&self, ap: usize, fp: usize, mem: &Memory<F>) -> F {
-        mem[self.get_dst_addr(ap, fp)].unwrap().into_felt()
+        mem.get(self.get_dst_addr(ap, fp)).unwrap().into_felt()
     }
 
     pub fn get_op1_addr(&self, pc: usize, ap: usize, fp: usize, mem: &Memory<F>) -> usize {
         self.get_off_op1() as usize
             + match self.get_flag_group(FlagGroup::Op1Src) {
-                0 => usize::try_from(mem[self.get_op0_addr(ap, fp)].unwrap().0).unwrap(),
+                0 => usize::try_from(mem.get(self.get_op0_addr(ap, fp)).unwrap().0).unwrap(),
                 1 => pc,
                 2 => fp,
                 4 => ap,
@@ -667,7 +1318,9 @@ impl<F: PrimeField> Word<F> {
     }
 
     pub fn get_op1(&self, pc: usize, ap: usize, fp: usize, mem: &Memory<F>) -> F {
-        mem[self.get_op1_addr(pc, ap, fp, mem)].unwrap().into_felt()
+        mem.get(self.get_op1_addr(pc, ap, fp, mem))
+            .unwrap()
+            .into_felt()
     }
 
     pub fn get_res(&self, pc: usize, ap: usize, fp: usize, mem: &Memory<F>) -> F {
@@ -689,8 +1342,11 @@ impl<F: PrimeField> Word<F> {
                 }
             }
             0..=2 => {
-                let op0: F = mem[self.get_op0_addr(ap, fp)].unwrap().into_felt();
-                let op1: F = mem[self.get_op1_addr(pc, ap, fp, mem)].unwrap().into_felt();
+                let op0: F = mem.get(self.get_op0_addr(ap, fp)).unwrap().into_felt();
+                let op1: F = mem
+                    .get(self.get_op1_addr(pc, ap, fp, mem))
+                    .unwrap()
+                    .into_felt();
                 match res_logic {
                     0 => op1,
                     1 => op0 + op1,
@@ -770,3 +1426,325 @@ pub enum Flag {
     // 0 - padding to make flag cells a power-of-2
     Zero = 15,
 }
+
+#[cfg(test)]
+mod tests {
+    use crate::AirPrivateInput;
+    use crate::AirPublicInput;
+    use crate::Error;
+    use crate::Layout;
+    use crate::Memory;
+    use crate::MemoryPage;
+    use crate::RegisterStates;
+    use crate::MAX_ADDRESS;
+    use ark_serialize::CanonicalDeserialize;
+    use ark_serialize::CanonicalSerialize;
+    use ministark_gpu::fields::p3618502788666131213697322783095070105623107215331596699973092056135872020481::ark::Fp;
+    use ruint::aliases::U256;
+
+    const TRACE: &[u8] = include_bytes!("../../example/trace.bin");
+    const MEMORY: &[u8] = include_bytes!("../../example/memory.bin");
+    const AIR_PUBLIC_INPUT: &str = include_str!("../../example/air-public-input.json");
+    const AIR_PRIVATE_INPUT: &str = include_str!("../../example/air-private-input.json");
+
+    fn memory_record(address: u64, value: U256) -> Vec<u8> {
+        [&address.to_le_bytes()[..], &value.to_le_bytes::<32>()].concat()
+    }
+
+    #[test]
+    fn truncated_trace_reports_offset() {
+        let bytes = [[1u8; 24].as_slice(), &[0; 10]].concat();
+
+        let err = RegisterStates::from_reader(&*bytes).unwrap_err();
+
+        assert!(matches!(
+            err,
+            Error::TruncatedRecord {
+                offset: 24,
+                expected_bytes: 24,
+                actual_bytes: 10
+            }
+        ));
+    }
+
+    #[test]
+    fn truncated_memory_address_reports_address_size() {
+        let bytes = [memory_record(1, U256::from(5)), vec![0; 3]].concat();
+
+        let err = Memory::<Fp>::from_reader(&*bytes).unwrap_err();
+
+        assert!(matches!(
+            err,
+            Error::TruncatedRecord {
+                offset: 40,
+                expected_bytes: 8,
+                actual_bytes: 3
+            }
+        ));
+    }
+
+    #[test]
+    fn misaligned_memory_word_is_rejected() {
+        let mut bytes = memory_record(1, U256::from(5));
+        bytes.truncate(8 + 16);
+
+        let err = Memory::<Fp>::from_reader(&*bytes).unwrap_err();
+
+        assert!(matches!(
+            err,
+            Error::MisalignedWord {
+                offset: 0,
+                word_bytes: 32,
+                actual_bytes: 16
+            }
+        ));
+    }
+
+    #[test]
+    fn out_of_field_memory_word_is_rejected() {
+        let bytes = [
+            memory_record(1, U256::from(5)),
+            memory_record(2, U256::MAX >> 4),
+        ]
+        .concat();
+
+        let err = Memory::<Fp>::from_reader(&*bytes).unwrap_err();
+
+        assert!(matches!(err, Error::InvalidFieldElement { offset: 40, .. }));
+    }
+
+    #[test]
+    fn conflicting_memory_address_is_rejected() {
+        let bytes = [
+            memory_record(1, U256::from(5)),
+            memory_record(1, U256::from(5)),
+            memory_record(1, U256::from(6)),
+        ]
+        .concat();
+
+        let err = Memory::<Fp>::from_reader(&*bytes).unwrap_err();
+
+        assert!(matches!(
+            err,
+            Error::ConflictingAddress {
+                offset: 80,
+                address: 1,
+                ..
+            }
+        ));
+    }
+
+    #[test]
+    fn oversized_memory_address_is_rejected() {
+        let bytes = memory_record(u64::MAX, U256::from(5));
+
+        let err = Memory::<Fp>::from_reader(&*bytes).unwrap_err();
+
+        assert!(matches!(err, Error::AddressOutOfRange { offset: 0, .. }));
+    }
+
+    #[test]
+    fn sparse_memory_addresses_are_stored_sparsely() {
+        let max_address = MAX_ADDRESS as usize;
+        let bytes = [
+            memory_record(MAX_ADDRESS, U256::from(5)),
+            memory_record(1, U256::from(6)),
+        ]
+        .concat();
+
+        let memory = Memory::<Fp>::from_reader(&*bytes).unwrap();
+
+        assert_eq!(memory.len(), 2);
+        assert_eq!(memory.max_address(), Some(max_address));
+        assert_eq!(memory.get(max_address).unwrap().0, U256::from(5));
+        assert!(memory.get(2).is_none());
+    }
+
+    #[test]
+    fn sparse_memory_moves_to_dense_storage() {
+        let sparse_address = 90_000;
+        let bytes = [memory_record(sparse_address, U256::from(5))]
+            .into_iter()
+            .chain((1..20_000).map(|address| memory_record(address, U256::from(address))))
+            .chain([memory_record(99_999, U256::from(6))])
+            .collect::<Vec<Vec<u8>>>()
+            .concat();
+
+        let memory = Memory::<Fp>::from_reader(&*bytes).unwrap();
+
+        assert!(memory.sparse.is_empty());
+        assert_eq!(memory.len(), 20_001);
+        assert_eq!(memory.get(90_000).unwrap().0, U256::from(5));
+        assert_eq!(memory.max_address(), Some(99_999));
+    }
+
+    #[test]
+    fn sharp_code_is_big_endian_layout_name() {
+        let layout = Layout::StarknetWithKeccak;
+
+        let code = layout.sharp_code().unwrap();
+
+        let name = layout.to_string();
+        assert_eq!(U256::try_from_be_slice(name.as_bytes()), Some(code));
+        assert_eq!(Layout::from_sharp_code(code), Some(layout));
+    }
+
+    #[test]
+    fn layout_serializes_to_16_bytes() {
+        let mut starknet = Vec::new();
+        let mut starknet_with_keccak = Vec::new();
+
+        Layout::Starknet
+            .serialize_compressed(&mut starknet)
+            .unwrap();
+        Layout::StarknetWithKeccak
+            .serialize_compressed(&mut starknet_with_keccak)
+            .unwrap();
+
+        let starknet_code = Layout::SHARP_CODE_STARKNET.to_be_bytes::<32>();
+        assert_eq!(starknet, starknet_code[16..]);
+        assert_eq!(starknet_with_keccak, b"knet_with_keccak");
+        let layout = Layout::deserialize_compressed(&*starknet_with_keccak).unwrap();
+        assert_eq!(layout, Layout::StarknetWithKeccak);
+    }
+
+    #[test]
+    fn layout_without_sharp_code_is_not_serialized() {
+        let mut bytes = Vec::new();
+
+        let res = Layout::Plain.serialize_compressed(&mut bytes);
+
+        assert_eq!(Layout::Plain.sharp_code(), None);
+        assert!(res.is_err());
+    }
+
+    #[test]
+    fn dynamic_layout_is_not_serialized() {
+        let mut bytes = Vec::new();
+
+        let res = Layout::Dynamic.serialize_compressed(&mut bytes);
+
+        assert!(Layout::Dynamic.sharp_code().is_some());
+        assert!(res.is_err());
+    }
+
+    fn public_input_json(public_memory: &str) -> String {
+        format!(
+            r#"{{
+                "layout": "recursive",
+                "rc_min": 0,
+                "rc_max": 0,
+                "n_steps": 16,
+                "memory_segments": {{
+                    "program": {{ "begin_addr": 1, "stop_ptr": 3 }},
+                    "execution": {{ "begin_addr": 3, "stop_ptr": 5 }}
+                }},
+                "public_memory": [{public_memory}]
+            }}"#
+        )
+    }
+
+    #[test]
+    fn public_memory_pages_are_parsed() {
+        let json = public_input_json(
+            r#"{ "address": 1, "value": "0x1", "page": 0 },
+               { "address": 2, "value": "0x2", "page": 0 },
+               { "address": 21, "value": "0x4", "page": 1 },
+               { "address": 20, "value": "0x3", "page": 1 }"#,
+        );
+
+        let public_input: AirPublicInput<Fp> = serde_json::from_str(&json).unwrap();
+
+        let [main_page, page] = public_input.public_memory_pages[..] else {
+            panic!("expected two pages")
+        };
+        assert_eq!(main_page.size, 2);
+        assert_eq!(
+            page,
+            MemoryPage {
+                id: 1,
+                start_address: 20,
+                size: 2
+            }
+        );
+        let page_entries = public_input.page_entries(&page);
+        let page_addresses = page_entries.iter().map(|e| e.address).collect::<Vec<u32>>();
+        assert_eq!(page_addresses, [20, 21]);
+        let main_page_entries = public_input.page_entries(&main_page);
+        let main_page_addresses = main_page_entries
+            .iter()
+            .map(|e| e.address)
+            .collect::<Vec<u32>>();
+        assert_eq!(main_page_addresses, [1, 2]);
+    }
+
+    #[test]
+    fn page_can_end_at_the_top_of_the_address_space() {
+        let page = MemoryPage {
+            id: 1,
+            start_address: u32::MAX - 1,
+            size: 2,
+        };
+
+        assert!(page.contains(u32::MAX));
+        assert!(!page.contains(u32::MAX - 2));
+    }
+
+    #[test]
+    fn non_continuous_public_memory_page_is_rejected() {
+        let json = public_input_json(
+            r#"{ "address": 1, "value": "0x1", "page": 0 },
+               { "address": 20, "value": "0x3", "page": 1 },
+               { "address": 22, "value": "0x4", "page": 1 }"#,
+        );
+
+        let res = serde_json::from_str::<AirPublicInput<Fp>>(&json);
+
+        assert!(res.unwrap_err().to_string().contains("not continuous"));
+    }
+
+    #[test]
+    fn trace_round_trips() {
+        let register_states = RegisterStates::from_reader(TRACE).unwrap();
+
+        let mut bytes = Vec::new();
+        register_states.to_writer(&mut bytes).unwrap();
+
+        assert_eq!(bytes, TRACE);
+    }
+
+    #[test]
+    fn memory_round_trips() {
+        let memory = Memory::<Fp>::from_reader(MEMORY).unwrap();
+
+        let mut bytes = Vec::new();
+        memory.to_writer(&mut bytes).unwrap();
+        let read_memory = Memory::<Fp>::from_reader(&*bytes).unwrap();
+
+        let words = |memory: &Memory<Fp>| memory.iter().map(|(a, w)| (a, w.0)).collect::<Vec<_>>();
+        assert_eq!(words(&read_memory), words(&memory));
+        assert!(read_memory.iter().is_sorted_by_key(|(address, _)| address));
+    }
+
+    #[test]
+    fn air_public_input_round_trips() {
+        let public_input: AirPublicInput<Fp> = serde_json::from_str(AIR_PUBLIC_INPUT).unwrap();
+
+        let mut bytes = Vec::new();
+        public_input.to_writer(&mut bytes).unwrap();
+
+        assert_eq!(String::from_utf8(bytes).unwrap(), AIR_PUBLIC_INPUT);
+    }
+
+    #[test]
+    fn air_private_input_round_trips() {
+        let private_input: AirPrivateInput = serde_json::from_str(AIR_PRIVATE_INPUT).unwrap();
+
+        let mut bytes = Vec::new();
+        private_input
+            .to_writer(&mut bytes, Layout::Recursive)
+            .unwrap();
+
+        assert_eq!(String::from_utf8(bytes).unwrap(), AIR_PRIVATE_INPUT);
+    }
+}
@@ -7,7 +7,13 @@ use ruint::aliases::U256;
 use serde::de;
 use serde::Deserialize;
 use serde::Deserializer;
+use serde::Serialize;
+use serde::Serializer;
+use serde_json::ser::PrettyFormatter;
 use serde_json::value::Number;
+use std::io::ErrorKind;
+use std::io::Read;
+use std::io::Write;
 
 fn try_felt_from_u256<F: PrimeField>(value: U256) -> Result<F, InvalidFieldElementError> {
     let modulus = U256::from::<BigUint>(F::MODULUS.into());
@@ -32,20 +38,63 @@ pub fn deserialize_hex_str<'de, D: Deserializer<'de>>(deserializer: D) -> Result
     hex_str.parse::<U256>().map_err(de::Error::custom)
 }
 
-/// Deserializes a list of memory entries of the form
-/// `{value: "0x...", address: ...}`
-pub fn deserialize_hex_str_memory_entries<'de, D: Deserializer<'de>, F: PrimeField>(
+/// Serializes a big integer into a hex string. Inverse of [deserialize_hex_str]
+pub fn serialize_hex_str<S: Serializer>(value: &U256, serializer: S) -> Result<S::Ok, S::Error> {
+    serializer.serialize_str(&format!("{value:#x}"))
+}
+
+/// (De)serializes big integers as hex strings
+pub mod hex_str {
+    pub use super::deserialize_hex_str as deserialize;
+    pub use super::serialize_hex_str as serialize;
+}
+
+/// Serializes a list of public memory entries of the form (entry, page) into
+/// `{address: ..., value: "0x...", page: ...}`. Inverse of
+/// [deserialize_hex_str_public_memory]
+pub fn serialize_hex_str_public_memory<S: Serializer, F: PrimeField>(
+    entries: &[(MemoryEntry<F>, u32)],
+    serializer: S,
+) -> Result<S::Ok, S::Error> {
+    #[derive(Serialize)]
+    struct Entry {
+        address: u32,
+        value: String,
+        page: u32,
+    }
+    serializer.collect_seq(entries.iter().map(|&(entry, page)| {
+        let value: BigUint = entry.value.into();
+        Entry {
+            address: entry.address,
+            value: format!("{value:#x}"),
+            page,
+        }
+    }))
+}
+
+/// Deserializes a list of public memory entries of the form
+/// `{value: "0x...", address: ..., page: ...}`
+/// Output is of the form (entry, page)
+pub fn deserialize_hex_str_public_memory<'de, D: Deserializer<'de>, F: PrimeField>(
     deserializer: D,
-) -> Result<Vec<MemoryEntry<F>>, D::Error> {
+) -> Result<Vec<(MemoryEntry<F>, u32)>, D::Error> {
     #[derive(Deserialize)]
     struct Entry<F: PrimeField> {
         #[serde(deserialize_with = "deserialize_hex_str_as_field_element")]
         pub value: F,
         pub address: u32,
+        #[serde(default)]
+        pub page: u32,
     }
     let v = Vec::deserialize(deserializer)?;
     Ok(v.into_iter()
-        .map(|Entry { address, value }| MemoryEntry { address, value })
+        .map(
+            |Entry {
+                 address,
+                 value,
+                 page,
+             }| (MemoryEntry { address, value }, page),
+        )
         .collect())
 }
 
@@ -88,3 +137,27 @@ pub fn _deserialize_vec_big_uint<'de, D: Deserializer<'de>>(
 pub const fn field_bytes<F: PrimeField>() -> usize {
     F::MODULUS_BIT_SIZE.next_multiple_of(8) as usize / 8
 }
+
+/// Writes JSON in the same format as `cairo-run` (Python's `json.dump` with an
+/// indent of 4 followed by a newline)
+pub fn write_json(mut writer: impl Write, value: &impl Serialize) -> std::io::Result<()> {
+    let formatter = PrettyFormatter::with_indent(b"    ");
+    let mut serializer = serde_json::Serializer::with_formatter(&mut writer, formatter);
+    value.serialize(&mut serializer)?;
+    writer.write_all(b"\n")
+}
+
+/// Reads bytes into `buf` until it is full or the reader has no data left.
+/// Returns the number of bytes read.
+pub fn read_until_full(reader: &mut impl Read, buf: &mut [u8]) -> std::io::Result<usize> {
+    let mut n = 0;
+    while n < buf.len() {
+        match reader.read(&mut buf[n..]) {
+            Ok(0) => break,
+            Ok(read) => n += read,
+            Err(e) if e.kind() == ErrorKind::Interrupted => {}
+            Err(e) => return Err(e),
+        }
+    }
+    Ok(n)
+}
@@ -0,0 +1,1008 @@
+//! Cairo VM for running compiled programs in proof mode. Produces the same
+//! trace, memory and AIR inputs as `cairo-run --proof_mode` so programs can be
+//! proven without StarkWare's Python toolchain. Reference implementation:
+//! <https://github.com/starkware-libs/cairo-lang/blob/361fe32d5930db340ea78fe05aedfe706f6c9405/src/starkware/cairo/lang/vm/vm_core.py>
+//!
+//! Only the `alloc()` hint is supported and programs can only use the builtins
+//! in [`SUPPORTED_BUILTINS`]. Other builtins are still given a segment if
+//! they're part of the layout.
+
+use crate::errors::VmError;
+use crate::AirPrivateInput;
+use crate::AirPublicInput;
+use crate::BitwiseInstance;
+use crate::CompiledProgram;
+use crate::FlagGroup;
+use crate::Layout;
+use crate::Memory;
+use crate::MemoryEntry;
+use crate::MemoryPage;
+use crate::MemorySegments;
+use crate::RangeCheckInstance;
+use crate::RegisterState;
+use crate::RegisterStates;
+use crate::Segment;
+use crate::Word;
+use crate::HALF_OFFSET;
+use crate::MAX_ADDRESS;
+use alloc::vec::Vec;
+use ark_ff::PrimeField;
+use num_bigint::BigUint;
+use ruint::aliases::U256;
+use std::fmt::Display;
+use std::path::PathBuf;
+
+/// Names of the builtins the VM can execute. Programs that use other builtins
+/// (e.g. pedersen or poseidon) must be run with `cairo-run --proof_mode`.
+pub const SUPPORTED_BUILTINS: [&str; 3] = ["output", "range_check", "bitwise"];
+
+/// Code of the hint used by `alloc()` in `starkware.cairo.common.alloc`
+const ALLOC_HINT: &str = "memory[ap] = segments.add()";
+
+const PROGRAM_SEGMENT: usize = 0;
+const EXECUTION_SEGMENT: usize = 1;
+
+/// Number of bits in the inputs of the bitwise builtin
+const BITWISE_TOTAL_N_BITS: usize = 251;
+
+/// Parameters of a layout that determine the number of steps an execution is
+/// padded to. Builtin ratios are the number of steps per builtin instance and
+/// are `None` for builtins that aren't part of the layout.
+#[derive(Clone, Copy, Debug)]
+pub struct LayoutParams {
+    pub layout: Layout,
+    /// Range check units per step
+    pub rc_units: usize,
+    /// Memory units per step
+    pub memory_units: usize,
+    /// One in every `public_memory_fraction` memory units is reserved for the
+    /// public memory
+    pub public_memory_fraction: usize,
+    /// Diluted check units per step. Zero if the layout has no diluted pool.
+    pub diluted_units: usize,
+    pub diluted_spacing: usize,
+    pub diluted_n_bits: usize,
+    /// Number of 16 bit parts in a value checked by the range check builtin
+    pub range_check_parts: usize,
+    pub output: bool,
+    pub pedersen_ratio: Option<usize>,
+    pub range_check_ratio: Option<usize>,
+    pub ecdsa_ratio: Option<usize>,
+    pub bitwise_ratio: Option<usize>,
+    pub ec_op_ratio: Option<usize>,
+    pub keccak_ratio: Option<usize>,
+    pub poseidon_ratio: Option<usize>,
+}
+
+impl LayoutParams {
+    fn has_builtin(&self, builtin: Builtin) -> bool {
+        builtin == Builtin::Output && self.output || self.builtin_ratio(builtin).is_some()
+    }
+
+    /// Number of diluted check units used by an instance of the bitwise
+    /// builtin
+    pub fn bitwise_diluted_units(&self) -> usize {
+        let (spacing, n_bits) = (self.diluted_spacing, self.diluted_n_bits);
+        let partition = (0..BITWISE_TOTAL_N_BITS)
+            .step_by(spacing * n_bits)
+            .flat_map(|i| (0..spacing).map(move |j| i + j))
+            .collect::<Vec<usize>>();
+        let num_trimmed = partition
+            .iter()
+            .filter(|&&x| x + spacing * (n_bits - 1) + 1 > BITWISE_TOTAL_N_BITS)
+            .count();
+        4 * partition.len() + num_trimmed
+    }
+
+    fn builtin_ratio(&self, builtin: Builtin) -> Option<usize> {
+        match builtin {
+            Builtin::Output => None,
+            Builtin::Pedersen => self.pedersen_ratio,
+            Builtin::RangeCheck => self.range_check_ratio,
+            Builtin::Ecdsa => self.ecdsa_ratio,
+            Builtin::Bitwise => self.bitwise_ratio,
+            Builtin::EcOp => self.ec_op_ratio,
+            Builtin::Keccak => self.keccak_ratio,
+            Builtin::Poseidon => self.poseidon_ratio,
+        }
+    }
+}
+
+/// Output of running a program in proof mode
+#[derive(Debug)]
+pub struct Execution<F: PrimeField> {
+    pub register_states: RegisterStates,
+    pub memory: Memory<F>,
+    pub air_public_input: AirPublicInput<F>,
+    /// The trace and memory paths are empty since they're held in memory
+    pub air_private_input: AirPrivateInput,
+}
+
+/// Address of a memory cell before segments are relocated
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Relocatable {
+    pub segment: usize,
+    pub offset: usize,
+}
+
+impl Relocatable {
+    const fn new(segment: usize, offset: usize) -> Self {
+        Self { segment, offset }
+    }
+
+    fn offset_by(self, delta: i64) -> Option<Self> {
+        let offset = i64::try_from(self.offset).ok()?.checked_add(delta)?;
+        Some(Self::new(self.segment, usize::try_from(offset).ok()?))
+    }
+}
+
+impl Display for Relocatable {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}:{}", self.segment, self.offset)
+    }
+}
+
+/// Memory value. Either a field element or an address.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum Value<F> {
+    Int(F),
+    Addr(Relocatable),
+}
+
+impl<F: PrimeField> Value<F> {
+    fn add(self, other: Self) -> Option<Self> {
+        match (self, other) {
+            (Self::Int(a), Self::Int(b)) => Some(Self::Int(a + b)),
+            (Self::Addr(a), Self::Int(b)) | (Self::Int(b), Self::Addr(a)) => {
+                Some(Self::Addr(a.offset_by(felt_to_i64(b)?)?))
+            }
+            (Self::Addr(_), Self::Addr(_)) => None,
+        }
+    }
+
+    fn sub(self, other: Self) -> Option<Self> {
+        match (self, other) {
+            (Self::Int(a), Self::Int(b)) => Some(Self::Int(a - b)),
+            (Self::Addr(a), Self::Int(b)) => Some(Self::Addr(a.offset_by(-felt_to_i64(b)?)?)),
+            (Self::Addr(a), Self::Addr(b)) if a.segment == b.segment => Some(Self::Int(
+                felt_from_i64::<F>(a.offset as i64) - felt_from_i64::<F>(b.offset as i64),
+            )),
+            _ => None,
+        }
+    }
+
+    fn mul(self, other: Self) -> Option<Self> {
+        match (self, other) {
+            (Self::Int(a), Self::Int(b)) => Some(Self::Int(a * b)),
+            _ => None,
+        }
+    }
+
+    fn div(self, other: Self) -> Option<Self> {
+        match (self, other) {
+            (Self::Int(a), Self::Int(b)) => Some(Self::Int(a * b.inverse()?)),
+            _ => None,
+        }
+    }
+}
+
+/// Builtins in the order their segments are allocated
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum Builtin {
+    Output,
+    Pedersen,
+    RangeCheck,
+    Ecdsa,
+    Bitwise,
+    EcOp,
+    Keccak,
+    Poseidon,
+}
+
+impl Builtin {
+    const ALL: [Self; 8] = [
+        Self::Output,
+        Self::Pedersen,
+        Self::RangeCheck,
+        Self::Ecdsa,
+        Self::Bitwise,
+        Self::EcOp,
+        Self::Keccak,
+        Self::Poseidon,
+    ];
+
+    /// Name used by the `%builtins` directive
+    const fn name(self) -> &'static str {
+        match self {
+            Self::Output => "output",
+            Self::Pedersen => "pedersen",
+            Self::RangeCheck => "range_check",
+            Self::Ecdsa => "ecdsa",
+            Self::Bitwise => "bitwise",
+            Self::EcOp => "ec_op",
+            Self::Keccak => "keccak",
+            Self::Poseidon => "poseidon",
+        }
+    }
+
+    const fn cells_per_instance(self) -> usize {
+        match self {
+            Self::Output | Self::RangeCheck => 1,
+            Self::Pedersen => 3,
+            Self::Ecdsa => 2,
+            Self::Bitwise => 5,
+            Self::EcOp => 7,
+            Self::Keccak => 16,
+            Self::Poseidon => 6,
+        }
+    }
+
+    /// Builtins the VM can execute (see [`SUPPORTED_BUILTINS`])
+    fn is_supported(self) -> bool {
+        SUPPORTED_BUILTINS.contains(&self.name())
+    }
+}
+
+#[derive(Clone, Copy, Debug)]
+struct BuiltinRunner {
+    builtin: Builtin,
+    segment: usize,
+    /// `None` for the output builtin
+    ratio: Option<usize>,
+    /// If the program uses the builtin (i.e. it's in `%builtins`)
+    included: bool,
+    /// Offset of the pointer returned by `main`
+    stop_offset: usize,
+}
+
+impl BuiltinRunner {
+    fn allocated_cells(&self, n_steps: usize) -> Option<usize> {
+        let ratio = self.ratio?;
+        Some(n_steps / ratio * self.builtin.cells_per_instance())
+    }
+}
+
+struct Runner<'a, F: PrimeField> {
+    program: &'a CompiledProgram<F>,
+    params: &'a LayoutParams,
+    segments: Vec<Vec<Option<Value<F>>>>,
+    /// Cells accessed by instructions. Used to count memory holes.
+    accessed: Vec<Vec<bool>>,
+    builtins: Vec<BuiltinRunner>,
+    trace: Vec<[Relocatable; 3]>,
+    pc: Relocatable,
+    ap: Relocatable,
+    fp: Relocatable,
+    /// Range of the instruction offsets (off_dst, off_op0 and off_op1)
+    rc_limits: Option<(u16, u16)>,
+}
+
+/// Runs a program in proof mode. The execution starts at `__start__` and is
+/// padded by looping on `__end__` until the number of steps is a power-of-two
+/// that fits in the layout. Fails if more than `max_steps` steps are needed.
+pub fn run<F: PrimeField>(
+    program: &CompiledProgram<F>,
+    params: &LayoutParams,
+    max_steps: usize,
+) -> Result<Execution<F>, VmError> {
+    let mut runner = Runner::new(program, params)?;
+    let end = Relocatable::new(PROGRAM_SEGMENT, program.label_pc("__end__")?);
+
+    while runner.pc != end {
+        runner.step(max_steps)?;
+    }
+    // run one more step so the last executed instruction is `jmp rel 0`
+    runner.step(max_steps)?;
+    runner.read_return_values()?;
+    runner.validate_builtins()?;
+
+    let mut n_steps = runner.trace.len().next_power_of_two();
+    loop {
+        while runner.trace.len() < n_steps {
+            runner.step(max_steps)?;
+        }
+        if runner.fits_in(n_steps) {
+            break;
+        }
+        n_steps *= 2;
+    }
+
+    runner.finalize(end)
+}
+
+impl<'a, F: PrimeField> Runner<'a, F> {
+    fn new(program: &'a CompiledProgram<F>, params: &'a LayoutParams) -> Result<Self, VmError> {
+        let mut runner = Runner {
+            program,
+            params,
+            segments: Vec::new(),
+            accessed: Vec::new(),
+            builtins: Vec::new(),
+            trace: Vec::new(),
+            pc: Relocatable::new(PROGRAM_SEGMENT, program.label_pc("__start__")?),
+            ap: Relocatable::new(EXECUTION_SEGMENT, 2),
+            fp: Relocatable::new(EXECUTION_SEGMENT, 2),
+            rc_limits: None,
+        };
+
+        let program_segment = runner.add_segment();
+        let execution_segment = runner.add_segment();
+        debug_assert_eq!(program_segment, PROGRAM_SEGMENT);
+        debug_assert_eq!(execution_segment, EXECUTION_SEGMENT);
+
+        // the program's builtins must be a subsequence of the layout's builtins
+        let mut program_builtins = program.builtins.iter().peekable();
+        for builtin in Builtin::ALL {
+            if !params.has_builtin(builtin) {
+                continue;
+            }
+            let included = program_builtins.next_if(|&b| b == builtin.name()).is_some();
+            if included && !builtin.is_supported() {
+                return Err(VmError::UnsupportedBuiltin(builtin.name().to_string()));
+            }
+            let segment = runner.add_segment();
+            runner.builtins.push(BuiltinRunner {
+                builtin,
+                segment,
+                ratio: params.builtin_ratio(builtin),
+                included,
+                stop_offset: 0,
+            });
+        }
+        if let Some(name) = program_builtins.next() {
+            return Err(VmError::UnsupportedBuiltin(name.clone()));
+        }
+
+        for (i, &value) in program.data.iter().enumerate() {
+            let addr = Relocatable::new(PROGRAM_SEGMENT, i);
+            runner.write(addr, Value::Int(value))?;
+            runner.mark_accessed(addr);
+        }
+
+        // the dummy return fp and pc are public so the verifier can enforce
+        // `[fp - 2] = fp` on the final step
+        let mut stack = vec![Value::Addr(runner.fp), Value::Int(F::zero())];
+        for builtin in &runner.builtins {
+            if builtin.included {
+                stack.push(Value::Addr(Relocatable::new(builtin.segment, 0)));
+            }
+        }
+        for (i, value) in stack.into_iter().enumerate() {
+            let addr = Relocatable::new(EXECUTION_SEGMENT, i);
+            runner.write(addr, value)?;
+            runner.mark_accessed(addr);
+        }
+
+        Ok(runner)
+    }
+
+    fn add_segment(&mut self) -> usize {
+        self.segments.push(Vec::new());
+        self.accessed.push(Vec::new());
+        self.segments.len() - 1
+    }
+
+    fn get(&self, addr: Relocatable) -> Option<Value<F>> {
+        *self.segments.get(addr.segment)?.get(addr.offset)?
+    }
+
+    fn write(&mut self, addr: Relocatable, value: Value<F>) -> Result<(), VmError> {
+        let segment = self
+            .segments
+            .get_mut(addr.segment)
+            .ok_or(VmError::InvalidAddress(addr))?;
+        if addr.offset >= segment.len() {
+            segment.resize(addr.offset + 1, None);
+        }
+        match segment[addr.offset] {
+            Some(existing) if existing != value => Err(VmError::InconsistentMemory(addr)),
+            _ => {
+                segment[addr.offset] = Some(value);
+                Ok(())
+            }
+        }
+    }
+
+    fn mark_accessed(&mut self, addr: Relocatable) {
+        let accessed = &mut self.accessed[addr.segment];
+        if addr.offset >= accessed.len() {
+            accessed.resize(addr.offset + 1, false);
+        }
+        accessed[addr.offset] = true;
+    }
+
+    /// Reads a memory cell. Values that are missing from builtin segments are
+    /// deduced by the builtin.
+    fn read(&self, addr: Relocatable) -> Result<Option<Value<F>>, VmError> {
+        match self.get(addr) {
+            Some(value) => Ok(Some(value)),
+            None => self.deduce_builtin_cell(addr),
+        }
+    }
+
+    fn deduce_builtin_cell(&self, addr: Relocatable) -> Result<Option<Value<F>>, VmError> {
+        let Some(runner) = self.builtins.iter().find(|b| b.segment == addr.segment) else {
+            return Ok(None);
+        };
+
+        match runner.builtin {
+            Builtin::Bitwise => {
+                let cell = addr.offset % Builtin::Bitwise.cells_per_instance();
+                if cell < 2 {
+                    return Ok(None);
+                }
+                let x_addr = Relocatable::new(addr.segment, addr.offset - cell);
+                let y_addr = Relocatable::new(addr.segment, addr.offset - cell + 1);
+                let (Some(x), Some(y)) = (self.get(x_addr), self.get(y_addr)) else {
+                    return Ok(None);
+                };
+                let x = bitwise_input(x).ok_or(VmError::InvalidBuiltinValue(x_addr))?;
+                let y = bitwise_input(y).ok_or(VmError::InvalidBuiltinValue(y_addr))?;
+                let res = match cell {
+                    2 => x & y,
+                    3 => x ^ y,
+                    4 => x | y,
+                    _ => unreachable!(),
+                };
+                Ok(Some(Value::Int(u256_to_felt(res))))
+            }
+            _ => Ok(None),
+        }
+    }
+
+    /// Executes the hints at the current pc
+    fn run_hints(&mut self) -> Result<(), VmError> {
+        if self.pc.segment != PROGRAM_SEGMENT {
+            return Ok(());
+        }
+
+        let program = self.program;
+        let hints = program.hints.get(&self.pc.offset);
+        for hint in hints.into_iter().flatten() {
+            if hint.code.trim() != ALLOC_HINT {
+                return Err(VmError::UnsupportedHint {
+                    pc: self.pc,
+                    code: hint.code.clone(),
+                });
+            }
+            let segment = self.add_segment();
+            self.write(self.ap, Value::Addr(Relocatable::new(segment, 0)))?;
+        }
+
+        Ok(())
+    }
+
+    fn step(&mut self, max_steps: usize) -> Result<(), VmError> {
+        if self.trace.len() >= max_steps {
+            return Err(VmError::StepLimitExceeded(max_steps));
+        }
+
+        self.run_hints()?;
+        self.trace.push([self.pc, self.ap, self.fp]);
+
+        let (pc, ap, fp) = (self.pc, self.ap, self.fp);
+        let invalid_instruction = VmError::InvalidInstruction(pc);
+        let Some(Value::Int(encoded)) = self.get(pc) else {
+            return Err(invalid_instruction);
+        };
+        let encoded = felt_to_u256(encoded);
+        if encoded.bit_len() > 63 {
+            return Err(invalid_instruction);
+        }
+        let word = Word::<F>::new(encoded);
+        self.mark_accessed(pc);
+
+        let op1_src = word.get_flag_group(FlagGroup::Op1Src);
+        let res_logic = word.get_flag_group(FlagGroup::ResLogic);
+        let pc_update = word.get_flag_group(FlagGroup::PcUpdate);
+        let ap_update = word.get_flag_group(FlagGroup::ApUpdate);
+        let opcode = word.get_flag_group(FlagGroup::Opcode);
+        if !matches!(op1_src, 0 | 1 | 2 | 4)
+            || res_logic > 2
+            || !matches!(pc_update, 0 | 1 | 2 | 4)
+            || ap_update > 2
+            || !matches!(opcode, 0 | 1 | 2 | 4)
+            || (opcode == 1 && ap_update != 0)
+        {
+            return Err(invalid_instruction);
+        }
+        let size = if op1_src == 1 { 2 } else { 1 };
+        let next_instruction = Relocatable::new(pc.segment, pc.offset + size);
+
+        // `ap` and `fp` share the execution segment so the word's address
+        // helpers can be applied to their offsets
+        if ap.segment != fp.segment {
+            return Err(VmError::InvalidOperation(pc));
+        }
+        let register_addr = |offset: Option<usize>| {
+            let offset = offset.ok_or(VmError::InvalidOperation(pc))?;
+            Ok::<_, VmError>(Relocatable::new(ap.segment, offset))
+        };
+        let dst_addr = register_addr(word.checked_dst_addr(ap.offset, fp.offset))?;
+        let op0_addr = register_addr(word.checked_op0_addr(ap.offset, fp.offset))?;
+        let mut dst = self.read(dst_addr)?;
+        let mut op0 = self.read(op0_addr)?;
+        let op1_reg = match op1_src {
+            0 => match op0 {
+                Some(Value::Addr(addr)) => addr,
+                _ => return Err(VmError::UnknownOperand { pc, operand: "op0" }),
+            },
+            1 => pc,
+            2 => fp,
+            _ => ap,
+        };
+        let op1_addr = offset_addr(op1_reg, word.get_off_op1(), pc)?;
+        let mut op1 = self.read(op1_addr)?;
+
+        // deduce missing operands
+        let mut res = None;
+        if op0.is_none() {
+            match (opcode, res_logic, dst, op1) {
+                (1, ..) => op0 = Some(Value::Addr(next_instruction)),
+                (4, 1, Some(dst), Some(op1)) => op0 = dst.sub(op1),
+                (4, 2, Some(dst), Some(op1)) => op0 = dst.div(op1),
+                _ => {}
+            }
+            if opcode == 4 && op0.is_some() {
+                res = dst;
+            }
+        }
+        if op1.is_none() {
+            match (opcode, res_logic, dst, op0) {
+                (4, 0, Some(dst), _) => op1 = Some(dst),
+                (4, 1, Some(dst), Some(op0)) => op1 = dst.sub(op0),
+                (4, 2, Some(dst), Some(op0)) => op1 = dst.div(op0),
+                _ => {}
+            }
+            if opcode == 4 && op1.is_some() {
+                res = dst;
+            }
+        }
+        let op0 = op0.ok_or(VmError::UnknownOperand { pc, operand: "op0" })?;
+        let op1 = op1.ok_or(VmError::UnknownOperand { pc, operand: "op1" })?;
+        let invalid_operation = || VmError::InvalidOperation(pc);
+        if res.is_none() {
+            res = match res_logic {
+                // `res` is unused by conditional jumps
+                0 if pc_update == 4 => None,
+                0 => Some(op1),
+                1 => Some(op0.add(op1).ok_or_else(invalid_operation)?),
+                _ => Some(op0.mul(op1).ok_or_else(invalid_operation)?),
+            };
+        }
+        if dst.is_none() {
+            dst = match opcode {
+                1 => Some(Value::Addr(fp)),
+                4 => res,
+                _ => None,
+            };
+        }
+        let dst = dst.ok_or(VmError::UnknownOperand { pc, operand: "dst" })?;
+
+        for (addr, value) in [(dst_addr, dst), (op0_addr, op0), (op1_addr, op1)] {
+            self.write(addr, value)?;
+            self.mark_accessed(addr);
+        }
+
+        match opcode {
+            1 if dst != Value::Addr(fp) || op0 != Value::Addr(next_instruction) => {
+                return Err(VmError::AssertionFailed(pc))
+            }
+            4 if Some(dst) != res => return Err(VmError::AssertionFailed(pc)),
+            _ => {}
+        }
+
+        for offset in [word.get_off_dst(), word.get_off_op0(), word.get_off_op1()] {
+            self.rc_limits = Some(match self.rc_limits {
+                Some((min, max)) => (min.min(offset), max.max(offset)),
+                None => (offset, offset),
+            });
+        }
+
+        self.fp = match opcode {
+            1 => Relocatable::new(ap.segment, ap.offset + 2),
+            2 => match dst {
+                Value::Addr(addr) => addr,
+                Value::Int(_) => return Err(invalid_operation()),
+            },
+            _ => fp,
+        };
+        self.ap = match (opcode, ap_update) {
+            (1, _) => Relocatable::new(ap.segment, ap.offset + 2),
+            (_, 0) => ap,
+            (_, 1) => match res {
+                Some(Value::Int(res)) => felt_to_i64(res).and_then(|res| ap.offset_by(res)),
+                _ => None,
+            }
+            .ok_or_else(invalid_operation)?,
+            _ => Relocatable::new(ap.segment, ap.offset + 1),
+        };
+        self.pc = match pc_update {
+            0 => Some(next_instruction),
+            1 => match res {
+                Some(Value::Addr(addr)) => Some(addr),
+                _ => None,
+            },
+            2 => match res {
+                Some(Value::Int(res)) => felt_to_i64(res).and_then(|res| pc.offset_by(res)),
+                _ => None,
+            },
+            _ => match (dst, op1) {
+                (Value::Int(dst), _) if dst.is_zero() => Some(next_instruction),
+                (_, Value::Int(op1)) => felt_to_i64(op1).and_then(|op1| pc.offset_by(op1)),
+                _ => None,
+            },
+        }
+        .ok_or_else(invalid_operation)?;
+
+        Ok(())
+    }
+
+    /// Reads the builtin pointers returned by `main`
+    fn read_return_values(&mut self) -> Result<(), VmError> {
+        let mut pointer = self.ap;
+        for i in (0..self.builtins.len()).rev() {
+            let runner = &self.builtins[i];
+            if !runner.included {
+                continue;
+            }
+            let invalid_stop_pointer = VmError::InvalidStopPointer(runner.builtin.name());
+            pointer = pointer.offset_by(-1).ok_or(invalid_stop_pointer)?;
+            let stop_offset = match self.get(pointer) {
+                Some(Value::Addr(stop)) if stop.segment == runner.segment => stop.offset,
+                _ => return Err(VmError::InvalidStopPointer(runner.builtin.name())),
+            };
+            if stop_offset < self.segments[runner.segment].len() {
+                return Err(VmError::InvalidStopPointer(runner.builtin.name()));
+            }
+            self.builtins[i].stop_offset = stop_offset;
+        }
+        Ok(())
+    }
+
+    fn validate_builtins(&self) -> Result<(), VmError> {
+        let range_check_bound = U256::from(1) << (16 * self.params.range_check_parts);
+        for runner in &self.builtins {
+            for (offset, value) in self.segments[runner.segment].iter().enumerate() {
+                let Some(value) = *value else { continue };
+                let addr = Relocatable::new(runner.segment, offset);
+                let is_valid = match (runner.builtin, value) {
+                    (Builtin::RangeCheck, Value::Int(v)) => felt_to_u256(v) < range_check_bound,
+                    (Builtin::Bitwise, v) => bitwise_input(v).is_some(),
+                    (Builtin::Output, _) => true,
+                    _ => false,
+                };
+                if !is_valid {
+                    return Err(VmError::InvalidBuiltinValue(addr));
+                }
+            }
+        }
+        Ok(())
+    }
+
+    /// Number of cells used by a builtin segment
+    fn used_cells(&self, runner: &BuiltinRunner) -> usize {
+        runner.stop_offset.max(self.segments[runner.segment].len())
+    }
+
+    fn range_check_limits(&self) -> (u16, u16) {
+        let (mut min, mut max) = self.rc_limits.unwrap();
+        for runner in &self.builtins {
+            if runner.builtin != Builtin::RangeCheck {
+                continue;
+            }
+            for value in self.segments[runner.segment].iter().flatten() {
+                let &Value::Int(value) = value else { continue };
+                let value = felt_to_u256(value);
+                for i in 0..self.params.range_check_parts {
+                    let part = u16::try_from((value >> (16 * i)) & U256::from(u16::MAX)).unwrap();
+                    min = min.min(part);
+                    max = max.max(part);
+                }
+            }
+        }
+        (min, max)
+    }
+
+    /// Checks the execution fits in a trace of `n_steps` steps. Matches
+    /// `CairoRunner.check_used_cells` from cairo-lang.
+    fn fits_in(&self, n_steps: usize) -> bool {
+        let params = self.params;
+
+        // builtins
+        let mut builtin_memory_units = 0;
+        let mut rc_units_used_by_builtins = 0;
+        let mut diluted_units_used_by_builtins = 0;
+        for runner in &self.builtins {
+            let used_cells = self.used_cells(runner);
+            let allocated_cells = match (runner.ratio, runner.allocated_cells(n_steps)) {
+                (Some(ratio), _) if n_steps < ratio => return false,
+                (_, Some(allocated_cells)) if used_cells > allocated_cells => return false,
+                (_, Some(allocated_cells)) => allocated_cells,
+                (_, None) => used_cells,
+            };
+            builtin_memory_units += allocated_cells;
+            match runner.builtin {
+                Builtin::RangeCheck => {
+                    rc_units_used_by_builtins += used_cells * params.range_check_parts;
+                }
+                Builtin::Bitwise if params.diluted_units != 0 => {
+                    let instances = allocated_cells / runner.builtin.cells_per_instance();
+                    diluted_units_used_by_builtins += instances * params.bitwise_diluted_units();
+                }
+                _ => {}
+            }
+        }
+
+        // range checks (three units per step are used by the instruction offsets)
+        let (rc_min, rc_max) = self.range_check_limits();
+        let rc_units = (params.rc_units - 3) * n_steps;
+        match rc_units.checked_sub(rc_units_used_by_builtins) {
+            Some(unused_rc_units) if unused_rc_units >= (rc_max - rc_min) as usize => {}
+            _ => return false,
+        }
+
+        // memory (four units per step are used by the instruction)
+        let total_memory_units = params.memory_units * n_steps;
+        let public_memory_units = total_memory_units / params.public_memory_fraction;
+        let instruction_memory_units = 4 * n_steps;
+        if self.public_memory_len() > public_memory_units {
+            return false;
+        }
+        match total_memory_units
+            .checked_sub(public_memory_units + instruction_memory_units + builtin_memory_units)
+        {
+            Some(unused_memory_units) if unused_memory_units >= self.memory_holes() => {}
+            _ => return false,
+        }
+
+        // diluted checks (the pool must contain every diluted value)
+        if params.diluted_units != 0 {
+            let diluted_units = params.diluted_units * n_steps;
+            match diluted_units.checked_sub(diluted_units_used_by_builtins) {
+                Some(unused) if unused >= 1 << params.diluted_n_bits => {}
+                _ => return false,
+            }
+        }
+
+        true
+    }
+
+    /// Number of cells in non-builtin segments that aren't accessed by an
+    /// instruction
+    fn memory_holes(&self) -> usize {
+        (0..self.segments.len())
+            .filter(|&i| !self.builtins.iter().any(|b| b.segment == i))
+            .map(|i| {
+                let num_accessed = self.accessed[i].iter().filter(|&&a| a).count();
+                self.segments[i].len() - num_accessed
+            })
+            .sum()
+    }
+
+    fn output_runner(&self) -> Option<&BuiltinRunner> {
+        self.builtins.iter().find(|b| b.builtin == Builtin::Output)
+    }
+
+    fn public_memory_len(&self) -> usize {
+        let num_stack_values = 2 + self.builtins.iter().filter(|b| b.included).count();
+        let num_output_values = self.output_runner().map_or(0, |runner| {
+            self.segments[runner.segment].iter().flatten().count()
+        });
+        self.program.data.len() + num_stack_values + num_output_values
+    }
+
+    /// Relocates all segments into a single continuous memory and builds the
+    /// AIR inputs
+    fn finalize(self, end: Relocatable) -> Result<Execution<F>, VmError> {
+        let n_steps = self.trace.len();
+
+        // segments are placed one after the other starting at address 1.
+        // builtin segments are given the number of cells allocated by the layout.
+        let mut segment_addrs = Vec::with_capacity(self.segments.len());
+        let mut next_addr = 1;
+        for (i, segment) in self.segments.iter().enumerate() {
+            let runner = self.builtins.iter().find(|b| b.segment == i);
+            let size = match runner {
+                Some(runner) => runner
+                    .allocated_cells(n_steps)
+                    .unwrap_or_else(|| self.used_cells(runner)),
+                None => segment.len(),
+            };
+            segment_addrs.push(next_addr);
+            next_addr += size;
+        }
+        if next_addr as u64 > MAX_ADDRESS {
+            return Err(VmError::AddressOutOfRange(next_addr as u64));
+        }
+
+        let relocate = |addr: Relocatable| (segment_addrs[addr.segment] + addr.offset) as u32;
+        let relocate_value = |value: Value<F>| match value {
+            Value::Int(v) => v,
+            Value::Addr(addr) => F::from(relocate(addr)),
+        };
+
+        let register_states = RegisterStates(
+            self.trace
+                .iter()
+                .map(|&[pc, ap, fp]| RegisterState {
+                    ap: relocate(ap) as usize,
+                    fp: relocate(fp) as usize,
+                    pc: relocate(pc) as usize,
+                })
+                .collect(),
+        );
+
+        let mut memory = Memory::default();
+        for (i, segment) in self.segments.iter().enumerate() {
+            for (offset, value) in segment.iter().enumerate() {
+                if let &Some(value) = value {
+                    let address = relocate(Relocatable::new(i, offset)) as usize;
+                    memory.insert(address, Word::new(felt_to_u256(relocate_value(value))));
+                }
+            }
+        }
+
+        // public memory holds the program, the initial stack and the output
+        let mut public_memory = Vec::new();
+        let num_stack_values = 2 + self.builtins.iter().filter(|b| b.included).count();
+        let public_cells = (0..self.program.data.len())
+            .map(|offset| Relocatable::new(PROGRAM_SEGMENT, offset))
+            .chain((0..num_stack_values).map(|offset| Relocatable::new(EXECUTION_SEGMENT, offset)))
+            .chain(self.output_runner().into_iter().flat_map(|runner| {
+                let used_cells = self.used_cells(runner);
+                (0..used_cells).map(|offset| Relocatable::new(runner.segment, offset))
+            }));
+        for addr in public_cells {
+            if let Some(value) = self.get(addr) {
+                public_memory.push(MemoryEntry {
+                    address: relocate(addr),
+                    value: relocate_value(value),
+                });
+            }
+        }
+
+        let builtin_segment = |builtin: Builtin| {
+            let runner = self.builtins.iter().find(|b| b.builtin == builtin)?;
+            let begin = Relocatable::new(runner.segment, 0);
+            let stop = Relocatable::new(runner.segment, runner.stop_offset);
+            Some(Segment {
+                begin_addr: relocate(begin),
+                stop_ptr: relocate(stop),
+            })
+        };
+        let memory_segments = MemorySegments {
+            program: Segment {
+                begin_addr: relocate(Relocatable::new(PROGRAM_SEGMENT, 0)),
+                stop_ptr: relocate(end),
+            },
+            execution: Segment {
+                begin_addr: relocate(self.trace[0][1]),
+                stop_ptr: relocate(self.ap),
+            },
+            output: builtin_segment(Builtin::Output),
+            pedersen: builtin_segment(Builtin::Pedersen),
+            range_check: builtin_segment(Builtin::RangeCheck),
+            ecdsa: builtin_segment(Builtin::Ecdsa),
+            bitwise: builtin_segment(Builtin::Bitwise),
+            ec_op: builtin_segment(Builtin::EcOp),
+            keccak: builtin_segment(Builtin::Keccak),
+            poseidon: builtin_segment(Builtin::Poseidon),
+        };
+
+        let (rc_min, rc_max) = self.range_check_limits();
+        let air_public_input = AirPublicInput {
+            rc_min,
+            rc_max,
+            n_steps: n_steps as u64,
+            layout: self.params.layout,
+            memory_segments,
+            public_memory_pages: vec![MemoryPage {
+                id: 0,
+                start_address: 0,
+                size: public_memory.len() as u32,
+            }],
+            public_memory,
+            dynamic_params: None,
+        };
+
+        let mut range_check = Vec::new();
+        let mut bitwise = Vec::new();
+        for runner in &self.builtins {
+            let segment = &self.segments[runner.segment];
+            let felt = |offset: usize| match segment.get(offset) {
+                Some(&Some(Value::Int(v))) => Some(felt_to_u256(v)),
+                _ => None,
+            };
+            match runner.builtin {
+                Builtin::RangeCheck => {
+                    for index in 0..segment.len() {
+                        if let Some(value) = felt(index) {
+                            let index = index as u32;
+                            range_check.push(RangeCheckInstance { index, value });
+                        }
+                    }
+                }
+                Builtin::Bitwise => {
+                    let cells = Builtin::Bitwise.cells_per_instance();
+                    for index in 0..segment.len().div_ceil(cells) {
+                        if let (Some(x), Some(y)) = (felt(index * cells), felt(index * cells + 1)) {
+                            let index = index as u32;
+                            bitwise.push(BitwiseInstance { index, x, y });
+                        }
+                    }
+                }
+                _ => {}
+            }
+        }
+        let air_private_input = AirPrivateInput {
+            trace_path: PathBuf::new(),
+            memory_path: PathBuf::new(),
+            pedersen: Vec::new(),
+            range_check,
+            ecdsa: Vec::new(),
+            bitwise,
+            ec_op: Vec::new(),
+            keccak: Vec::new(),
+            poseidon: Vec::new(),
+        };
+
+        Ok(Execution {
+            register_states,
+            memory,
+            air_public_input,
+            air_private_input,
+        })
+    }
+}
+
+impl<F: PrimeField> CompiledProgram<F> {
+    /// Returns the pc of a label in the program's main scope
+    fn label_pc(&self, label: &str) -> Result<usize, VmError> {
+        let name = format!("{}.{label}", self.main_scope);
+        self.identifiers
+            .get(&name)
+            .and_then(|identifier| identifier.pc)
+            .ok_or(VmError::MissingLabel(name))
+    }
+}
+
+/// Applies an instruction offset to a register
+fn offset_addr(reg: Relocatable, offset: u16, pc: Relocatable) -> Result<Relocatable, VmError> {
+    reg.offset_by(offset as i64 - HALF_OFFSET as i64)
+        .ok_or(VmError::InvalidOperation(pc))
+}
+
+fn bitwise_input<F: PrimeField>(value: Value<F>) -> Option<U256> {
+    match value {
+        Value::Int(v) => Some(felt_to_u256(v)).filter(|v| v.bit_len() <= BITWISE_TOTAL_N_BITS),
+        Value::Addr(_) => None,
+    }
+}
+
+fn felt_to_u256<F: PrimeField>(v: F) -> U256 {
+    U256::from::<BigUint>(v.into())
+}
+
+fn u256_to_felt<F: PrimeField>(v: U256) -> F {
+    BigUint::from(v).into()
+}
+
+/// Interprets a field element as a signed integer i.e. `p - n` is `-n`
+fn felt_to_i64<F: PrimeField>(v: F) -> Option<i64> {
+    let v: BigUint = v.into();
+    if let Ok(v) = i64::try_from(&v) {
+        return Some(v);
+    }
+    let modulus: BigUint = F::MODULUS.into();
+    i64::try_from(&(modulus - v)).ok().map(|v| -v)
+}
+
+fn felt_from_i64<F: PrimeField>(v: i64) -> F {
+    let abs = F::from(v.unsigned_abs());
+    if v < 0 {
+        -abs
+    } else {
+        abs
+    }
+}
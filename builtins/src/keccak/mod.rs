@@ -0,0 +1,232 @@
+//! Keccak builtin. Applies the Keccak-f[1600] permutation to a 1600 bit state.
+//! Reference implementation (used by StarkWare):
+//! <https://github.com/starkware-libs/cairo-lang/blob/361fe32d5930db340ea78fe05aedfe706f6c9405/src/starkware/cairo/lang/builtins/keccak/keccak_builtin_runner.py>
+
+use binary::KeccakInstance;
+use ministark_gpu::fields::p3618502788666131213697322783095070105623107215331596699973092056135872020481::ark::Fp;
+use num_bigint::BigUint;
+use ruint::aliases::U256;
+use std::fmt::Display;
+
+/// Number of rounds in Keccak-f[1600]
+pub const NUM_ROUNDS: usize = 24;
+
+/// Number of bits in each of the 8 input and output field elements
+pub const STATE_REP_BITS: usize = 200;
+
+/// Number of 64 bit lanes in the Keccak state
+pub const NUM_LANES: usize = 25;
+
+pub const ROUND_CONSTANTS: [u64; NUM_ROUNDS] = [
+    0x0000000000000001,
+    0x0000000000008082,
+    0x800000000000808A,
+    0x8000000080008000,
+    0x000000000000808B,
+    0x0000000080000001,
+    0x8000000080008081,
+    0x8000000000008009,
+    0x000000000000008A,
+    0x0000000000000088,
+    0x0000000080008009,
+    0x000000008000000A,
+    0x000000008000808B,
+    0x800000000000008B,
+    0x8000000000008089,
+    0x8000000000008003,
+    0x8000000000008002,
+    0x8000000000000080,
+    0x000000000000800A,
+    0x800000008000000A,
+    0x8000000080008081,
+    0x8000000000008080,
+    0x0000000080000001,
+    0x8000000080008008,
+];
+
+/// Rotation offsets of the rho step. Lane `(x, y)` is at index `x + 5 * y`.
+const ROTATION_OFFSETS: [u32; NUM_LANES] = [
+    0, 1, 62, 28, 27, 36, 44, 6, 55, 20, 3, 10, 43, 25, 39, 41, 45, 15, 21, 8, 18, 2, 61, 56, 14,
+];
+
+/// Keccak state represented as 25 lanes of 64 bits. Lane `(x, y)` is at index
+/// `x + 5 * y`.
+pub type State = [u64; NUM_LANES];
+
+/// A keccak builtin input doesn't fit in [STATE_REP_BITS] bits
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct InvalidInputError {
+    /// Position of the input in the instance (`0..8`)
+    pub position: usize,
+    pub value: U256,
+}
+
+impl Display for InvalidInputError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let Self { position, value } = self;
+        write!(
+            f,
+            "keccak input {position} ({value:#x}) exceeds {STATE_REP_BITS} bits"
+        )
+    }
+}
+
+impl std::error::Error for InvalidInputError {}
+
+#[derive(Clone, Debug)]
+pub struct InstanceTrace {
+    pub instance: KeccakInstance,
+    pub input: [Fp; 8],
+    pub output: [Fp; 8],
+    /// State before each round followed by the state after the last round
+    pub round_states: Vec<State>,
+}
+
+impl InstanceTrace {
+    pub fn new(instance: KeccakInstance) -> Result<Self, InvalidInputError> {
+        let inputs = instance.inputs();
+        let mut state = to_state(inputs)?;
+        let mut round_states = Vec::with_capacity(NUM_ROUNDS + 1);
+        round_states.push(state);
+        for round_constant in ROUND_CONSTANTS {
+            keccak_round(&mut state, round_constant);
+            round_states.push(state);
+        }
+        let outputs = from_state(&state);
+
+        let input = inputs.map(|v| BigUint::from(v).into());
+        let output = outputs.map(|v| BigUint::from(v).into());
+
+        Ok(Self {
+            instance,
+            input,
+            output,
+            round_states,
+        })
+    }
+}
+
+/// Applies the Keccak-f[1600] permutation to the 8 field element
+/// representation of the state used by the keccak builtin
+pub fn keccak_f1600(inputs: [U256; 8]) -> Result<[U256; 8], InvalidInputError> {
+    let mut state = to_state(inputs)?;
+    permute(&mut state);
+    Ok(from_state(&state))
+}
+
+/// Applies all rounds of the Keccak-f[1600] permutation
+pub fn permute(state: &mut State) {
+    for round_constant in ROUND_CONSTANTS {
+        keccak_round(state, round_constant);
+    }
+}
+
+/// Applies a single Keccak round (theta, rho, pi, chi and iota steps)
+pub fn keccak_round(a: &mut State, round_constant: u64) {
+    // theta
+    let c: [u64; 5] = core::array::from_fn(|x| a[x] ^ a[x + 5] ^ a[x + 10] ^ a[x + 15] ^ a[x + 20]);
+    for x in 0..5 {
+        let d = c[(x + 4) % 5] ^ c[(x + 1) % 5].rotate_left(1);
+        for y in 0..5 {
+            a[x + 5 * y] ^= d;
+        }
+    }
+
+    // rho and pi
+    let mut b = [0; NUM_LANES];
+    for x in 0..5 {
+        for y in 0..5 {
+            let i = x + 5 * y;
+            b[y + 5 * ((2 * x + 3 * y) % 5)] = a[i].rotate_left(ROTATION_OFFSETS[i]);
+        }
+    }
+
+    // chi
+    for x in 0..5 {
+        for y in 0..5 {
+            a[x + 5 * y] = b[x + 5 * y] ^ (!b[(x + 1) % 5 + 5 * y] & b[(x + 2) % 5 + 5 * y]);
+        }
+    }
+
+    // iota
+    a[0] ^= round_constant;
+}
+
+/// Converts the builtin's representation (8 little-endian 200 bit values) into
+/// 25 little-endian 64 bit lanes
+fn to_state(inputs: [U256; 8]) -> Result<State, InvalidInputError> {
+    const CHUNK_BYTES: usize = STATE_REP_BITS / 8;
+    let mut bytes = [0u8; NUM_LANES * 8];
+    let chunks = bytes.chunks_exact_mut(CHUNK_BYTES);
+    for (position, (chunk, value)) in chunks.zip(inputs).enumerate() {
+        if value.bit_len() > STATE_REP_BITS {
+            return Err(InvalidInputError { position, value });
+        }
+        chunk.copy_from_slice(&value.to_le_bytes::<32>()[0..CHUNK_BYTES]);
+    }
+    Ok(core::array::from_fn(|i| {
+        u64::from_le_bytes(bytes[i * 8..(i + 1) * 8].try_into().unwrap())
+    }))
+}
+
+/// Inverse of [to_state]
+fn from_state(state: &State) -> [U256; 8] {
+    const CHUNK_BYTES: usize = STATE_REP_BITS / 8;
+    let bytes = state
+        .iter()
+        .flat_map(|lane| lane.to_le_bytes())
+        .collect::<Vec<u8>>();
+    core::array::from_fn(|i| U256::from_le_slice(&bytes[i * CHUNK_BYTES..(i + 1) * CHUNK_BYTES]))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::keccak_f1600;
+    use super::permute;
+    use super::InvalidInputError;
+    use super::STATE_REP_BITS;
+    use ruint::aliases::U256;
+
+    #[test]
+    fn permutation_of_zero_state_matches_reference() {
+        let mut state = [0; 25];
+
+        permute(&mut state);
+
+        // test vector from the Keccak team's `KeccakF-1600-IntermediateValues.txt`
+        assert_eq!(state[0], 0xF1258F7940E1DDE7);
+        assert_eq!(state[1], 0x84D5CCF933C0478A);
+        assert_eq!(state[24], 0xEAF1FF7B5CECA249);
+    }
+
+    #[test]
+    fn builtin_representation_matches_lanes() {
+        let mut state = [0; 25];
+        permute(&mut state);
+
+        let output = keccak_f1600([U256::ZERO; 8]).unwrap();
+
+        // first 200 bits contain lanes 0, 1, 2 and the first byte of lane 3
+        let expected = U256::from(state[0])
+            | U256::from(state[1]) << 64
+            | U256::from(state[2]) << 128
+            | U256::from(state[3] & 0xFF) << 192;
+        assert_eq!(output[0], expected);
+    }
+
+    #[test]
+    fn oversized_input_is_rejected() {
+        let mut inputs = [U256::ZERO; 8];
+        inputs[3] = U256::from(1) << STATE_REP_BITS;
+
+        let err = keccak_f1600(inputs).unwrap_err();
+
+        assert_eq!(
+            err,
+            InvalidInputError {
+                position: 3,
+                value: inputs[3]
+            }
+        );
+    }
+}
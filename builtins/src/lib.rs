@@ -1,6 +1,7 @@
 pub mod bitwise;
 pub mod ec_op;
 pub mod ecdsa;
+pub mod keccak;
 pub mod pedersen;
 pub mod poseidon;
 pub mod range_check;
@@ -16,6 +16,7 @@ use self::params::ROUND_KEYS;
 use crate::poseidon::params::FULL_ROUND_KEYS_2ND_HALF;
 use crate::poseidon::params::PARTIAL_ROUND_KEYS_OPTIMIZED;
 use crate::utils::Mat3x3;
+use ark_ff::BigInt;
 use ark_ff::Field;
 use num_bigint::BigUint;
 
@@ -147,6 +148,26 @@ fn gen_half_full_round_states(
     rounds
 }
 
+/// Hashes two field elements with Poseidon. Matches `poseidon_hash` in
+/// StarkWare's cairo-lang.
+pub fn poseidon_hash(x: Fp, y: Fp) -> Fp {
+    let x = starknet_crypto::FieldElement::from_mont((x.0).0);
+    let y = starknet_crypto::FieldElement::from_mont((y.0).0);
+    let res = starknet_crypto::poseidon_hash(x, y);
+    Fp::new_unchecked(BigInt(res.into_mont()))
+}
+
+/// Hashes a sequence of field elements with Poseidon. Matches
+/// `poseidon_hash_many` in StarkWare's cairo-lang.
+pub fn poseidon_hash_many(values: &[Fp]) -> Fp {
+    let values = values
+        .iter()
+        .map(|v| starknet_crypto::FieldElement::from_mont((v.0).0))
+        .collect::<Vec<_>>();
+    let res = starknet_crypto::poseidon_hash_many(&values);
+    Fp::new_unchecked(BigInt(res.into_mont()))
+}
+
 /// Computes the Poseidon hash using StarkWare's parameters. Source:
 /// <https://extgit.iaik.tugraz.at/krypto/hadeshash/-/blob/master/code/starkadperm_x5_256_3.sage>
 fn permute(input: [Fp; 3]) -> [Fp; 3] {
@@ -0,0 +1,114 @@
+use binary::Layout;
+use sandstorm::claims::ClaimError;
+use std::fmt::Display;
+use std::path::PathBuf;
+
+/// Exit code for input files that can't be opened or parsed
+pub const EXIT_INVALID_INPUT: u8 = 2;
+/// Exit code for layouts or prime fields that aren't supported
+pub const EXIT_UNSUPPORTED: u8 = 3;
+/// Exit code for witnesses (trace and memory) that are inconsistent
+pub const EXIT_INVALID_WITNESS: u8 = 4;
+/// Exit code for failures while generating a proof
+pub const EXIT_PROVING_FAILED: u8 = 5;
+/// Exit code for proofs that were rejected by the verifier
+pub const EXIT_VERIFICATION_FAILED: u8 = 6;
+/// Exit code for output files that can't be written
+pub const EXIT_INVALID_OUTPUT: u8 = 7;
+
+#[derive(Debug)]
+pub enum CliError {
+    /// An input file couldn't be read or parsed
+    InvalidInput {
+        path: PathBuf,
+        reason: String,
+    },
+    /// The output file couldn't be written
+    InvalidOutput {
+        path: PathBuf,
+        reason: String,
+    },
+    /// A command line argument required by the command wasn't given
+    MissingArgument(&'static str),
+    UnsupportedLayout(Layout),
+    /// The program uses a builtin that sandstorm's Cairo VM can't execute
+    UnsupportedBuiltin(String),
+    UnsupportedPrime(String),
+    /// The selected verifier and hash function can't be used for the command
+    UnsupportedClaim(String),
+    /// The trace or memory are inconsistent with the program or public input
+    InvalidWitness(String),
+    ProvingFailed(String),
+    VerificationFailed(String),
+}
+
+impl CliError {
+    pub fn invalid_input(path: impl Into<PathBuf>, reason: impl Display) -> Self {
+        Self::InvalidInput {
+            path: path.into(),
+            reason: reason.to_string(),
+        }
+    }
+
+    pub fn invalid_output(path: impl Into<PathBuf>, reason: impl Display) -> Self {
+        Self::InvalidOutput {
+            path: path.into(),
+            reason: reason.to_string(),
+        }
+    }
+
+    /// Process exit code associated with the error
+    pub const fn exit_code(&self) -> u8 {
+        match self {
+            Self::InvalidInput { .. } | Self::MissingArgument(_) => EXIT_INVALID_INPUT,
+            Self::InvalidOutput { .. } => EXIT_INVALID_OUTPUT,
+            Self::UnsupportedLayout(_)
+            | Self::UnsupportedBuiltin(_)
+            | Self::UnsupportedPrime(_)
+            | Self::UnsupportedClaim(_) => EXIT_UNSUPPORTED,
+            Self::InvalidWitness(_) => EXIT_INVALID_WITNESS,
+            Self::ProvingFailed(_) => EXIT_PROVING_FAILED,
+            Self::VerificationFailed(_) => EXIT_VERIFICATION_FAILED,
+        }
+    }
+}
+
+impl Display for CliError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::InvalidInput { path, reason } => {
+                write!(f, "invalid input file {}: {reason}", path.display())
+            }
+            Self::InvalidOutput { path, reason } => {
+                write!(f, "could not write {}: {reason}", path.display())
+            }
+            Self::MissingArgument(name) => write!(f, "{name} is required by this command"),
+            Self::UnsupportedLayout(layout) => write!(f, "layout {layout} is not supported yet"),
+            Self::UnsupportedBuiltin(name) => write!(
+                f,
+                "the {name} builtin is not supported by `run`. Run the program with \
+                 `cairo-run --proof_mode` and prove it with `prove` instead."
+            ),
+            Self::UnsupportedPrime(prime) => write!(
+                f,
+                "prime field p={prime} is not supported yet. Consider enabling the \
+                 \"experimental_claims\" feature."
+            ),
+            Self::UnsupportedClaim(reason) => write!(f, "{reason}"),
+            Self::InvalidWitness(reason) => write!(f, "invalid witness: {reason}"),
+            Self::ProvingFailed(reason) => write!(f, "proof generation failed: {reason}"),
+            Self::VerificationFailed(reason) => write!(f, "proof rejected: {reason}"),
+        }
+    }
+}
+
+impl std::error::Error for CliError {}
+
+impl From<ClaimError> for CliError {
+    fn from(err: ClaimError) -> Self {
+        match err {
+            ClaimError::UnsupportedLayout(layout) => Self::UnsupportedLayout(layout),
+            err => Self::UnsupportedClaim(err.to_string()),
+        }
+    }
+}
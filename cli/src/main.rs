@@ -2,25 +2,63 @@ use ark_ff::Field;
 use ark_ff::PrimeField;
 use ark_serialize::CanonicalDeserialize;
 use ark_serialize::CanonicalSerialize;
+use binary::vm;
+use binary::vm::Execution;
 use binary::AirPrivateInput;
 use binary::AirPublicInput;
 use binary::CompiledProgram;
 use binary::Layout;
 use binary::Memory;
 use binary::RegisterStates;
+use crypto::merkle::Decommitment;
+use crypto::merkle::ReadDecommitment;
+use crypto::public_coin::cairo::CairoVerifierPublicCoin;
+use crypto::public_coin::solidity::SolidityVerifierPublicCoin;
+use errors::CliError;
+use layouts::constraints::ConstraintRegistry;
+use layouts::debug::failing_constraints;
+use layouts::debug::ConstraintFailure;
+use layouts::CairoAirConfig;
 use layouts::CairoWitness;
+use ministark::air::AirConfig;
+use ministark::merkle::MerkleTree;
 use ministark::stark::Stark;
+use ministark::Air;
 use ministark::Proof;
 use ministark::ProofOptions;
+use ministark::Trace;
 use ministark_gpu::fields::p3618502788666131213697322783095070105623107215331596699973092056135872020481;
-use sandstorm::claims;
+use num_bigint::BigUint;
+use p3618502788666131213697322783095070105623107215331596699973092056135872020481::ark::Fp;
+use ruint::aliases::U256;
+use sandstorm::annotated::AnnotatedProof;
+use sandstorm::batch::Batch;
+use sandstorm::batch::BatchFacts;
+use sandstorm::bundle::ProofBundle;
+use sandstorm::cairo_verifier::CairoVerifierInput;
+use sandstorm::claims::visit_claim;
+use sandstorm::claims::ClaimSelection;
+use sandstorm::claims::ClaimVisitor;
+use sandstorm::claims::CommitmentHash;
+use sandstorm::claims::Verifier;
+use sandstorm::evm::SplitProof;
+use sandstorm::fact;
+use sandstorm::fact::Fact;
+use sandstorm::fact::ProgramHashFn;
+use sandstorm::stone::StoneProof;
+use sandstorm::VerifyOutput;
 use std::fs;
 use std::fs::File;
+use std::io::BufReader;
+use std::io::BufWriter;
 use std::io::Write;
 use std::path::PathBuf;
+use std::process::ExitCode;
 use std::time::Instant;
 use structopt::StructOpt;
 
+mod errors;
+
 /// Modulus of Starkware's 252-bit prime field used for Cairo
 const STARKWARE_PRIME_HEX_STR: &str =
     "0x800000000000011000000000000000000000000000000000000000000000001";
@@ -32,10 +70,22 @@ const GOLDILOCKS_PRIME_HEX_STR: &str = "0xffffffff00000001";
 #[derive(StructOpt, Debug)]
 #[structopt(name = "sandstorm", about = "cairo prover")]
 struct SandstormOptions {
+    /// Required by all commands except `batch-input` and the ones that read a
+    /// proof bundle
     #[structopt(long, parse(from_os_str))]
-    program: PathBuf,
+    program: Option<PathBuf>,
+    /// Required by all commands except `batch-input` and the ones that read a
+    /// proof bundle
     #[structopt(long, parse(from_os_str))]
-    air_public_input: PathBuf,
+    air_public_input: Option<PathBuf>,
+    /// Verifier the proof is for: evm, cairo or native. Defaults to evm for
+    /// the starknet layout and cairo for other layouts.
+    #[structopt(long)]
+    verifier: Option<Verifier>,
+    /// Hash function of the commitments: keccak, blake2s, pedersen or
+    /// poseidon. Defaults to the first hash the verifier supports.
+    #[structopt(long)]
+    hash: Option<CommitmentHash>,
     #[structopt(subcommand)]
     command: Command,
 }
@@ -47,58 +97,265 @@ enum Command {
         output: PathBuf,
         #[structopt(long, parse(from_os_str))]
         air_private_input: PathBuf,
-        // TODO: add validation to the proof options
-        #[structopt(long, default_value = "65")]
-        num_queries: u8,
-        #[structopt(long, default_value = "2")]
-        lde_blowup_factor: u8,
-        #[structopt(long, default_value = "16")]
-        proof_of_work_bits: u8,
-        #[structopt(long, default_value = "8")]
-        fri_folding_factor: u8,
-        #[structopt(long, default_value = "16")]
-        fri_max_remainder_coeffs: u8,
+        #[structopt(flatten)]
+        options: ProvingOptions,
+    },
+    /// Runs the program with sandstorm's Cairo VM in proof mode and proves the
+    /// execution. The AIR public input of the run is written to
+    /// `--air-public-input`. Only the `alloc()` hint is supported so programs
+    /// that use other hints (e.g. to read a program input) must be run with
+    /// `cairo-run --proof_mode` and proven with `prove`. The same goes for
+    /// programs that use builtins other than output, range_check and bitwise.
+    Run {
+        /// Layout the execution is padded to fit: starknet or recursive
+        #[structopt(long, parse(try_from_str = parse_layout))]
+        layout: Layout,
+        #[structopt(long, default_value = "16777216")]
+        max_steps: usize,
+        #[structopt(long, parse(from_os_str))]
+        output: PathBuf,
+        /// Directory the trace (`trace.bin`), memory (`memory.bin`) and AIR
+        /// private input (`air-private-input.json`) of the run are written to
+        #[structopt(long, parse(from_os_str))]
+        witness_dir: Option<PathBuf>,
+        #[structopt(flatten)]
+        options: ProvingOptions,
     },
     Verify {
         #[structopt(long, parse(from_os_str))]
         proof: PathBuf,
         #[structopt(long, default_value = "80")]
         required_security_bits: u8,
+        /// Prints the verified output as JSON
+        #[structopt(long)]
+        json: bool,
+    },
+    /// Checks the witness is consistent without generating a proof
+    Check {
+        #[structopt(long, parse(from_os_str))]
+        air_private_input: PathBuf,
+    },
+    /// Lists the AIR constraints that don't hold on the execution trace
+    DebugConstraints {
+        #[structopt(long, parse(from_os_str))]
+        air_private_input: PathBuf,
+        /// Proof options the AIR is instantiated with
+        #[structopt(flatten)]
+        options: ProvingOptions,
+    },
+    /// Converts a proof to StarkWare's annotated JSON proof format
+    ExportAnnotated {
+        #[structopt(long, parse(from_os_str))]
+        proof: PathBuf,
+        #[structopt(long, parse(from_os_str))]
+        output: PathBuf,
     },
+    /// Generates calldata for verifying a proof with StarkWare's Ethereum
+    /// verifier as a split proof
+    ExportEvm {
+        #[structopt(long, parse(from_os_str))]
+        proof: PathBuf,
+        #[structopt(long, parse(from_os_str))]
+        output: PathBuf,
+        /// JSON array with the task metadata of the bootloader run
+        #[structopt(long, parse(from_os_str))]
+        task_metadata: PathBuf,
+        #[structopt(long)]
+        cairo_verifier_id: u64,
+    },
+    /// Verifies a proof in StarkWare's JSON proof format e.g. a proof
+    /// generated by stone-prover
+    VerifyStone {
+        #[structopt(long, parse(from_os_str))]
+        proof: PathBuf,
+        #[structopt(long, default_value = "80")]
+        required_security_bits: u8,
+    },
+    /// Generates the program input of StarkWare's Cairo verifier for a proof
+    ExportCairoVerifierInput {
+        #[structopt(long, parse(from_os_str))]
+        proof: PathBuf,
+        #[structopt(long, parse(from_os_str))]
+        output: PathBuf,
+    },
+    /// Writes the program input of the simple bootloader for running several
+    /// tasks. The bootloader's hints aren't supported by sandstorm's VM so it
+    /// has to be run on the input with `cairo-run --proof_mode`.
+    BatchInput {
+        /// Compiled program of a task
+        #[structopt(long = "task", parse(from_os_str), required = true)]
+        tasks: Vec<PathBuf>,
+        /// Program input of each task. Tasks run on an empty input if omitted.
+        #[structopt(long = "task-input", parse(from_os_str))]
+        task_inputs: Vec<PathBuf>,
+        #[structopt(long, parse(from_os_str))]
+        output: PathBuf,
+    },
+    /// Proves an existing run of the simple bootloader (passed as the program)
+    /// made with `cairo-run --proof_mode` on the input from `batch-input`.
+    /// Sandstorm doesn't run the bootloader itself. Writes the program hash,
+    /// output and fact of each task after checking each task's program hash
+    /// against its program.
+    ProveBootloaderRun(BootloaderRunOptions),
+    /// Packs a proof with its program, public input and proof options
+    Bundle {
+        #[structopt(long, parse(from_os_str))]
+        proof: PathBuf,
+        #[structopt(long, parse(from_os_str))]
+        output: PathBuf,
+    },
+    /// Extracts the program, public input and proof of a bundle
+    Unbundle {
+        #[structopt(long, parse(from_os_str))]
+        bundle: PathBuf,
+        /// Directory the program (`program.json`), public input
+        /// (`air-public-input.json`) and proof (`proof.bin`) are written to
+        #[structopt(long, parse(from_os_str))]
+        output_dir: PathBuf,
+    },
+    /// Verifies a proof bundle
+    VerifyBundle {
+        #[structopt(long, parse(from_os_str))]
+        bundle: PathBuf,
+        #[structopt(long, default_value = "80")]
+        required_security_bits: u8,
+        /// Prints the verified output as JSON
+        #[structopt(long)]
+        json: bool,
+    },
+}
+
+#[derive(StructOpt, Debug)]
+struct BootloaderRunOptions {
+    /// Compiled program of each task in the order they were run
+    #[structopt(long = "task", parse(from_os_str), required = true)]
+    tasks: Vec<PathBuf>,
+    #[structopt(long, parse(from_os_str))]
+    air_private_input: PathBuf,
+    #[structopt(long, parse(from_os_str))]
+    output: PathBuf,
+    /// Writes the facts of the tasks and their task metadata
+    #[structopt(long, parse(from_os_str))]
+    facts: PathBuf,
+    #[structopt(flatten)]
+    options: ProvingOptions,
 }
 
-fn main() {
+#[derive(StructOpt, Debug)]
+struct ProvingOptions {
+    // TODO: add validation to the proof options
+    #[structopt(long, default_value = "65")]
+    num_queries: u8,
+    #[structopt(long, default_value = "2")]
+    lde_blowup_factor: u8,
+    #[structopt(long, default_value = "16")]
+    proof_of_work_bits: u8,
+    #[structopt(long, default_value = "8")]
+    fri_folding_factor: u8,
+    #[structopt(long, default_value = "16")]
+    fri_max_remainder_coeffs: u8,
+}
+
+impl From<ProvingOptions> for ProofOptions {
+    fn from(options: ProvingOptions) -> Self {
+        ProofOptions::new(
+            options.num_queries,
+            options.lde_blowup_factor,
+            options.proof_of_work_bits,
+            options.fri_folding_factor,
+            options.fri_max_remainder_coeffs,
+        )
+    }
+}
+
+fn main() -> ExitCode {
     // read command-line args
+    let options = SandstormOptions::from_args();
+    match run(options) {
+        Ok(()) => ExitCode::SUCCESS,
+        Err(err) => {
+            eprintln!("error: {err}");
+            ExitCode::from(err.exit_code())
+        }
+    }
+}
+
+fn run(options: SandstormOptions) -> Result<(), CliError> {
     let SandstormOptions {
         program,
         air_public_input,
+        verifier,
+        hash,
         command,
-    } = SandstormOptions::from_args();
+    } = options;
+
+    // bundles hold the program, public input and claim selection
+    match &command {
+        Command::BatchInput {
+            tasks,
+            task_inputs,
+            output,
+        } => return batch_input(tasks, task_inputs, output),
+        Command::Unbundle { bundle, output_dir } => return unbundle(bundle, output_dir),
+        Command::VerifyBundle {
+            bundle,
+            required_security_bits,
+            json,
+        } => return verify_bundle(bundle, *required_security_bits, *json),
+        _ => {}
+    }
+    let program = program.ok_or(CliError::MissingArgument("--program"))?;
+    let air_public_input =
+        air_public_input.ok_or(CliError::MissingArgument("--air-public-input"))?;
+    // the public input of a run is generated by the VM
+    if let Command::Run { .. } = command {
+        return run_program(&program, &air_public_input, verifier, hash, command);
+    }
 
-    let program_file = File::open(program).expect("could not open program file");
-    let air_public_input_file = File::open(air_public_input).expect("could not open public input");
-    let program_json: serde_json::Value = serde_json::from_reader(program_file).unwrap();
-    let prime: String = serde_json::from_value(program_json["prime"].clone()).unwrap();
+    let program_file = File::open(&program).map_err(|e| CliError::invalid_input(&program, e))?;
+    let air_public_input_file =
+        File::open(&air_public_input).map_err(|e| CliError::invalid_input(&air_public_input, e))?;
+    let program_json: serde_json::Value =
+        serde_json::from_reader(program_file).map_err(|e| CliError::invalid_input(&program, e))?;
+    let prime: String = serde_json::from_value(program_json["prime"].clone())
+        .map_err(|e| CliError::invalid_input(&program, e))?;
 
     match prime.to_lowercase().as_str() {
         STARKWARE_PRIME_HEX_STR => {
-            use p3618502788666131213697322783095070105623107215331596699973092056135872020481::ark::Fp;
-            let program: CompiledProgram<Fp> = serde_json::from_value(program_json).unwrap();
+            let program_path = program;
+            let program: CompiledProgram<Fp> = serde_json::from_value(program_json.clone())
+                .map_err(|e| CliError::invalid_input(&program_path, e))?;
+            let air_public_input_path = air_public_input;
             let air_public_input: AirPublicInput<Fp> =
-                serde_json::from_reader(air_public_input_file).unwrap();
-            match air_public_input.layout {
-                Layout::Starknet => {
-                    use claims::starknet::EthVerifierClaim;
-                    let claim = EthVerifierClaim::new(program, air_public_input);
-                    execute_command(command, claim);
-                }
-                Layout::Recursive => {
-                    use claims::recursive::CairoVerifierClaim;
-                    let claim = CairoVerifierClaim::new(program, air_public_input);
-                    execute_command(command, claim);
-                }
-                _ => unimplemented!(),
+                serde_json::from_reader(air_public_input_file)
+                    .map_err(|e| CliError::invalid_input(&air_public_input_path, e))?;
+            if let Command::Check { air_private_input } = &command {
+                return check(&air_public_input, air_private_input);
             }
+            // the fact is printed once the proof is generated or verified. Runs
+            // without a `main` function or public output have no fact but can
+            // still be proven.
+            let fact = match &command {
+                Command::Prove { .. } | Command::Verify { .. } => {
+                    Fact::from_run(&program, &air_public_input).ok()
+                }
+                _ => None,
+            };
+            // commands for StarkWare's proof formats imply the verifier
+            let verifier = verifier.or(match &command {
+                Command::ExportEvm { .. } | Command::VerifyStone { .. } => Some(Verifier::Evm),
+                Command::ExportCairoVerifierInput { .. } => Some(Verifier::Cairo),
+                _ => None,
+            });
+            let selection = ClaimSelection::resolve(air_public_input.layout, verifier, hash)?;
+            let visitor = CommandVisitor {
+                command,
+                selection,
+                program_json,
+                air_public_input_path,
+                fact,
+            };
+            visit_claim(selection, program, air_public_input, visitor)?
         }
         #[cfg(feature = "experimental_claims")]
         GOLDILOCKS_PRIME_HEX_STR => {
@@ -109,9 +366,30 @@ fn main() {
             use p18446744069414584321::ark::Fp;
             use p18446744069414584321::ark::Fq3;
             use sandstorm::CairoClaim;
-            let program: CompiledProgram<Fp> = serde_json::from_value(program_json).unwrap();
+            let program: CompiledProgram<Fp> = serde_json::from_value(program_json)
+                .map_err(|e| CliError::invalid_input(&program, e))?;
             let air_public_input: AirPublicInput<Fp> =
-                serde_json::from_reader(air_public_input_file).unwrap();
+                serde_json::from_reader(air_public_input_file)
+                    .map_err(|e| CliError::invalid_input(&air_public_input, e))?;
+            if let Command::Check { air_private_input } = &command {
+                return check(&air_public_input, air_private_input);
+            }
+            if verifier.is_some() || hash.is_some() {
+                return Err(CliError::UnsupportedClaim(
+                    "the Goldilocks field only supports native Sha256 commitments".into(),
+                ));
+            }
+            if let Command::ExportAnnotated { .. }
+            | Command::ExportEvm { .. }
+            | Command::VerifyStone { .. }
+            | Command::ExportCairoVerifierInput { .. }
+            | Command::ProveBootloaderRun(_)
+            | Command::Bundle { .. } = &command
+            {
+                // StarkWare's proof format and bootloader are only defined over the 252-bit
+                // field
+                return Err(CliError::UnsupportedLayout(air_public_input.layout));
+            }
             match air_public_input.layout {
                 Layout::Plain => {
                     type A = layouts::plain::AirConfig<Fp, Fq3>;
@@ -120,61 +398,430 @@ fn main() {
                     type P = PublicCoinImpl<Fq3, Sha256HashFn>;
                     type C = CairoClaim<Fp, A, T, M, P>;
                     let claim = C::new(program, air_public_input);
-                    execute_command(command, claim);
+                    execute_command(command, claim)
                 }
-                Layout::Starknet => {
-                    unimplemented!("'starknet' layout does not support Goldilocks field")
+                // NOTE: 'starknet' and 'recursive' layouts do not support Goldilocks field
+                layout => Err(CliError::UnsupportedLayout(layout)),
+            }
+        }
+        prime => Err(CliError::UnsupportedPrime(prime.to_string())),
+    }
+}
+
+/// Runs a command on the claim picked by the claim registry. Commands for
+/// StarkWare's proof formats are only run on claims for StarkWare's verifiers.
+struct CommandVisitor {
+    command: Command,
+    selection: ClaimSelection,
+    program_json: serde_json::Value,
+    air_public_input_path: PathBuf,
+    /// Fact of the run (if it has one) printed once the proof is generated or
+    /// verified
+    fact: Option<Fact>,
+}
+
+impl ClaimVisitor for CommandVisitor {
+    type Output = Result<(), CliError>;
+
+    fn visit<Claim>(self, claim: Claim) -> Result<(), CliError>
+    where
+        Claim: Stark<Fp = Fp, Fq = Fp, Witness = CairoWitness<Fp>> + VerifyOutput,
+        Claim::AirConfig: ConstraintRegistry + AirConfig<PublicInputs = AirPublicInput<Fp>>,
+    {
+        match self.command {
+            Command::Prove {
+                output,
+                air_private_input,
+                options,
+            } => {
+                prove(options.into(), &air_private_input, &output, claim)?;
+                if let Some(fact) = &self.fact {
+                    print_fact(fact);
                 }
-                Layout::Recursive => {
-                    unimplemented!("'recursive' layout does not support Goldilocks field")
+                Ok(())
+            }
+            Command::Verify {
+                proof,
+                required_security_bits,
+                json,
+            } => {
+                let fact = self.fact.as_ref();
+                verify(required_security_bits, &proof, json, fact, claim)
+            }
+            Command::ProveBootloaderRun(run) => {
+                prove_bootloader_run(run, &self.air_public_input_path, claim)
+            }
+            Command::Bundle { proof, output } => {
+                bundle(&proof, &output, self.selection, self.program_json, claim)
+            }
+            Command::ExportAnnotated { .. } | Command::VerifyStone { .. } => {
+                Err(CliError::UnsupportedClaim(format!(
+                    "StarkWare's proof formats aren't defined for the {} verifier",
+                    self.selection.verifier()
+                )))
+            }
+            Command::ExportEvm { .. } => Err(unexpected_verifier(self.selection, Verifier::Evm)),
+            Command::ExportCairoVerifierInput { .. } => {
+                Err(unexpected_verifier(self.selection, Verifier::Cairo))
+            }
+            command => execute_command(command, claim),
+        }
+    }
+
+    fn visit_evm<Claim>(self, claim: Claim) -> Result<(), CliError>
+    where
+        Claim: Stark<
+                Fp = Fp,
+                Fq = Fp,
+                Witness = CairoWitness<Fp>,
+                PublicCoin = SolidityVerifierPublicCoin,
+            > + VerifyOutput,
+        Claim::AirConfig:
+            ConstraintRegistry + CairoAirConfig + AirConfig<PublicInputs = AirPublicInput<Fp>>,
+        Claim::MerkleTree: ReadDecommitment<Root = Claim::Digest>,
+        <Claim::MerkleTree as MerkleTree>::Proof: Decommitment,
+    {
+        match &self.command {
+            Command::ExportAnnotated { proof, output } => export_annotated(proof, output, claim),
+            Command::ExportEvm {
+                proof,
+                output,
+                task_metadata,
+                cairo_verifier_id,
+            } => {
+                // proofs for the Ethereum verifier use the starknet layout
+                let layout = claim.get_public_inputs().layout;
+                if layout != Layout::Starknet {
+                    return Err(CliError::UnsupportedLayout(layout));
                 }
-                layout => unimplemented!("layout {layout} is not supported yet"),
+                export_evm(proof, output, task_metadata, *cairo_verifier_id, claim)
+            }
+            Command::VerifyStone {
+                proof,
+                required_security_bits,
+            } => verify_stone(*required_security_bits, proof, claim),
+            _ => self.visit(claim),
+        }
+    }
+
+    fn visit_cairo_verifier<Claim>(self, claim: Claim) -> Result<(), CliError>
+    where
+        Claim: Stark<
+                Fp = Fp,
+                Fq = Fp,
+                Witness = CairoWitness<Fp>,
+                PublicCoin = CairoVerifierPublicCoin,
+            > + VerifyOutput,
+        Claim::AirConfig:
+            ConstraintRegistry + CairoAirConfig + AirConfig<PublicInputs = AirPublicInput<Fp>>,
+        Claim::MerkleTree: ReadDecommitment<Root = Claim::Digest>,
+        <Claim::MerkleTree as MerkleTree>::Proof: Decommitment,
+    {
+        match &self.command {
+            Command::ExportAnnotated { proof, output } => export_annotated(proof, output, claim),
+            Command::ExportCairoVerifierInput { proof, output } => {
+                export_cairo_verifier_input(proof, output, claim)
             }
+            Command::VerifyStone {
+                proof,
+                required_security_bits,
+            } => verify_stone(*required_security_bits, proof, claim),
+            _ => self.visit(claim),
         }
-        prime => unimplemented!("prime field p={prime} is not supported yet. Consider enabling the \"experimental_claims\" feature."),
     }
 }
 
-fn execute_command<Fp: PrimeField, Claim: Stark<Fp = Fp, Witness = CairoWitness<Fp>>>(
-    command: Command,
-    claim: Claim,
-) {
+/// Proves a witness generated by the Cairo VM
+struct RunVisitor {
+    options: ProofOptions,
+    witness: CairoWitness<Fp>,
+    output: PathBuf,
+}
+
+impl ClaimVisitor for RunVisitor {
+    type Output = Result<(), CliError>;
+
+    fn visit<Claim>(self, claim: Claim) -> Result<(), CliError>
+    where
+        Claim: Stark<Fp = Fp, Fq = Fp, Witness = CairoWitness<Fp>> + VerifyOutput,
+        Claim::AirConfig: ConstraintRegistry + AirConfig<PublicInputs = AirPublicInput<Fp>>,
+    {
+        prove_witness(self.options, self.witness, &self.output, claim)
+    }
+}
+
+fn unexpected_verifier(selection: ClaimSelection, verifier: Verifier) -> CliError {
+    CliError::UnsupportedClaim(format!(
+        "the command needs a proof for the {verifier} verifier but {} was selected",
+        selection.verifier()
+    ))
+}
+
+fn execute_command<Fp: PrimeField, Claim>(command: Command, claim: Claim) -> Result<(), CliError>
+where
+    Claim: Stark<Fp = Fp, Witness = CairoWitness<Fp>> + VerifyOutput,
+    Claim::AirConfig: ConstraintRegistry + AirConfig<PublicInputs = AirPublicInput<Fp>>,
+{
     match command {
         Command::Prove {
             output,
             air_private_input,
-            num_queries,
-            lde_blowup_factor,
-            proof_of_work_bits,
-            fri_folding_factor,
-            fri_max_remainder_coeffs,
-        } => {
-            let options = ProofOptions::new(
-                num_queries,
-                lde_blowup_factor,
-                proof_of_work_bits,
-                fri_folding_factor,
-                fri_max_remainder_coeffs,
-            );
-            prove(options, &air_private_input, &output, claim)
-        }
+            options,
+        } => prove(options.into(), &air_private_input, &output, claim),
         Command::Verify {
             proof,
             required_security_bits,
-        } => verify(required_security_bits, &proof, claim),
+            json,
+        } => verify(required_security_bits, &proof, json, None, claim),
+        Command::DebugConstraints {
+            air_private_input,
+            options,
+        } => debug_constraints(options.into(), &air_private_input, claim),
+        Command::Check { .. } => unreachable!("witness checks don't need a claim"),
+        Command::ExportAnnotated { .. }
+        | Command::ExportEvm { .. }
+        | Command::VerifyStone { .. }
+        | Command::ExportCairoVerifierInput { .. }
+        | Command::ProveBootloaderRun(_)
+        | Command::Bundle { .. } => {
+            unreachable!("StarkWare proofs and bootloader runs are handled by the claim registry")
+        }
+        Command::BatchInput { .. } | Command::Unbundle { .. } | Command::VerifyBundle { .. } => {
+            unreachable!("bootloader inputs and bundles are handled before reading the program")
+        }
+        Command::Run { .. } => unreachable!("runs are handled before reading the public input"),
+    }
+}
+
+fn verify<Fp: PrimeField, Claim: VerifyOutput<Fp = Fp>>(
+    required_security_bits: u8,
+    proof_path: &PathBuf,
+    json: bool,
+    fact: Option<&Fact>,
+    claim: Claim,
+) -> Result<(), CliError> {
+    let proof_bytes = fs::read(proof_path).map_err(|e| CliError::invalid_input(proof_path, e))?;
+    let proof = Proof::<Claim>::deserialize_compressed(&*proof_bytes)
+        .map_err(|e| CliError::invalid_input(proof_path, e))?;
+    verify_proof(required_security_bits, proof, json, fact, claim)
+}
+
+/// Verifies a proof and prints the program's output and fact. The output is
+/// part of the public input so it's only trusted once the proof is verified.
+fn verify_proof<Fp: PrimeField, Claim: VerifyOutput<Fp = Fp>>(
+    required_security_bits: u8,
+    proof: Proof<Claim>,
+    json: bool,
+    fact: Option<&Fact>,
+    claim: Claim,
+) -> Result<(), CliError> {
+    let now = Instant::now();
+    let output = claim
+        .verify_output(proof, required_security_bits.into())
+        .map_err(|e| CliError::VerificationFailed(e.to_string()))?;
+    let elapsed = now.elapsed();
+
+    let output = output
+        .into_iter()
+        .map(|entry| (entry.address, BigUint::from(entry.value.into_bigint())))
+        .collect::<Vec<(u32, BigUint)>>();
+    if json {
+        let output = output
+            .iter()
+            .map(|(address, value)| {
+                serde_json::json!({
+                    "address": address,
+                    "decimal": value.to_string(),
+                    "hex": format!("{value:#x}"),
+                })
+            })
+            .collect::<Vec<_>>();
+        println!("{}", serde_json::json!({ "output": output, "fact": fact }));
+        return Ok(());
+    }
+
+    println!("Proof verified in: {elapsed:?}");
+    if output.is_empty() {
+        println!("Program output: (empty)");
+    } else {
+        println!("Program output:");
+        for (address, value) in &output {
+            println!("    [{address}] {value} ({value:#x})");
+        }
+    }
+    if let Some(fact) = fact {
+        print_fact(fact);
     }
+    Ok(())
+}
+
+fn print_fact(fact: &Fact) {
+    println!("Program hash: {:#x}", fact.program_hash);
+    println!("Output hash: {:#x}", fact.output_hash);
+    println!("Fact: {:#x}", fact.fact);
 }
 
-fn verify<Claim: Stark<Fp = impl Field>>(
+/// Packs a proof with the program and public input it was generated for
+fn bundle<Claim>(
+    proof_path: &PathBuf,
+    output_path: &PathBuf,
+    selection: ClaimSelection,
+    program_json: serde_json::Value,
+    claim: Claim,
+) -> Result<(), CliError>
+where
+    Claim: Stark<Fp = Fp>,
+    Claim::AirConfig: AirConfig<PublicInputs = AirPublicInput<Fp>>,
+{
+    let proof_bytes = fs::read(proof_path).map_err(|e| CliError::invalid_input(proof_path, e))?;
+    let proof = Proof::<Claim>::deserialize_compressed(&*proof_bytes)
+        .map_err(|e| CliError::invalid_input(proof_path, e))?;
+    let bundle = ProofBundle::new(selection, program_json, claim.get_public_inputs(), &proof)
+        .map_err(|e| CliError::invalid_input(proof_path, e))?;
+    let output_file =
+        File::create(output_path).map_err(|e| CliError::invalid_output(output_path, e))?;
+    let mut writer = BufWriter::new(output_file);
+    bundle
+        .write(&mut writer)
+        .and_then(|()| Ok(writer.flush()?))
+        .map_err(|e| CliError::invalid_output(output_path, e))?;
+    println!("Bundle written to {}", output_path.display());
+    Ok(())
+}
+
+fn read_bundle(bundle_path: &PathBuf) -> Result<ProofBundle, CliError> {
+    let bundle_file =
+        File::open(bundle_path).map_err(|e| CliError::invalid_input(bundle_path, e))?;
+    ProofBundle::read(BufReader::new(bundle_file))
+        .map_err(|e| CliError::invalid_input(bundle_path, e))
+}
+
+/// Writes the program, public input and proof of a bundle to a directory
+fn unbundle(bundle_path: &PathBuf, output_dir: &PathBuf) -> Result<(), CliError> {
+    let bundle = read_bundle(bundle_path)?;
+    fs::create_dir_all(output_dir).map_err(|e| CliError::invalid_output(output_dir, e))?;
+
+    let program_path = output_dir.join("program.json");
+    let program_file =
+        File::create(&program_path).map_err(|e| CliError::invalid_output(&program_path, e))?;
+    serde_json::to_writer(program_file, &bundle.program)
+        .map_err(|e| CliError::invalid_output(&program_path, e))?;
+    let air_public_input_path = output_dir.join("air-public-input.json");
+    let air_public_input_file = File::create(&air_public_input_path)
+        .map_err(|e| CliError::invalid_output(&air_public_input_path, e))?;
+    (bundle.air_public_input)
+        .to_writer(air_public_input_file)
+        .map_err(|e| CliError::invalid_output(&air_public_input_path, e))?;
+    let proof_path = output_dir.join("proof.bin");
+    fs::write(&proof_path, &bundle.proof).map_err(|e| CliError::invalid_output(&proof_path, e))?;
+
+    let header = &bundle.header;
+    println!(
+        "Bundle (version {}, layout {}, {} claim) extracted to {}",
+        header.version,
+        header.layout,
+        header.claim,
+        output_dir.display()
+    );
+    Ok(())
+}
+
+/// Verifies a proof bundle with the claim recorded in its header
+fn verify_bundle(
+    bundle_path: &PathBuf,
+    required_security_bits: u8,
+    json: bool,
+) -> Result<(), CliError> {
+    let bundle = read_bundle(bundle_path)?;
+    let invalid_bundle = |e| CliError::invalid_input(bundle_path, e);
+    let program = bundle.program().map_err(invalid_bundle)?;
+    let air_public_input = bundle.air_public_input.clone();
+    let fact = Fact::from_run(&program, &air_public_input).ok();
+
+    let visitor = BundleVisitor {
+        bundle: &bundle,
+        bundle_path,
+        required_security_bits,
+        json,
+        fact: fact.as_ref(),
+    };
+    visit_claim(bundle.header.claim, program, air_public_input, visitor)?
+}
+
+/// Verifies the proof of a bundle with the claim picked by the claim registry
+struct BundleVisitor<'a> {
+    bundle: &'a ProofBundle,
+    bundle_path: &'a PathBuf,
     required_security_bits: u8,
+    json: bool,
+    fact: Option<&'a Fact>,
+}
+
+impl ClaimVisitor for BundleVisitor<'_> {
+    type Output = Result<(), CliError>;
+
+    fn visit<Claim>(self, claim: Claim) -> Result<(), CliError>
+    where
+        Claim: Stark<Fp = Fp, Fq = Fp, Witness = CairoWitness<Fp>> + VerifyOutput,
+        Claim::AirConfig: ConstraintRegistry + AirConfig<PublicInputs = AirPublicInput<Fp>>,
+    {
+        let proof =
+            (self.bundle.proof()).map_err(|e| CliError::invalid_input(self.bundle_path, e))?;
+        verify_proof(
+            self.required_security_bits,
+            proof,
+            self.json,
+            self.fact,
+            claim,
+        )
+    }
+}
+
+/// Writes the Cairo verifier's program input for a proof
+fn export_cairo_verifier_input<Claim>(
     proof_path: &PathBuf,
+    output_path: &PathBuf,
     claim: Claim,
-) {
-    let proof_bytes = fs::read(proof_path).unwrap();
-    let proof = Proof::<Claim>::deserialize_compressed(&*proof_bytes).unwrap();
+) -> Result<(), CliError>
+where
+    Claim: Stark<Fp = Fp, Fq = Fp, PublicCoin = CairoVerifierPublicCoin>,
+    Claim::AirConfig: AirConfig<PublicInputs = AirPublicInput<Fp>>,
+    <Claim::MerkleTree as MerkleTree>::Proof: Decommitment,
+{
+    let proof_bytes = fs::read(proof_path).map_err(|e| CliError::invalid_input(proof_path, e))?;
+    let proof = Proof::<Claim>::deserialize_compressed(&*proof_bytes)
+        .map_err(|e| CliError::invalid_input(proof_path, e))?;
+    let input = CairoVerifierInput::new(&claim, &proof);
+    let output_file =
+        File::create(output_path).map_err(|e| CliError::invalid_output(output_path, e))?;
+    serde_json::to_writer_pretty(output_file, &input)
+        .map_err(|e| CliError::invalid_output(output_path, e))?;
+    println!("Cairo verifier input written to {}", output_path.display());
+    Ok(())
+}
+
+/// Imports a proof in StarkWare's JSON proof format and verifies it
+fn verify_stone<Claim>(
+    required_security_bits: u8,
+    proof_path: &PathBuf,
+    claim: Claim,
+) -> Result<(), CliError>
+where
+    Claim: Stark<Fp = Fp, Fq = Fp>,
+    Claim::MerkleTree: ReadDecommitment<Root = Claim::Digest>,
+{
+    let proof_file = File::open(proof_path).map_err(|e| CliError::invalid_input(proof_path, e))?;
+    let stone_proof: StoneProof =
+        serde_json::from_reader(proof_file).map_err(|e| CliError::invalid_input(proof_path, e))?;
+    let proof = stone_proof
+        .into_proof(&claim)
+        .map_err(|e| CliError::invalid_input(proof_path, e))?;
     let now = Instant::now();
-    claim.verify(proof, required_security_bits.into()).unwrap();
+    claim
+        .verify(proof, required_security_bits.into())
+        .map_err(|e| CliError::VerificationFailed(format!("{e:?}")))?;
     println!("Proof verified in: {:?}", now.elapsed());
+    Ok(())
 }
 
 fn prove<Fp: PrimeField, Claim: Stark<Fp = Fp, Witness = CairoWitness<Fp>>>(
@@ -182,32 +829,423 @@ fn prove<Fp: PrimeField, Claim: Stark<Fp = Fp, Witness = CairoWitness<Fp>>>(
     private_input_path: &PathBuf,
     output_path: &PathBuf,
     claim: Claim,
-) {
-    let private_input_file =
-        File::open(private_input_path).expect("could not open private input file");
-    let private_input: AirPrivateInput = serde_json::from_reader(private_input_file).unwrap();
-
-    let trace_path = &private_input.trace_path;
-    let trace_file = File::open(trace_path).expect("could not open trace file");
-    let register_states = RegisterStates::from_reader(trace_file);
-
-    let memory_path = &private_input.memory_path;
-    let memory_file = File::open(memory_path).expect("could not open memory file");
-    let memory = Memory::from_reader(memory_file);
-
-    let witness = CairoWitness::new(private_input, register_states, memory);
+) -> Result<(), CliError>
+where
+    Claim::AirConfig: AirConfig<PublicInputs = AirPublicInput<Fp>>,
+{
+    let witness = read_witness(private_input_path, check_witness)?;
+    prove_witness(options, witness, output_path, claim)
+}
 
+fn prove_witness<Fp: PrimeField, Claim: Stark<Fp = Fp, Witness = CairoWitness<Fp>>>(
+    options: ProofOptions,
+    witness: CairoWitness<Fp>,
+    output_path: &PathBuf,
+    claim: Claim,
+) -> Result<(), CliError>
+where
+    Claim::AirConfig: AirConfig<PublicInputs = AirPublicInput<Fp>>,
+{
+    // trace generation assumes the witness is well formed
+    validate_witness(&claim.get_public_inputs(), &witness)?;
     let now = Instant::now();
-    let proof = pollster::block_on(claim.prove(options, witness)).unwrap();
+    let proof = pollster::block_on(claim.prove(options, witness))
+        .map_err(|e| CliError::ProvingFailed(format!("{e:?}")))?;
     println!("Proof generated in: {:?}", now.elapsed());
     let security_level_bits = proof.security_level_bits();
     println!("Proof security (conjectured): {security_level_bits}bit");
 
     let mut proof_bytes = Vec::new();
-    proof.serialize_compressed(&mut proof_bytes).unwrap();
+    proof
+        .serialize_compressed(&mut proof_bytes)
+        .map_err(|e| CliError::ProvingFailed(e.to_string()))?;
     println!("Proof size: {:?}KB", proof_bytes.len() / 1024);
-    let mut f = File::create(output_path).unwrap();
-    f.write_all(proof_bytes.as_slice()).unwrap();
-    f.flush().unwrap();
+    let mut f = File::create(output_path).map_err(|e| CliError::invalid_output(output_path, e))?;
+    f.write_all(proof_bytes.as_slice())
+        .and_then(|()| f.flush())
+        .map_err(|e| CliError::invalid_output(output_path, e))?;
     println!("Proof written to {}", output_path.as_path().display());
+    Ok(())
+}
+
+/// Writes the input of the simple bootloader for running the tasks
+fn batch_input(
+    tasks: &[PathBuf],
+    task_inputs: &[PathBuf],
+    output_path: &PathBuf,
+) -> Result<(), CliError> {
+    if !task_inputs.is_empty() && task_inputs.len() != tasks.len() {
+        return Err(CliError::invalid_input(
+            &task_inputs[0],
+            format!("expected an input for each of the {} tasks", tasks.len()),
+        ));
+    }
+
+    let mut batch = Batch::new();
+    for (i, task_path) in tasks.iter().enumerate() {
+        let program = read_json(task_path)?;
+        let program_input = match task_inputs.get(i) {
+            Some(input_path) => read_json(input_path)?,
+            None => serde_json::json!({}),
+        };
+        batch.add_task(program, program_input);
+    }
+    let output_file =
+        File::create(output_path).map_err(|e| CliError::invalid_output(output_path, e))?;
+    serde_json::to_writer_pretty(output_file, &batch.bootloader_input())
+        .map_err(|e| CliError::invalid_output(output_path, e))?;
+    println!("Bootloader input written to {}", output_path.display());
+    Ok(())
+}
+
+/// Proves an existing simple bootloader run and writes the facts of its tasks
+fn prove_bootloader_run<Claim>(
+    run_options: BootloaderRunOptions,
+    air_public_input_path: &PathBuf,
+    claim: Claim,
+) -> Result<(), CliError>
+where
+    Claim: Stark<Fp = Fp, Witness = CairoWitness<Fp>>,
+    Claim::AirConfig: AirConfig<PublicInputs = AirPublicInput<Fp>>,
+{
+    let BootloaderRunOptions {
+        tasks,
+        air_private_input,
+        output,
+        facts,
+        options,
+    } = run_options;
+
+    // the bootloader output is untrusted until it's matched with the tasks
+    let mut program_hashes = Vec::new();
+    for task_path in &tasks {
+        let program: CompiledProgram<Fp> = serde_json::from_value(read_json(task_path)?)
+            .map_err(|e| CliError::invalid_input(task_path, e))?;
+        let program_hash = fact::program_hash(&program, ProgramHashFn::Pedersen)
+            .map_err(|e| CliError::invalid_input(task_path, e))?;
+        program_hashes.push(U256::from(BigUint::from(program_hash)));
+    }
+    let batch_facts = BatchFacts::from_public_input(&claim.get_public_inputs())
+        .and_then(|batch_facts| {
+            (batch_facts.expect_program_hashes(&program_hashes)).map(|()| batch_facts)
+        })
+        .map_err(|e| CliError::invalid_input(air_public_input_path, e))?;
+
+    prove(options.into(), &air_private_input, &output, claim)?;
+
+    for (i, task) in batch_facts.tasks.iter().enumerate() {
+        println!(
+            "Task {i}: program hash {:#x}, output hash {:#x}, fact {:#x}",
+            task.program_hash, task.output_hash, task.fact
+        );
+    }
+    let facts_file = File::create(&facts).map_err(|e| CliError::invalid_output(&facts, e))?;
+    serde_json::to_writer_pretty(facts_file, &batch_facts)
+        .map_err(|e| CliError::invalid_output(&facts, e))?;
+    println!("Facts written to {}", facts.display());
+    Ok(())
+}
+
+/// Runs a program with the Cairo VM, writes the AIR public input (and
+/// optionally the witness) of the run and proves the execution
+fn run_program(
+    program_path: &PathBuf,
+    air_public_input_path: &PathBuf,
+    verifier: Option<Verifier>,
+    hash: Option<CommitmentHash>,
+    command: Command,
+) -> Result<(), CliError> {
+    let Command::Run {
+        layout,
+        max_steps,
+        output,
+        witness_dir,
+        options,
+    } = command
+    else {
+        unreachable!("only runs are handled")
+    };
+
+    let program_json = read_json(program_path)?;
+    let prime: String = serde_json::from_value(program_json["prime"].clone())
+        .map_err(|e| CliError::invalid_input(program_path, e))?;
+    if prime.to_lowercase() != STARKWARE_PRIME_HEX_STR {
+        return Err(CliError::UnsupportedPrime(prime));
+    }
+    let program: CompiledProgram<Fp> = serde_json::from_value(program_json)
+        .map_err(|e| CliError::invalid_input(program_path, e))?;
+    let is_supported = |name: &&String| vm::SUPPORTED_BUILTINS.contains(&name.as_str());
+    if let Some(name) = program.builtins.iter().find(|name| !is_supported(name)) {
+        return Err(CliError::UnsupportedBuiltin(name.clone()));
+    }
+    let params = match layout {
+        Layout::Starknet => layouts::starknet::VM_PARAMS,
+        Layout::Recursive => layouts::recursive::VM_PARAMS,
+        layout => return Err(CliError::UnsupportedLayout(layout)),
+    };
+    // resolve the claim before running so bad selections fail fast
+    let selection = ClaimSelection::resolve(layout, verifier, hash)?;
+
+    let now = Instant::now();
+    let Execution {
+        register_states,
+        memory,
+        air_public_input,
+        mut air_private_input,
+    } = vm::run(&program, &params, max_steps)
+        .map_err(|e| CliError::invalid_input(program_path, e))?;
+    println!("Program run in: {:?}", now.elapsed());
+
+    let air_public_input_file = File::create(air_public_input_path)
+        .map_err(|e| CliError::invalid_output(air_public_input_path, e))?;
+    air_public_input
+        .to_writer(air_public_input_file)
+        .map_err(|e| CliError::invalid_output(air_public_input_path, e))?;
+    println!(
+        "AIR public input written to {}",
+        air_public_input_path.display()
+    );
+
+    if let Some(witness_dir) = witness_dir {
+        air_private_input.trace_path = witness_dir.join("trace.bin");
+        air_private_input.memory_path = witness_dir.join("memory.bin");
+        let private_input_path = witness_dir.join("air-private-input.json");
+        let create =
+            |path: &PathBuf| File::create(path).map_err(|e| CliError::invalid_output(path, e));
+        let trace_path = &air_private_input.trace_path;
+        register_states
+            .to_writer(create(trace_path)?)
+            .map_err(|e| CliError::invalid_output(trace_path, e))?;
+        let memory_path = &air_private_input.memory_path;
+        memory
+            .to_writer(create(memory_path)?)
+            .map_err(|e| CliError::invalid_output(memory_path, e))?;
+        air_private_input
+            .to_writer(create(&private_input_path)?, layout)
+            .map_err(|e| CliError::invalid_output(&private_input_path, e))?;
+        println!("Witness written to {}", witness_dir.display());
+    }
+
+    let fact = Fact::from_run(&program, &air_public_input)
+        .map_err(|e| CliError::invalid_input(program_path, e))?;
+    let witness = CairoWitness::new(air_private_input, register_states, memory);
+    let visitor = RunVisitor {
+        options: options.into(),
+        witness,
+        output,
+    };
+    visit_claim(selection, program, air_public_input, visitor)??;
+    print_fact(&fact);
+    Ok(())
+}
+
+fn parse_layout(layout: &str) -> Result<Layout, String> {
+    serde_json::from_value(serde_json::Value::String(layout.to_string()))
+        .map_err(|_| format!("unknown layout {layout}"))
+}
+
+fn read_json(path: &PathBuf) -> Result<serde_json::Value, CliError> {
+    let file = File::open(path).map_err(|e| CliError::invalid_input(path, e))?;
+    serde_json::from_reader(file).map_err(|e| CliError::invalid_input(path, e))
+}
+
+/// Writes a proof in StarkWare's annotated JSON proof format
+fn export_annotated<Claim: Stark<Fp = Fp, Fq = Fp>>(
+    proof_path: &PathBuf,
+    output_path: &PathBuf,
+    claim: Claim,
+) -> Result<(), CliError>
+where
+    <Claim::MerkleTree as MerkleTree>::Proof: Decommitment,
+{
+    let proof_bytes = fs::read(proof_path).map_err(|e| CliError::invalid_input(proof_path, e))?;
+    let proof = Proof::<Claim>::deserialize_compressed(&*proof_bytes)
+        .map_err(|e| CliError::invalid_input(proof_path, e))?;
+    let annotated_proof = AnnotatedProof::new(&claim, &proof);
+    let output_file =
+        File::create(output_path).map_err(|e| CliError::invalid_output(output_path, e))?;
+    serde_json::to_writer_pretty(output_file, &annotated_proof)
+        .map_err(|e| CliError::invalid_output(output_path, e))?;
+    println!("Annotated proof written to {}", output_path.display());
+    Ok(())
+}
+
+/// Writes the calldata of each transaction needed to verify a proof with
+/// StarkWare's Ethereum verifier
+fn export_evm<Claim>(
+    proof_path: &PathBuf,
+    output_path: &PathBuf,
+    task_metadata_path: &PathBuf,
+    cairo_verifier_id: u64,
+    claim: Claim,
+) -> Result<(), CliError>
+where
+    Claim: Stark<Fp = Fp, Fq = Fp, PublicCoin = SolidityVerifierPublicCoin>,
+    Claim::AirConfig: CairoAirConfig + AirConfig<PublicInputs = AirPublicInput<Fp>>,
+    <Claim::MerkleTree as MerkleTree>::Proof: Decommitment,
+{
+    let proof_bytes = fs::read(proof_path).map_err(|e| CliError::invalid_input(proof_path, e))?;
+    let proof = Proof::<Claim>::deserialize_compressed(&*proof_bytes)
+        .map_err(|e| CliError::invalid_input(proof_path, e))?;
+    let task_metadata_file = File::open(task_metadata_path)
+        .map_err(|e| CliError::invalid_input(task_metadata_path, e))?;
+    let task_metadata: Vec<U256> = serde_json::from_reader(task_metadata_file)
+        .map_err(|e| CliError::invalid_input(task_metadata_path, e))?;
+
+    let split_proof = SplitProof::new(
+        &claim,
+        &proof,
+        &task_metadata,
+        U256::from(cairo_verifier_id),
+    )
+    .map_err(|e| CliError::invalid_input(proof_path, e))?;
+    let output_file =
+        File::create(output_path).map_err(|e| CliError::invalid_output(output_path, e))?;
+    serde_json::to_writer_pretty(output_file, &split_proof)
+        .map_err(|e| CliError::invalid_output(output_path, e))?;
+    println!(
+        "Calldata for {} merkle statements and {} FRI statements written to {}",
+        split_proof.verify_merkle.len(),
+        split_proof.verify_fri.len(),
+        output_path.display()
+    );
+    Ok(())
+}
+
+/// Evaluates every AIR constraint on the execution trace and prints the ones
+/// that don't vanish along with the trace cells they use
+fn debug_constraints<Fp: PrimeField, Claim: Stark<Fp = Fp, Witness = CairoWitness<Fp>>>(
+    options: ProofOptions,
+    private_input_path: &PathBuf,
+    claim: Claim,
+) -> Result<(), CliError>
+where
+    Claim::AirConfig: ConstraintRegistry + AirConfig<PublicInputs = AirPublicInput<Fp>>,
+{
+    let witness = read_witness(private_input_path, check_witness)?;
+    validate_witness(&claim.get_public_inputs(), &witness)?;
+    let trace = claim.generate_trace(witness);
+    let base_trace = trace.base_columns();
+
+    let air = Air::new(base_trace.num_rows(), claim.get_public_inputs(), options);
+    let mut public_coin = claim.gen_public_coin(&air);
+    let challenges = air.gen_challenges(&mut public_coin);
+    let hints = air.gen_hints(&challenges);
+    let extension_trace = trace.build_extension_columns(&challenges);
+
+    let failures = failing_constraints::<Claim::AirConfig>(
+        &challenges,
+        &hints,
+        base_trace,
+        extension_trace.as_ref(),
+    );
+    if failures.is_empty() {
+        println!("All constraints hold");
+        return Ok(());
+    }
+
+    for failure in &failures {
+        let ConstraintFailure {
+            index,
+            name,
+            row,
+            cells,
+        } = failure;
+        println!("constraint {index} ({name}) does not hold at row {row}");
+        for ((column, offset), value) in cells {
+            println!("    column {column} row {row}{offset:+}: {value}");
+        }
+    }
+    Err(CliError::InvalidWitness(format!(
+        "{} constraint evaluations do not vanish",
+        failures.len()
+    )))
+}
+
+/// Reports every inconsistency in the witness
+fn check<Fp: PrimeField>(
+    air_public_input: &AirPublicInput<Fp>,
+    private_input_path: &PathBuf,
+) -> Result<(), CliError> {
+    let witness = read_witness(private_input_path, |_, _| Ok(()))?;
+    let violations = layouts::check::check_witness(air_public_input, &witness);
+    if violations.is_empty() {
+        println!("Witness is valid");
+        return Ok(());
+    }
+
+    for violation in &violations {
+        println!("{violation}");
+    }
+    Err(CliError::InvalidWitness(format!(
+        "found {} violations",
+        violations.len()
+    )))
+}
+
+/// Replays the execution and fails on the first inconsistency in the witness.
+/// Run before generating the trace, which assumes a well formed witness.
+fn validate_witness<Fp: PrimeField>(
+    air_public_input: &AirPublicInput<Fp>,
+    witness: &CairoWitness<Fp>,
+) -> Result<(), CliError> {
+    let violations = layouts::check::check_witness(air_public_input, witness);
+    match violations.as_slice() {
+        [] => Ok(()),
+        [violation] => Err(CliError::InvalidWitness(violation.to_string())),
+        [violation, ..] => Err(CliError::InvalidWitness(format!(
+            "{violation} (and {} more violations, run `check` to list them)",
+            violations.len() - 1
+        ))),
+    }
+}
+
+/// Reads the witness referenced by the AIR private input. `validate` is run on
+/// the trace and memory before the witness is constructed.
+fn read_witness<Fp: PrimeField>(
+    private_input_path: &PathBuf,
+    validate: impl FnOnce(&RegisterStates, &Memory<Fp>) -> Result<(), CliError>,
+) -> Result<CairoWitness<Fp>, CliError> {
+    let private_input_file = File::open(private_input_path)
+        .map_err(|e| CliError::invalid_input(private_input_path, e))?;
+    let private_input: AirPrivateInput = serde_json::from_reader(private_input_file)
+        .map_err(|e| CliError::invalid_input(private_input_path, e))?;
+
+    let trace_path = &private_input.trace_path;
+    let trace_file = File::open(trace_path).map_err(|e| CliError::invalid_input(trace_path, e))?;
+    let register_states = RegisterStates::from_reader(trace_file)
+        .map_err(|e| CliError::InvalidWitness(format!("{}: {e}", trace_path.display())))?;
+
+    let memory_path = &private_input.memory_path;
+    let memory_file =
+        File::open(memory_path).map_err(|e| CliError::invalid_input(memory_path, e))?;
+    let memory = Memory::from_reader(memory_file)
+        .map_err(|e| CliError::InvalidWitness(format!("{}: {e}", memory_path.display())))?;
+
+    validate(&register_states, &memory)?;
+    Ok(CairoWitness::new(private_input, register_states, memory))
+}
+
+/// Cheap checks that catch common witness problems before proving
+fn check_witness<F: Field>(
+    register_states: &RegisterStates,
+    memory: &Memory<F>,
+) -> Result<(), CliError> {
+    let num_steps = register_states.len();
+    if !num_steps.is_power_of_two() {
+        return Err(CliError::InvalidWitness(format!(
+            "number of steps must be a power of two but is {num_steps}"
+        )));
+    }
+
+    for (step, registers) in register_states.iter().enumerate() {
+        if memory.get(registers.pc).is_none() {
+            return Err(CliError::InvalidWitness(format!(
+                "no instruction in memory at pc={} (step {step})",
+                registers.pc
+            )));
+        }
+    }
+
+    Ok(())
 }
@@ -1,6 +1,47 @@
 pub mod blake2s;
 pub mod keccak;
 pub mod pedersen;
+pub mod poseidon;
+
+use ark_serialize::CanonicalDeserialize;
+use ark_serialize::CanonicalSerialize;
+use ministark::hash::Digest;
+use ministark_gpu::fields::p3618502788666131213697322783095070105623107215331596699973092056135872020481::ark::Fp;
+use num_bigint::BigUint;
+use ruint::aliases::U256;
+use std::fmt::Display;
+use std::ops::Deref;
+
+/// Digest of the field-friendly hash functions (Pedersen and Poseidon)
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, CanonicalDeserialize, CanonicalSerialize)]
+pub struct FieldDigest(pub Fp);
+
+impl Display for FieldDigest {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        self.0.fmt(f)
+    }
+}
+
+impl Digest for FieldDigest {
+    fn as_bytes(&self) -> [u8; 32] {
+        let num = U256::from(BigUint::from(self.0));
+        num.to_be_bytes::<32>()
+    }
+}
+
+impl Deref for FieldDigest {
+    type Target = Fp;
+
+    fn deref(&self) -> &Self::Target {
+        &self.0
+    }
+}
+
+impl From<Fp> for FieldDigest {
+    fn from(value: Fp) -> Self {
+        FieldDigest(value)
+    }
+}
 
 #[inline]
 pub fn mask_least_significant_bytes<const N_UNMASKED_BYTES: u32>(bytes: &mut [u8]) {
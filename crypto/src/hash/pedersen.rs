@@ -1,45 +1,12 @@
-use std::fmt::Display;
-use std::ops::Deref;
-use ark_serialize::CanonicalDeserialize;
-use ark_serialize::CanonicalSerialize;
+use super::FieldDigest;
 use builtins::pedersen::pedersen_hash;
-use ministark::hash::Digest;
 use ministark::hash::ElementHashFn;
 use ark_ff::Field;
 use ministark::hash::HashFn;
 use ministark_gpu::fields::p3618502788666131213697322783095070105623107215331596699973092056135872020481::ark::Fp;
-use num_bigint::BigUint;
-use ruint::aliases::U256;
 
-#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, CanonicalDeserialize, CanonicalSerialize)]
-pub struct PedersenDigest(pub Fp);
-
-impl Display for PedersenDigest {
-    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        self.0.fmt(f)
-    }
-}
-
-impl Digest for PedersenDigest {
-    fn as_bytes(&self) -> [u8; 32] {
-        let num = U256::from(BigUint::from(self.0));
-        num.to_be_bytes::<32>()
-    }
-}
-
-impl Deref for PedersenDigest {
-    type Target = Fp;
-
-    fn deref(&self) -> &Self::Target {
-        &self.0
-    }
-}
-
-impl From<Fp> for PedersenDigest {
-    fn from(value: Fp) -> Self {
-        PedersenDigest(value)
-    }
-}
+/// Pedersen digests are field elements
+pub type PedersenDigest = FieldDigest;
 
 pub struct PedersenHashFn;
 
@@ -56,11 +23,11 @@ impl HashFn for PedersenHashFn {
     }
 
     fn merge(v0: &PedersenDigest, v1: &PedersenDigest) -> PedersenDigest {
-        PedersenDigest(pedersen_hash(**v0, **v1))
+        FieldDigest(pedersen_hash(**v0, **v1))
     }
 
     fn merge_with_int(seed: &PedersenDigest, value: u64) -> PedersenDigest {
-        PedersenDigest(pedersen_hash(**seed, value.into()))
+        FieldDigest(pedersen_hash(**seed, value.into()))
     }
 }
 
@@ -72,6 +39,6 @@ impl ElementHashFn<Fp> for PedersenHashFn {
             curr_hash = pedersen_hash(curr_hash, v);
             num_items += 1;
         }
-        PedersenDigest(pedersen_hash(curr_hash, num_items.into()))
+        FieldDigest(pedersen_hash(curr_hash, num_items.into()))
     }
 }
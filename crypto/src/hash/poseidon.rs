@@ -0,0 +1,39 @@
+use super::FieldDigest;
+use builtins::poseidon::poseidon_hash;
+use builtins::poseidon::poseidon_hash_many;
+use ministark::hash::ElementHashFn;
+use ministark::hash::HashFn;
+use ministark_gpu::fields::p3618502788666131213697322783095070105623107215331596699973092056135872020481::ark::Fp;
+
+/// Poseidon hash function used by StarkWare's Cairo verifier for layouts with
+/// Poseidon commitments. Digests are field elements, the same as
+/// [PedersenHashFn](super::pedersen::PedersenHashFn) digests.
+pub struct PoseidonHashFn;
+
+impl HashFn for PoseidonHashFn {
+    type Digest = FieldDigest;
+    const COLLISION_RESISTANCE: u32 = 125;
+
+    fn hash(_bytes: impl IntoIterator<Item = u8>) -> FieldDigest {
+        unreachable!()
+    }
+
+    fn hash_chunks<'a>(_chunks: impl IntoIterator<Item = &'a [u8]>) -> Self::Digest {
+        unreachable!()
+    }
+
+    fn merge(v0: &FieldDigest, v1: &FieldDigest) -> FieldDigest {
+        FieldDigest(poseidon_hash(**v0, **v1))
+    }
+
+    fn merge_with_int(seed: &FieldDigest, value: u64) -> FieldDigest {
+        FieldDigest(poseidon_hash(**seed, value.into()))
+    }
+}
+
+impl ElementHashFn<Fp> for PoseidonHashFn {
+    fn hash_elements(elements: impl IntoIterator<Item = Fp>) -> FieldDigest {
+        let elements = elements.into_iter().collect::<Vec<Fp>>();
+        FieldDigest(poseidon_hash_many(&elements))
+    }
+}
@@ -8,6 +8,7 @@ use ark_serialize::CanonicalSerialize;
 use ark_serialize::Valid;
 use blake2::Blake2s256;
 use ministark::Matrix;
+use ministark::hash::Digest;
 use ministark::hash::ElementHashFn;
 use ministark::hash::HashFn;
 use ministark::merkle::HashedLeafConfig;
@@ -23,7 +24,12 @@ use ministark_gpu::fields::p3618502788666131213697322783095070105623107215331596
 use mixed::FriendlyMerkleTreeConfig;
 use mixed::MixedHashMerkleTreeImpl;
 use mixed::MixedMerkleDigest;
+use num_bigint::BigUint;
 use crate::hash::blake2s::MaskedBlake2sHashFn;
+use crate::utils::from_montgomery;
+use crate::utils::to_montgomery;
+use ruint::aliases::U256;
+use sha3::Keccak256;
 
 /// Friendly merkle tree is used as the merkle tree when generating recursive
 /// STARK proofs.
@@ -416,6 +422,294 @@ impl<H: ElementHashFn<Fp>> CanonicalDeserialize for LeafVariantMerkleTreeProof<H
     }
 }
 
+/// Merkle proof that can be written as a decommitment in StarkWare's proof
+/// format. Field elements are written in Montgomery form to match the channel.
+pub trait Decommitment {
+    /// Leaves of the queried rows as 32 byte words
+    fn leaf_words(&self) -> Vec<[u8; 32]>;
+
+    /// Sibling leaves followed by the authentication nodes as 32 byte words
+    fn authentication_words(&self) -> Vec<[u8; 32]>;
+
+    /// Lowest nodes committing to the rows at the given sorted positions as 32
+    /// byte words. These are the leaves of a multi column tree. Leaves of a
+    /// single column tree are values so its lowest nodes are the hashes of
+    /// pairs of leaves (a "package") with one node per queried package.
+    fn queried_node_words(&self, positions: &[usize]) -> Vec<[u8; 32]>;
+}
+
+impl<H: ElementHashFn<Fp>> Decommitment for LeafVariantMerkleTreeProof<H> {
+    fn leaf_words(&self) -> Vec<[u8; 32]> {
+        match self {
+            Self::Hashed(proof) => proof.initial_leaves.iter().map(Digest::as_bytes).collect(),
+            Self::Unhashed(proof) => proof
+                .initial_leaves
+                .iter()
+                .map(field_element_word)
+                .collect(),
+        }
+    }
+
+    fn authentication_words(&self) -> Vec<[u8; 32]> {
+        match self {
+            Self::Hashed(proof) => view_words(proof, Digest::as_bytes),
+            Self::Unhashed(proof) => view_words(proof, field_element_word),
+        }
+    }
+
+    fn queried_node_words(&self, positions: &[usize]) -> Vec<[u8; 32]> {
+        match self {
+            Self::Hashed(_) => self.leaf_words(),
+            Self::Unhashed(proof) => package_words::<H>(proof, positions),
+        }
+    }
+}
+
+impl<H: ElementHashFn<Fp>> Decommitment for FriendlyMerkleTreeProof<H> {
+    fn leaf_words(&self) -> Vec<[u8; 32]> {
+        match self {
+            Self::MultiCol(proof) => proof.initial_leaves.iter().map(Digest::as_bytes).collect(),
+            Self::SingleCol(proof) => proof
+                .initial_leaves
+                .iter()
+                .map(field_element_word)
+                .collect(),
+        }
+    }
+
+    fn authentication_words(&self) -> Vec<[u8; 32]> {
+        match self {
+            Self::MultiCol(proof) => view_words(proof, Digest::as_bytes),
+            Self::SingleCol(proof) => view_words(proof, field_element_word),
+        }
+    }
+
+    fn queried_node_words(&self, positions: &[usize]) -> Vec<[u8; 32]> {
+        match self {
+            Self::MultiCol(_) => self.leaf_words(),
+            Self::SingleCol(proof) => package_words::<H>(proof, positions),
+        }
+    }
+}
+
+/// Merkle tree whose roots and proofs can be read back from the words written
+/// by [Decommitment]
+pub trait ReadDecommitment: MerkleTree {
+    /// Reads the root of a tree committing to a matrix with the given number
+    /// of columns
+    fn read_root(num_columns: usize, word: [u8; 32]) -> Self::Root;
+
+    /// Reads the proof for the rows at the given sorted and deduplicated
+    /// positions. Returns `None` if there aren't enough words.
+    fn read_decommitment(
+        rows: &[&[Fp]],
+        positions: &[usize],
+        height: u32,
+        words: &mut impl Iterator<Item = [u8; 32]>,
+    ) -> Option<Self::Proof>;
+}
+
+impl<H: ElementHashFn<Fp, Digest = SerdeOutput<Keccak256>>> ReadDecommitment
+    for LeafVariantMerkleTree<H>
+{
+    fn read_root(_num_columns: usize, word: [u8; 32]) -> H::Digest {
+        SerdeOutput::new(word.into())
+    }
+
+    fn read_decommitment(
+        rows: &[&[Fp]],
+        positions: &[usize],
+        height: u32,
+        words: &mut impl Iterator<Item = [u8; 32]>,
+    ) -> Option<LeafVariantMerkleTreeProof<H>> {
+        let (num_sibling_leaves, node_depths) = decommitment_size(positions, height);
+        let num_nodes = node_depths.len();
+        let read_digest = |word: [u8; 32]| SerdeOutput::new(word.into());
+        Some(if rows.iter().all(|row| row.len() == 1) {
+            let initial_leaves = rows.iter().map(|row| row[0]).collect();
+            let sibling_leaves = read_words(words, num_sibling_leaves, field_element)?;
+            let nodes = read_words(words, num_nodes, read_digest)?;
+            LeafVariantMerkleTreeProof::Unhashed(MerkleView {
+                initial_leaves,
+                sibling_leaves,
+                nodes,
+                height,
+            })
+        } else {
+            let initial_leaves = rows
+                .iter()
+                .map(|row| H::hash_elements(row.iter().copied()))
+                .collect();
+            let sibling_leaves = read_words(words, num_sibling_leaves, read_digest)?;
+            let nodes = read_words(words, num_nodes, read_digest)?;
+            LeafVariantMerkleTreeProof::Hashed(MerkleView {
+                initial_leaves,
+                sibling_leaves,
+                nodes,
+                height,
+            })
+        })
+    }
+}
+
+/// Algebraic digests are written as canonical big-endian integers. Nodes in
+/// the top `N_FRIENDLY_LAYERS` layers of a multi column tree are algebraic
+/// digests and the nodes below them are Blake2s digests.
+impl<const N_FRIENDLY_LAYERS: u32, H: ElementHashFn<Fp>> ReadDecommitment
+    for FriendlyMerkleTree<N_FRIENDLY_LAYERS, H>
+where
+    H::Digest: From<Fp>,
+{
+    fn read_root(num_columns: usize, word: [u8; 32]) -> Self::Root {
+        if num_columns == 1 || N_FRIENDLY_LAYERS > 0 {
+            MixedMerkleDigest::HighLevel(friendly_digest(word))
+        } else {
+            MixedMerkleDigest::LowLevel(SerdeOutput::new(word.into()))
+        }
+    }
+
+    fn read_decommitment(
+        rows: &[&[Fp]],
+        positions: &[usize],
+        height: u32,
+        words: &mut impl Iterator<Item = [u8; 32]>,
+    ) -> Option<FriendlyMerkleTreeProof<H>> {
+        let (num_sibling_leaves, node_depths) = decommitment_size(positions, height);
+        let num_nodes = node_depths.len();
+        Some(if rows.iter().all(|row| row.len() == 1) {
+            let initial_leaves = rows.iter().map(|row| row[0]).collect();
+            let sibling_leaves = read_words(words, num_sibling_leaves, field_element)?;
+            let nodes = read_words(words, num_nodes, friendly_digest)?;
+            FriendlyMerkleTreeProof::SingleCol(MerkleView {
+                initial_leaves,
+                sibling_leaves,
+                nodes,
+                height,
+            })
+        } else {
+            let initial_leaves = rows
+                .iter()
+                .map(|row| MaskedBlake2sHashFn::<20>::hash_elements(row.iter().copied()))
+                .collect();
+            let sibling_leaves = read_words(words, num_sibling_leaves, |word| {
+                SerdeOutput::new(word.into())
+            })?;
+            let mut nodes = Vec::with_capacity(num_nodes);
+            for depth in node_depths {
+                let word = words.next()?;
+                nodes.push(if depth < N_FRIENDLY_LAYERS {
+                    MixedMerkleDigest::HighLevel(friendly_digest(word))
+                } else {
+                    MixedMerkleDigest::LowLevel(SerdeOutput::new(word.into()))
+                });
+            }
+            FriendlyMerkleTreeProof::MultiCol(MerkleView {
+                initial_leaves,
+                sibling_leaves,
+                nodes,
+                height,
+            })
+        })
+    }
+}
+
+/// Number of sibling leaves needed to decommit the leaves at the given
+/// positions and the depth (distance from the root) of each authentication
+/// node.
+fn decommitment_size(positions: &[usize], height: u32) -> (usize, Vec<u32>) {
+    let nodes = decommitment_nodes(positions, height);
+    let num_sibling_leaves = nodes.iter().filter(|(depth, _)| *depth == height).count();
+    let node_depths = nodes[num_sibling_leaves..]
+        .iter()
+        .map(|(depth, _)| *depth)
+        .collect();
+    (num_sibling_leaves, node_depths)
+}
+
+/// Depth (distance from the root) and position within its layer of each word
+/// of the authentication path of the leaves at the given sorted and
+/// deduplicated positions. Words are in the order they're read by
+/// [ReadDecommitment] i.e. sibling leaves then nodes layer by layer. Siblings
+/// that are also being decommitted (or can be computed from them) aren't part
+/// of the decommitment.
+///
+/// StarkWare's tooling numbers nodes from the root (node 1) so the node index
+/// of a word is `2^depth + position`.
+pub fn decommitment_nodes(positions: &[usize], height: u32) -> Vec<(u32, usize)> {
+    let mut nodes = Vec::new();
+    let mut layer = positions.to_vec();
+    for level in 0..height {
+        let mut parents = Vec::new();
+        let mut i = 0;
+        while i < layer.len() {
+            let position = layer[i];
+            if layer.get(i + 1) == Some(&(position ^ 1)) {
+                i += 2;
+            } else {
+                nodes.push((height - level, position ^ 1));
+                i += 1;
+            }
+            parents.push(position / 2);
+        }
+        layer = parents;
+    }
+    nodes
+}
+
+fn read_words<T>(
+    words: &mut impl Iterator<Item = [u8; 32]>,
+    n: usize,
+    f: impl Fn([u8; 32]) -> T,
+) -> Option<Vec<T>> {
+    let values = words.take(n).map(f).collect::<Vec<T>>();
+    (values.len() == n).then_some(values)
+}
+
+fn view_words<N: Digest, L>(
+    view: &MerkleView<N, L>,
+    leaf_word: impl Fn(&L) -> [u8; 32],
+) -> Vec<[u8; 32]> {
+    let sibling_leaves = view.sibling_leaves.iter().map(leaf_word);
+    let nodes = view.nodes.iter().map(Digest::as_bytes);
+    sibling_leaves.chain(nodes).collect()
+}
+
+/// Hashes of the packages holding the leaves at the given sorted positions.
+/// Sibling leaves are taken from the view unless they're also queried.
+fn package_words<H: ElementHashFn<Fp>>(
+    view: &MerkleView<H::Digest, Fp>,
+    positions: &[usize],
+) -> Vec<[u8; 32]> {
+    let mut sibling_leaves = view.sibling_leaves.iter();
+    let mut leaves = zip(positions, &view.initial_leaves).peekable();
+    let mut words = Vec::new();
+    while let Some((&position, &leaf)) = leaves.next() {
+        let sibling = match leaves.next_if(|(&p, _)| p == position ^ 1) {
+            Some((_, &sibling)) => sibling,
+            None => *sibling_leaves.next().unwrap(),
+        };
+        let package = if position % 2 == 0 {
+            [leaf, sibling]
+        } else {
+            [sibling, leaf]
+        };
+        words.push(H::hash_elements(package).as_bytes());
+    }
+    words
+}
+
+fn field_element_word(v: &Fp) -> [u8; 32] {
+    to_montgomery(*v).to_be_bytes::<32>()
+}
+
+fn field_element(word: [u8; 32]) -> Fp {
+    from_montgomery(U256::from_be_bytes(word))
+}
+
+fn friendly_digest<D: From<Fp>>(word: [u8; 32]) -> D {
+    Fp::from(BigUint::from_bytes_be(&word)).into()
+}
+
 #[derive(Default)]
 pub struct UnhashedLeafConfig<H>(PhantomData<H>);
 
@@ -438,13 +732,20 @@ impl<H: ElementHashFn<Fp>> MerkleTreeConfig for UnhashedLeafConfig<H> {
 
 #[cfg(test)]
 mod tests {
+    use super::decommitment_nodes;
     use super::FriendlyMerkleTree;
     use crate::hash::keccak::Keccak256HashFn;
     use crate::hash::pedersen::PedersenHashFn;
+    use crate::merkle::Decommitment;
     use crate::merkle::LeafVariantMerkleTree;
+    use crate::merkle::ReadDecommitment;
+    use crate::utils::to_montgomery;
     use ark_ff::MontFp as Fp;
     use blake2::Blake2s256;
     use digest::Output;
+    use ministark::hash::Digest;
+    use ministark::hash::ElementHashFn;
+    use ministark::hash::HashFn;
     use ministark::merkle::Error;
     use ministark::merkle::MatrixMerkleTree;
     use ministark::merkle::MerkleTree;
@@ -501,6 +802,204 @@ mod tests {
         TestMerkleTree::verify(&root, proof, &[REVEAL_INDEX])
     }
 
+    #[test]
+    fn leaf_variant_decommitment_round_trip() -> Result<(), Error> {
+        const REVEAL_INDICES: [usize; 3] = [1, 2, 6];
+        type TestMerkleTree = LeafVariantMerkleTree<Keccak256HashFn>;
+        let col = [
+            Fp!("0"),
+            Fp!("1"),
+            Fp!("2"),
+            Fp!("3"),
+            Fp!("4"),
+            Fp!("5"),
+            Fp!("6"),
+            Fp!("7"),
+        ];
+        let matrix = Matrix::new(vec![
+            col.to_vec_in(GpuAllocator),
+            col.to_vec_in(GpuAllocator),
+        ]);
+        let merkle_tree = TestMerkleTree::from_matrix(&matrix);
+        let root = merkle_tree.root();
+        let proof = merkle_tree.prove_rows(&REVEAL_INDICES)?;
+        let rows = REVEAL_INDICES.map(|i| [col[i], col[i]]);
+        let row_slices = rows.each_ref().map(|row| row.as_slice());
+
+        let mut words = proof.authentication_words().into_iter();
+        let read_proof =
+            TestMerkleTree::read_decommitment(&row_slices, &REVEAL_INDICES, 3, &mut words).unwrap();
+
+        assert!(words.next().is_none());
+        TestMerkleTree::verify_rows(&root, &REVEAL_INDICES, &rows, read_proof)
+    }
+
+    #[test]
+    fn friendly_decommitment_round_trip() -> Result<(), Error> {
+        // one friendly layer so the authentication path has Blake2s nodes
+        // below a Pedersen root
+        const REVEAL_INDICES: [usize; 3] = [1, 2, 6];
+        type TestMerkleTree = FriendlyMerkleTree<1, PedersenHashFn>;
+        let col = [
+            Fp!("0"),
+            Fp!("1"),
+            Fp!("2"),
+            Fp!("3"),
+            Fp!("4"),
+            Fp!("5"),
+            Fp!("6"),
+            Fp!("7"),
+        ];
+        let matrix = Matrix::new(vec![
+            col.to_vec_in(GpuAllocator),
+            col.to_vec_in(GpuAllocator),
+        ]);
+        let merkle_tree = TestMerkleTree::from_matrix(&matrix);
+        let root = TestMerkleTree::read_root(2, merkle_tree.root().as_bytes());
+        let proof = merkle_tree.prove_rows(&REVEAL_INDICES)?;
+        let rows = REVEAL_INDICES.map(|i| [col[i], col[i]]);
+        let row_slices = rows.each_ref().map(|row| row.as_slice());
+
+        let mut words = proof.authentication_words().into_iter();
+        let read_proof =
+            TestMerkleTree::read_decommitment(&row_slices, &REVEAL_INDICES, 3, &mut words).unwrap();
+
+        assert!(words.next().is_none());
+        TestMerkleTree::verify_rows(&root, &REVEAL_INDICES, &rows, read_proof)
+    }
+
+    #[test]
+    fn friendly_single_column_decommitment_round_trip() -> Result<(), Error> {
+        const REVEAL_INDICES: [usize; 2] = [1, 6];
+        type TestMerkleTree = FriendlyMerkleTree<1, PedersenHashFn>;
+        let col = [
+            Fp!("0"),
+            Fp!("1"),
+            Fp!("2"),
+            Fp!("3"),
+            Fp!("4"),
+            Fp!("5"),
+            Fp!("6"),
+            Fp!("7"),
+        ];
+        let matrix = Matrix::new(vec![col.to_vec_in(GpuAllocator)]);
+        let merkle_tree = TestMerkleTree::from_matrix(&matrix);
+        let root = TestMerkleTree::read_root(1, merkle_tree.root().as_bytes());
+        let proof = merkle_tree.prove_rows(&REVEAL_INDICES)?;
+        let rows = REVEAL_INDICES.map(|i| [col[i]]);
+        let row_slices = rows.each_ref().map(|row| row.as_slice());
+
+        let mut words = proof.authentication_words().into_iter();
+        let read_proof =
+            TestMerkleTree::read_decommitment(&row_slices, &REVEAL_INDICES, 3, &mut words).unwrap();
+
+        assert!(words.next().is_none());
+        TestMerkleTree::verify_rows(&root, &REVEAL_INDICES, &rows, read_proof)
+    }
+
+    #[test]
+    fn decommitment_words_are_in_verifier_order() -> Result<(), Error> {
+        // StarkWare's verifiers read the authentication path layer by layer
+        // starting from the leaves with the nodes of each layer in ascending
+        // order. Revealing rows 1 and 6 of 8 needs rows 0 and 7 and then the
+        // nodes above rows 2..4 and 4..6.
+        const REVEAL_INDICES: [usize; 2] = [1, 6];
+        type H = Keccak256HashFn;
+        let col = [
+            Fp!("0"),
+            Fp!("1"),
+            Fp!("2"),
+            Fp!("3"),
+            Fp!("4"),
+            Fp!("5"),
+            Fp!("6"),
+            Fp!("7"),
+        ];
+        let single_column = Matrix::new(vec![col.to_vec_in(GpuAllocator)]);
+        let multiple_columns = Matrix::new(vec![
+            col.to_vec_in(GpuAllocator),
+            col.to_vec_in(GpuAllocator),
+        ]);
+        let row_hash = |i: usize| H::hash_elements([col[i], col[i]]);
+        let node = |a: usize, b: usize| H::merge(&row_hash(a), &row_hash(b));
+
+        let single_column_words = LeafVariantMerkleTree::<H>::from_matrix(&single_column)
+            .prove_rows(&REVEAL_INDICES)?
+            .authentication_words();
+        let multiple_columns_words = LeafVariantMerkleTree::<H>::from_matrix(&multiple_columns)
+            .prove_rows(&REVEAL_INDICES)?
+            .authentication_words();
+
+        assert_eq!(
+            single_column_words,
+            [
+                to_montgomery(col[0]).to_be_bytes::<32>(),
+                to_montgomery(col[7]).to_be_bytes::<32>(),
+                H::hash_elements([col[2], col[3]]).as_bytes(),
+                H::hash_elements([col[4], col[5]]).as_bytes(),
+            ]
+        );
+        assert_eq!(
+            multiple_columns_words,
+            [
+                row_hash(0).as_bytes(),
+                row_hash(7).as_bytes(),
+                node(2, 3).as_bytes(),
+                node(4, 5).as_bytes(),
+            ]
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn decommitment_nodes_match_the_verifier_order() {
+        // same decommitment as above: rows 0 and 7 (nodes 8 and 15) then the
+        // nodes above rows 2..4 and 4..6 (nodes 5 and 6)
+        let nodes = decommitment_nodes(&[1, 6], 3);
+
+        assert_eq!(nodes, [(3, 0), (3, 7), (2, 1), (2, 2)]);
+    }
+
+    #[test]
+    fn queried_node_words_are_packages_of_a_single_column() -> Result<(), Error> {
+        // rows 2 and 3 share a package and the others need their sibling
+        const REVEAL_INDICES: [usize; 4] = [1, 2, 3, 6];
+        type H = Keccak256HashFn;
+        let col = [
+            Fp!("0"),
+            Fp!("1"),
+            Fp!("2"),
+            Fp!("3"),
+            Fp!("4"),
+            Fp!("5"),
+            Fp!("6"),
+            Fp!("7"),
+        ];
+        let single_column = Matrix::new(vec![col.to_vec_in(GpuAllocator)]);
+        let multiple_columns = Matrix::new(vec![
+            col.to_vec_in(GpuAllocator),
+            col.to_vec_in(GpuAllocator),
+        ]);
+        let package = |a: usize, b: usize| H::hash_elements([col[a], col[b]]).as_bytes();
+
+        let single_column_words = LeafVariantMerkleTree::<H>::from_matrix(&single_column)
+            .prove_rows(&REVEAL_INDICES)?
+            .queried_node_words(&REVEAL_INDICES);
+        let multiple_columns_words = LeafVariantMerkleTree::<H>::from_matrix(&multiple_columns)
+            .prove_rows(&REVEAL_INDICES)?
+            .queried_node_words(&REVEAL_INDICES);
+
+        assert_eq!(
+            single_column_words,
+            [package(0, 1), package(2, 3), package(6, 7)]
+        );
+        assert_eq!(
+            multiple_columns_words,
+            REVEAL_INDICES.map(|i| package(i, i))
+        );
+        Ok(())
+    }
+
     #[test]
     fn friendly_merkle_tree_with_single_column() -> Result<(), Error> {
         const REVEAL_INDEX: usize = 3;
@@ -0,0 +1,45 @@
+//! Parameters of the `all_solidity` layout from StarkWare's open source
+//! verifier <https://github.com/starkware-libs/cairo-lang/blob/361fe32d5930db340ea78fe05aedfe706f6c9405/src/starkware/cairo/lang/instances.py>
+//!
+//! Builtins: output, pedersen, range_check, ecdsa, bitwise and ec_op. This is
+//! the layout with the most builtins supported by StarkWare's Solidity
+//! verifier. Note there is no poseidon builtin since the Solidity verifier
+//! doesn't support it.
+//!
+//! Only the parameters and the public input encoding exist for this layout.
+//! There's no trace or AIR yet so programs can't be proven with it.
+
+// TODO: add the trace and AIR for this layout. Pedersen has 4 repetitions per
+// component and each repetition gets its own set of columns so like the
+// `small` layout the trace generation can't be shared with the `starknet` or
+// `recursive` layouts.
+
+// must be a power-of-two
+pub const CYCLE_HEIGHT: usize = 16;
+/// 8 memory units per step with a public memory fraction of 8
+pub const PUBLIC_MEMORY_STEP: usize = 16;
+pub const MEMORY_STEP: usize = 2;
+/// 8 range check units per step
+pub const RANGE_CHECK_STEP: usize = 2;
+/// 16 diluted units per step
+pub const DILUTED_CHECK_STEP: usize = 1;
+
+pub const DILUTED_CHECK_N_BITS: usize = 16;
+pub const DILUTED_CHECK_SPACING: usize = 4;
+
+/// How many cycles per pedersen hash
+pub const PEDERSEN_BUILTIN_RATIO: usize = 8;
+pub const PEDERSEN_BUILTIN_REPETITIONS: usize = 4;
+
+/// How many cycles per 128 bit range check
+pub const RANGE_CHECK_BUILTIN_RATIO: usize = 8;
+pub const RANGE_CHECK_BUILTIN_PARTS: usize = 8;
+
+pub const ECDSA_BUILTIN_RATIO: usize = 512;
+pub const ECDSA_BUILTIN_REPETITIONS: usize = 1;
+
+pub const BITWISE_RATIO: usize = 256;
+
+pub const EC_OP_BUILTIN_RATIO: usize = 256;
+pub const EC_OP_SCALAR_HEIGHT: usize = 256;
+pub const EC_OP_N_BITS: usize = 252;
@@ -0,0 +1,756 @@
+//! Pre-flight validation of a witness. Inconsistent witnesses otherwise only
+//! surface as panics deep inside trace generation. [check_witness] replays the
+//! execution and reports every violation it finds rather than stopping at the
+//! first.
+
+use crate::CairoWitness;
+use ark_ff::PrimeField;
+use binary::vm::LayoutParams;
+use binary::AirPrivateInput;
+use binary::AirPublicInput;
+use binary::Flag;
+use binary::FlagGroup;
+use binary::Layout;
+use binary::Memory;
+use binary::RegisterState;
+use binary::Segment;
+use binary::Word;
+use binary::HALF_OFFSET;
+use builtins::keccak::keccak_f1600;
+use num_bigint::BigUint;
+use ruint::aliases::U256;
+use std::collections::BTreeSet;
+use std::fmt::Display;
+
+/// Problem with a witness found by [check_witness]
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Violation {
+    /// Step of the execution the violation was found at
+    pub step: Option<usize>,
+    /// Memory address the violation relates to
+    pub address: Option<usize>,
+    pub reason: String,
+}
+
+impl Violation {
+    fn new(step: Option<usize>, address: Option<usize>, reason: impl Into<String>) -> Self {
+        Self {
+            step,
+            address,
+            reason: reason.into(),
+        }
+    }
+}
+
+impl Display for Violation {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match (self.step, self.address) {
+            (Some(step), Some(address)) => write!(f, "step {step}, address {address}: ")?,
+            (Some(step), None) => write!(f, "step {step}: ")?,
+            (None, Some(address)) => write!(f, "address {address}: ")?,
+            (None, None) => {}
+        }
+        write!(f, "{}", self.reason)
+    }
+}
+
+/// Checks a witness is consistent with the public input. Checks that:
+/// - the register states are a valid execution of the instructions in memory
+/// - the builtin instances fit in their segments, their inputs are in memory
+///   and they agree with memory
+/// - `rc_min` and `rc_max` are the bounds of all range checked values
+/// - the public memory agrees with memory
+/// - memory starts at address 1 and its holes fit in the unused memory cells
+/// - the range check and diluted pools fit in the trace so that the permutation
+///   arguments hold
+///
+/// Outputs of the pedersen, ecdsa, ec_op and poseidon builtins aren't checked.
+/// Returns all violations found. An empty result means the witness is valid.
+pub fn check_witness<F: PrimeField>(
+    public_input: &AirPublicInput<F>,
+    witness: &CairoWitness<F>,
+) -> Vec<Violation> {
+    let CairoWitness {
+        air_private_input,
+        register_states,
+        memory,
+    } = witness;
+
+    let mut violations = Vec::new();
+    let Some(params) = layout_params(public_input.layout) else {
+        let reason = format!("{} layout is not supported", public_input.layout);
+        violations.push(Violation::new(None, None, reason));
+        return violations;
+    };
+    check_registers(public_input, register_states, &mut violations);
+
+    let mut rc_values = Vec::new();
+    let mut accesses = Vec::new();
+    for (step, [curr, next]) in register_states.array_windows().enumerate() {
+        check_step(
+            step,
+            curr,
+            Some(next),
+            memory,
+            &mut rc_values,
+            &mut accesses,
+            &mut violations,
+        );
+    }
+    if let Some(last) = register_states.last() {
+        let step = register_states.len() - 1;
+        check_step(
+            step,
+            last,
+            None,
+            memory,
+            &mut rc_values,
+            &mut accesses,
+            &mut violations,
+        );
+    }
+
+    let rc_builtin_units = check_builtins(
+        public_input,
+        air_private_input,
+        &params,
+        memory,
+        &mut rc_values,
+        &mut violations,
+    );
+    check_range_check_bounds(public_input, &rc_values, &mut violations);
+    check_public_memory(public_input, memory, &mut violations);
+    check_memory_continuity(public_input, &params, &accesses, &mut violations);
+    check_permutations(public_input, &params, rc_builtin_units, &mut violations);
+    violations
+}
+
+/// Parameters of the layouts that have a trace
+fn layout_params(layout: Layout) -> Option<LayoutParams> {
+    match layout {
+        Layout::Plain => Some(crate::plain::VM_PARAMS),
+        Layout::Starknet => Some(crate::starknet::VM_PARAMS),
+        Layout::Recursive => Some(crate::recursive::VM_PARAMS),
+        _ => None,
+    }
+}
+
+fn check_registers<F: PrimeField>(
+    public_input: &AirPublicInput<F>,
+    register_states: &[RegisterState],
+    violations: &mut Vec<Violation>,
+) {
+    let n_steps = register_states.len();
+    if !n_steps.is_power_of_two() {
+        let reason = format!("number of steps must be a power of two but is {n_steps}");
+        violations.push(Violation::new(None, None, reason));
+    }
+    if n_steps as u64 != public_input.n_steps {
+        let reason = format!(
+            "trace has {n_steps} steps but the public input has {}",
+            public_input.n_steps
+        );
+        violations.push(Violation::new(None, None, reason));
+    }
+
+    let (Some(first), Some(last)) = (register_states.first(), register_states.last()) else {
+        return;
+    };
+
+    let initial_ap = public_input.initial_ap() as usize;
+    let initial_pc = public_input.initial_pc() as usize;
+    let final_ap = public_input.final_ap() as usize;
+    let final_pc = public_input.final_pc() as usize;
+    let last_step = n_steps - 1;
+    let expected = [
+        (0, "initial pc", first.pc, initial_pc),
+        (0, "initial ap", first.ap, initial_ap),
+        (0, "initial fp", first.fp, initial_ap),
+        (last_step, "final pc", last.pc, final_pc),
+        (last_step, "final ap", last.ap, final_ap),
+    ];
+    for (step, register, actual, expected) in expected {
+        if actual != expected {
+            let reason = format!("{register} is {actual} but the public input has {expected}");
+            violations.push(Violation::new(Some(step), None, reason));
+        }
+    }
+}
+
+/// Replays a single step of the execution. `next` is `None` for the last step.
+/// The biased offsets of the instruction are added to `rc_values` and the
+/// addresses it reads are added to `accesses`.
+fn check_step<F: PrimeField>(
+    step: usize,
+    curr: &RegisterState,
+    next: Option<&RegisterState>,
+    memory: &Memory<F>,
+    rc_values: &mut Vec<u16>,
+    accesses: &mut Vec<usize>,
+    violations: &mut Vec<Violation>,
+) {
+    let &RegisterState { pc, ap, fp } = curr;
+    let mut violation = |address: Option<usize>, reason: String| {
+        violations.push(Violation::new(Some(step), address, reason));
+    };
+
+    accesses.push(pc);
+    let Some(instruction) = read(memory, pc) else {
+        violation(Some(pc), "no instruction in memory at pc".to_string());
+        return;
+    };
+    if let Err(reason) = validate_instruction(&instruction) {
+        violation(Some(pc), reason);
+        return;
+    }
+
+    rc_values.extend([
+        instruction.get_off_dst(),
+        instruction.get_off_op0(),
+        instruction.get_off_op1(),
+    ]);
+
+    let dst_reg = if instruction.get_flag(Flag::DstReg) {
+        fp
+    } else {
+        ap
+    };
+    let op0_reg = if instruction.get_flag(Flag::Op0Reg) {
+        fp
+    } else {
+        ap
+    };
+    let Some(dst_addr) = offset_addr(dst_reg, instruction.get_off_dst()) else {
+        violation(None, "dst address is negative".to_string());
+        return;
+    };
+    let Some(op0_addr) = offset_addr(op0_reg, instruction.get_off_op0()) else {
+        violation(None, "op0 address is negative".to_string());
+        return;
+    };
+
+    accesses.extend([dst_addr, op0_addr]);
+    let Some(dst) = read_felt(memory, dst_addr) else {
+        violation(Some(dst_addr), "dst is not in memory".to_string());
+        return;
+    };
+    let Some(op0) = read_felt(memory, op0_addr) else {
+        violation(Some(op0_addr), "op0 is not in memory".to_string());
+        return;
+    };
+
+    let op1_base = match instruction.get_flag_group(FlagGroup::Op1Src) {
+        0 => match felt_to_usize(op0) {
+            Some(op0) => op0,
+            None => {
+                violation(Some(op0_addr), "op0 is not a valid address".to_string());
+                return;
+            }
+        },
+        1 => pc,
+        2 => fp,
+        4 => ap,
+        _ => unreachable!(),
+    };
+    let Some(op1_addr) = offset_addr(op1_base, instruction.get_off_op1()) else {
+        violation(None, "op1 address is negative".to_string());
+        return;
+    };
+    accesses.push(op1_addr);
+    let Some(op1) = read_felt(memory, op1_addr) else {
+        violation(Some(op1_addr), "op1 is not in memory".to_string());
+        return;
+    };
+
+    let pc_update = instruction.get_flag_group(FlagGroup::PcUpdate);
+    let opcode = instruction.get_flag_group(FlagGroup::Opcode);
+    let instruction_size = if instruction.get_flag(Flag::Op1Imm) {
+        2
+    } else {
+        1
+    };
+    // `res` is unused by jnz instructions
+    let res = match instruction.get_flag_group(FlagGroup::ResLogic) {
+        0 => op1,
+        1 => op0 + op1,
+        2 => op0 * op1,
+        _ => unreachable!(),
+    };
+
+    // opcode assertions
+    match opcode {
+        // call
+        1 => {
+            if dst != F::from(fp as u64) {
+                violation(Some(dst_addr), "call does not store fp at dst".to_string());
+            }
+            if op0 != F::from((pc + instruction_size) as u64) {
+                let reason = "call does not store the return pc at op0".to_string();
+                violation(Some(op0_addr), reason);
+            }
+        }
+        // assert_eq
+        4 if dst != res => {
+            violation(Some(dst_addr), "assert_eq failed: dst != res".to_string());
+        }
+        _ => {}
+    }
+
+    let Some(next) = next else {
+        return;
+    };
+
+    let next_pc = match pc_update {
+        0 => Some(pc + instruction_size),
+        1 => felt_to_usize(res),
+        2 => felt_to_usize(F::from(pc as u64) + res),
+        4 if dst.is_zero() => Some(pc + instruction_size),
+        4 => felt_to_usize(F::from(pc as u64) + op1),
+        _ => unreachable!(),
+    };
+    let next_ap = match (opcode, instruction.get_flag_group(FlagGroup::ApUpdate)) {
+        (1, _) => Some(ap + 2),
+        (_, 0) => Some(ap),
+        (_, 1) => felt_to_usize(F::from(ap as u64) + res),
+        (_, 2) => Some(ap + 1),
+        _ => unreachable!(),
+    };
+    let next_fp = match opcode {
+        1 => Some(ap + 2),
+        2 => felt_to_usize(dst),
+        _ => Some(fp),
+    };
+
+    for (register, actual, expected) in [
+        ("pc", next.pc, next_pc),
+        ("ap", next.ap, next_ap),
+        ("fp", next.fp, next_fp),
+    ] {
+        if Some(actual) != expected {
+            let expected = expected.map_or("an invalid value".to_string(), |v| v.to_string());
+            let reason =
+                format!("next {register} is {actual} but the instruction gives {expected}");
+            violation(Some(pc), reason);
+        }
+    }
+}
+
+/// Checks an instruction is encoded the way the Cairo AIR expects
+fn validate_instruction<F>(instruction: &Word<F>) -> Result<(), String> {
+    if instruction.0 >> 63 != U256::ZERO {
+        return Err("instruction is larger than 63 bits".to_string());
+    }
+
+    let groups = [
+        ("op1 source", FlagGroup::Op1Src, &[0, 1, 2, 4][..]),
+        ("res logic", FlagGroup::ResLogic, &[0, 1, 2]),
+        ("pc update", FlagGroup::PcUpdate, &[0, 1, 2, 4]),
+        ("ap update", FlagGroup::ApUpdate, &[0, 1, 2]),
+        ("opcode", FlagGroup::Opcode, &[0, 1, 2, 4]),
+    ];
+    for (name, group, valid) in groups {
+        let value = instruction.get_flag_group(group);
+        if !valid.contains(&value) {
+            return Err(format!("invalid {name} flags ({value}) in instruction"));
+        }
+    }
+
+    let opcode = instruction.get_flag_group(FlagGroup::Opcode);
+    let pc_update = instruction.get_flag_group(FlagGroup::PcUpdate);
+    let res_logic = instruction.get_flag_group(FlagGroup::ResLogic);
+    let ap_update = instruction.get_flag_group(FlagGroup::ApUpdate);
+    if pc_update == 4 && (res_logic != 0 || opcode != 0 || ap_update == 1) {
+        return Err("jnz instruction uses res".to_string());
+    }
+    if opcode == 1 && ap_update != 0 {
+        return Err("call instruction updates ap".to_string());
+    }
+
+    Ok(())
+}
+
+/// Returns the number of range check units used by the range check builtin
+fn check_builtins<F: PrimeField>(
+    public_input: &AirPublicInput<F>,
+    air_private_input: &AirPrivateInput,
+    params: &LayoutParams,
+    memory: &Memory<F>,
+    rc_values: &mut Vec<u16>,
+    violations: &mut Vec<Violation>,
+) -> usize {
+    let AirPrivateInput {
+        pedersen,
+        range_check,
+        ecdsa,
+        bitwise,
+        ec_op,
+        keccak,
+        poseidon,
+        ..
+    } = air_private_input;
+    let segments = &public_input.memory_segments;
+    // instances are only checked if the builtin has a segment
+    let begin_addr = |segment: Option<Segment>| segment.map_or(0, |s| s.begin_addr);
+
+    // cells of each instance start with its `num_inputs` inputs which must be
+    // in memory. outputs may be missing since the prover fills them in.
+    let mut builtin = |name: &str,
+                       segment: Option<Segment>,
+                       num_inputs: usize,
+                       cells: Vec<Vec<(u32, U256)>>| {
+        if cells.is_empty() {
+            return;
+        }
+        let Some(segment) = segment else {
+            let reason = format!("{name} instances given but there is no {name} segment");
+            violations.push(Violation::new(None, None, reason));
+            return;
+        };
+        let segment_range = segment.begin_addr as usize..segment.stop_ptr as usize;
+        let cells = cells
+            .into_iter()
+            .flat_map(|cells| cells.into_iter().enumerate());
+        for (i, (address, expected)) in cells {
+            let address = address as usize;
+            let reason = match read(memory, address) {
+                _ if !segment_range.contains(&address) => {
+                    format!("{name} instance is outside the {name} segment")
+                }
+                Some(word) if word.0 != expected => {
+                    format!("{name} instance does not match memory")
+                }
+                None if i < num_inputs => format!("{name} instance input is missing from memory"),
+                _ => continue,
+            };
+            violations.push(Violation::new(None, Some(address), reason));
+        }
+    };
+
+    builtin(
+        "pedersen",
+        segments.pedersen,
+        2,
+        pedersen
+            .iter()
+            .map(|instance| {
+                let (a_addr, b_addr, _) = instance.mem_addr(begin_addr(segments.pedersen));
+                vec![(a_addr, instance.a), (b_addr, instance.b)]
+            })
+            .collect(),
+    );
+
+    builtin(
+        "range_check",
+        segments.range_check,
+        1,
+        range_check
+            .iter()
+            .map(|instance| {
+                let address = instance.mem_addr(begin_addr(segments.range_check));
+                vec![(address, instance.value)]
+            })
+            .collect(),
+    );
+
+    builtin(
+        "ecdsa",
+        segments.ecdsa,
+        2,
+        ecdsa
+            .iter()
+            .map(|instance| {
+                let (pubkey_addr, msg_addr) = instance.mem_addr(begin_addr(segments.ecdsa));
+                vec![
+                    (pubkey_addr, instance.pubkey_x),
+                    (msg_addr, instance.message),
+                ]
+            })
+            .collect(),
+    );
+
+    builtin(
+        "bitwise",
+        segments.bitwise,
+        2,
+        bitwise
+            .iter()
+            .map(|instance| {
+                let (x, y) = (instance.x, instance.y);
+                let (x_addr, y_addr, and_addr, xor_addr, or_addr) =
+                    instance.mem_addr(begin_addr(segments.bitwise));
+                vec![
+                    (x_addr, x),
+                    (y_addr, y),
+                    (and_addr, x & y),
+                    (xor_addr, x ^ y),
+                    (or_addr, x | y),
+                ]
+            })
+            .collect(),
+    );
+
+    builtin(
+        "ec_op",
+        segments.ec_op,
+        5,
+        ec_op
+            .iter()
+            .map(|instance| {
+                let (p_x_addr, p_y_addr, q_x_addr, q_y_addr, m_addr, _, _) =
+                    instance.mem_addr(begin_addr(segments.ec_op));
+                vec![
+                    (p_x_addr, instance.p_x),
+                    (p_y_addr, instance.p_y),
+                    (q_x_addr, instance.q_x),
+                    (q_y_addr, instance.q_y),
+                    (m_addr, instance.m),
+                ]
+            })
+            .collect(),
+    );
+
+    // outputs can't be computed for inputs that don't fit in the keccak state
+    let mut invalid_keccak_inputs = Vec::new();
+    builtin(
+        "keccak",
+        segments.keccak,
+        8,
+        keccak
+            .iter()
+            .map(|instance| {
+                let inputs = instance.inputs();
+                let (input_addrs, output_addrs) = instance.mem_addr(begin_addr(segments.keccak));
+                let mut cells = input_addrs.into_iter().zip(inputs).collect::<Vec<_>>();
+                match keccak_f1600(inputs) {
+                    Ok(outputs) => cells.extend(output_addrs.into_iter().zip(outputs)),
+                    Err(err) => invalid_keccak_inputs.push((input_addrs[err.position], err)),
+                }
+                cells
+            })
+            .collect(),
+    );
+
+    builtin(
+        "poseidon",
+        segments.poseidon,
+        3,
+        poseidon
+            .iter()
+            .map(|instance| {
+                let (input0_addr, input1_addr, input2_addr, _, _, _) =
+                    instance.mem_addr(begin_addr(segments.poseidon));
+                vec![
+                    (input0_addr, instance.input0),
+                    (input1_addr, instance.input1),
+                    (input2_addr, instance.input2),
+                ]
+            })
+            .collect(),
+    );
+
+    for (address, err) in invalid_keccak_inputs {
+        let address = address as usize;
+        violations.push(Violation::new(None, Some(address), err.to_string()));
+    }
+
+    // range check values must fit in the 16-bit parts checked by the AIR
+    let parts = params.range_check_parts;
+    for instance in range_check {
+        let value = instance.value;
+        if value.bit_len() > parts * 16 {
+            let address = segments
+                .range_check
+                .map(|segment| instance.mem_addr(segment.begin_addr) as usize);
+            let reason = format!("range check value {value} is out of range");
+            violations.push(Violation::new(None, address, reason));
+            continue;
+        }
+        let limbs = value.as_limbs();
+        rc_values.extend((0..parts).map(|i| (limbs[i / 4] >> (i % 4 * 16)) as u16));
+    }
+    range_check.len() * parts
+}
+
+/// `rc_min` and `rc_max` must be the exact bounds of the range checked values
+/// since the AIR checks they are the first and last values of the sorted range
+/// check column.
+fn check_range_check_bounds<F: PrimeField>(
+    public_input: &AirPublicInput<F>,
+    rc_values: &[u16],
+    violations: &mut Vec<Violation>,
+) {
+    let (Some(&rc_min), Some(&rc_max)) = (rc_values.iter().min(), rc_values.iter().max()) else {
+        return;
+    };
+    if rc_min != public_input.rc_min {
+        let reason = format!("rc_min is {} but should be {rc_min}", public_input.rc_min);
+        violations.push(Violation::new(None, None, reason));
+    }
+    if rc_max != public_input.rc_max {
+        let reason = format!("rc_max is {} but should be {rc_max}", public_input.rc_max);
+        violations.push(Violation::new(None, None, reason));
+    }
+}
+
+fn check_public_memory<F: PrimeField>(
+    public_input: &AirPublicInput<F>,
+    memory: &Memory<F>,
+    violations: &mut Vec<Violation>,
+) {
+    for entry in &public_input.public_memory {
+        let address = entry.address as usize;
+        match read_felt(memory, address) {
+            None => {
+                let reason = "public memory entry is not in memory".to_string();
+                violations.push(Violation::new(None, Some(address), reason));
+            }
+            Some(value) if value != entry.value => {
+                let reason = format!("public memory value {} does not match memory", entry.value);
+                violations.push(Violation::new(None, Some(address), reason));
+            }
+            Some(_) => {}
+        }
+    }
+}
+
+/// The AIR sorts all memory accesses by address and requires the sorted
+/// addresses to start at 1 and increase by at most one. Cells that aren't
+/// accessed (holes) are filled in using the unused memory cells of the trace.
+/// Matches `CairoRunner.check_memory_usage` from cairo-lang.
+fn check_memory_continuity<F: PrimeField>(
+    public_input: &AirPublicInput<F>,
+    params: &LayoutParams,
+    accesses: &[usize],
+    violations: &mut Vec<Violation>,
+) {
+    let segments = &public_input.memory_segments;
+    let builtin_segments = [
+        segments.output,
+        segments.pedersen,
+        segments.range_check,
+        segments.ecdsa,
+        segments.bitwise,
+        segments.ec_op,
+        segments.keccak,
+        segments.poseidon,
+    ];
+    let builtin_segments = builtin_segments.into_iter().flatten().collect::<Vec<_>>();
+    let in_builtin_segment = |address: usize| {
+        builtin_segments
+            .iter()
+            .any(|s| (s.begin_addr as usize..s.stop_ptr as usize).contains(&address))
+    };
+
+    let accessed = accesses
+        .iter()
+        .copied()
+        .chain(
+            public_input
+                .public_memory
+                .iter()
+                .map(|e| e.address as usize),
+        )
+        .collect::<BTreeSet<usize>>();
+    let (Some(&first), Some(&last)) = (accessed.first(), accessed.last()) else {
+        return;
+    };
+    if first != 1 {
+        let reason = format!("memory must start at address 1 but starts at {first}");
+        violations.push(Violation::new(None, Some(first), reason));
+    }
+    if !public_input.public_memory.iter().any(|e| e.address == 1) {
+        let reason = "address 1 is not in the public memory".to_string();
+        violations.push(Violation::new(None, Some(1), reason));
+    }
+
+    let holes = (first..=last)
+        .filter(|address| !accessed.contains(address) && !in_builtin_segment(*address))
+        .count();
+    let n_steps = public_input.n_steps as usize;
+    let total_units = params.memory_units * n_steps;
+    let public_memory_units = total_units / params.public_memory_fraction;
+    // four memory units per step are used by the instruction
+    let instruction_units = 4 * n_steps;
+    let builtin_units: usize = builtin_segments
+        .iter()
+        .map(|s| (s.stop_ptr - s.begin_addr) as usize)
+        .sum();
+    let public_memory_len = public_input.public_memory.len();
+    if public_memory_len > public_memory_units {
+        let reason = format!(
+            "public memory has {public_memory_len} entries but the trace only has room for \
+             {public_memory_units}"
+        );
+        violations.push(Violation::new(None, None, reason));
+    }
+    let unused_units =
+        total_units.saturating_sub(public_memory_units + instruction_units + builtin_units);
+    if holes > unused_units {
+        let reason =
+            format!("memory has {holes} holes but the trace only has room for {unused_units}");
+        violations.push(Violation::new(None, None, reason));
+    }
+}
+
+/// The range check and diluted pools of the trace are sorted permutations of
+/// the values checked plus filler values for every missing value in between.
+/// The permutation arguments only hold if the filler values fit in the unused
+/// cells of the pool.
+fn check_permutations<F: PrimeField>(
+    public_input: &AirPublicInput<F>,
+    params: &LayoutParams,
+    rc_builtin_units: usize,
+    violations: &mut Vec<Violation>,
+) {
+    let n_steps = public_input.n_steps as usize;
+
+    // three range check units per step are used by the instruction offsets
+    let rc_units = params.rc_units.saturating_sub(3) * n_steps;
+    let rc_range = public_input.rc_max.saturating_sub(public_input.rc_min) as usize;
+    match rc_units.checked_sub(rc_builtin_units) {
+        Some(unused) if unused >= rc_range => {}
+        unused => {
+            let reason = format!(
+                "range check pool needs {rc_range} unused units but only has {}",
+                unused.unwrap_or(0)
+            );
+            violations.push(Violation::new(None, None, reason));
+        }
+    }
+
+    if params.diluted_units != 0 {
+        // every bitwise instance allocated in the trace uses the diluted pool
+        let bitwise_instances = params.bitwise_ratio.map_or(0, |ratio| n_steps / ratio);
+        let bitwise_units = bitwise_instances * params.bitwise_diluted_units();
+        let diluted_units = params.diluted_units * n_steps;
+        let needed = 1 << params.diluted_n_bits;
+        match diluted_units.checked_sub(bitwise_units) {
+            Some(unused) if unused >= needed => {}
+            unused => {
+                let reason = format!(
+                    "diluted pool needs {needed} unused units but only has {}",
+                    unused.unwrap_or(0)
+                );
+                violations.push(Violation::new(None, None, reason));
+            }
+        }
+    }
+}
+
+fn read<F: PrimeField>(memory: &Memory<F>, address: usize) -> Option<Word<F>> {
+    memory.get(address)
+}
+
+fn read_felt<F: PrimeField>(memory: &Memory<F>, address: usize) -> Option<F> {
+    read(memory, address).map(Word::into_felt)
+}
+
+/// Applies a biased instruction offset to a register
+fn offset_addr(register: usize, offset: u16) -> Option<usize> {
+    (register + offset as usize).checked_sub(HALF_OFFSET)
+}
+
+fn felt_to_usize<F: PrimeField>(felt: F) -> Option<usize> {
+    let felt: BigUint = felt.into();
+    usize::try_from(felt).ok()
+}
@@ -0,0 +1,100 @@
+//! Metadata for the constraints of each layout. Constraints are returned by
+//! [AirConfig::constraints] as a flat list whose ordering must match
+//! StarkWare's `CpuConstraintPoly.sol` so the registry is the only way to tell
+//! which entry is which.
+
+use ministark::air::AirConfig;
+use ministark::constraints::AlgebraicItem;
+use ministark::expression::Expr;
+use std::fmt::Display;
+
+/// Component of the Cairo AIR a constraint belongs to
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum ConstraintGroup {
+    /// Instruction decoding, register updates and the boundary constraints on
+    /// the initial and final registers
+    Cpu,
+    Memory,
+    PublicMemory,
+    RangeCheck,
+    DilutedCheck,
+    Pedersen,
+    RangeCheckBuiltin,
+    Ecdsa,
+    Bitwise,
+    EcOp,
+    Poseidon,
+}
+
+impl Display for ConstraintGroup {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Cpu => write!(f, "cpu"),
+            Self::Memory => write!(f, "memory"),
+            Self::PublicMemory => write!(f, "public_memory"),
+            Self::RangeCheck => write!(f, "rc16"),
+            Self::DilutedCheck => write!(f, "diluted_check"),
+            Self::Pedersen => write!(f, "pedersen"),
+            Self::RangeCheckBuiltin => write!(f, "rc_builtin"),
+            Self::Ecdsa => write!(f, "ecdsa"),
+            Self::Bitwise => write!(f, "bitwise"),
+            Self::EcOp => write!(f, "ec_op"),
+            Self::Poseidon => write!(f, "poseidon"),
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct ConstraintInfo {
+    /// Name of the constraint. Matches the variable name used in the layout's
+    /// `constraints` function.
+    pub name: &'static str,
+    pub group: ConstraintGroup,
+    /// Rows of the trace the constraint applies to. Named after the
+    /// constraint's zerofier.
+    pub domain: &'static str,
+}
+
+impl ConstraintInfo {
+    pub const fn new(name: &'static str, group: ConstraintGroup, domain: &'static str) -> Self {
+        Self {
+            name,
+            group,
+            domain,
+        }
+    }
+}
+
+/// Registry of the constraints of a layout
+pub trait ConstraintRegistry: AirConfig {
+    /// Metadata of each constraint in the order they are returned by
+    /// [AirConfig::constraints]
+    const CONSTRAINTS: &'static [ConstraintInfo];
+
+    /// Index of the constraint with the given name
+    fn constraint_index(name: &str) -> Option<usize> {
+        Self::CONSTRAINTS.iter().position(|info| info.name == name)
+    }
+
+    /// Degree of each constraint in the trace columns
+    fn constraint_degrees(trace_len: usize) -> Vec<usize> {
+        Self::constraints(trace_len)
+            .iter()
+            .map(|constraint| degree(constraint))
+            .collect()
+    }
+}
+
+/// Degree of a constraint expression in the trace columns. Zerofiers and
+/// periodic columns don't contribute to the degree.
+pub fn degree<T>(expr: &Expr<AlgebraicItem<T>>) -> usize {
+    match expr {
+        Expr::Leaf(AlgebraicItem::Trace(..)) => 1,
+        Expr::Leaf(_) => 0,
+        Expr::Neg(a) => degree(a),
+        Expr::Add(a, b) => degree(a).max(degree(b)),
+        Expr::Mul(a, b) => degree(a) + degree(b),
+        Expr::Div(a, b) => degree(a).saturating_sub(degree(b)),
+        Expr::Pow(a, exp) => degree(a) * exp,
+    }
+}
@@ -0,0 +1,170 @@
+//! Evaluates AIR constraints on a trace row by row. ministark only reports a
+//! bad trace as a composition polynomial of the wrong degree so this is used
+//! to find which constraints don't hold and where.
+
+use crate::constraints::ConstraintRegistry;
+use ark_ff::Field;
+use ark_poly::EvaluationDomain;
+use ark_poly::Radix2EvaluationDomain;
+use ministark::challenges::Challenges;
+use ministark::constraints::AlgebraicItem;
+use ministark::expression::Expr;
+use ministark::hints::Hints;
+use ministark::utils::FieldVariant;
+use ministark::Matrix;
+use std::collections::BTreeMap;
+
+/// Constraint that doesn't vanish on a row of the trace
+#[derive(Clone, Debug)]
+pub struct ConstraintFailure<Fq> {
+    /// Index of the constraint in [ministark::air::AirConfig::constraints]
+    pub index: usize,
+    pub name: &'static str,
+    pub row: usize,
+    /// Trace cells used by the constraint
+    /// Of the form `((column, offset), value)`
+    pub cells: BTreeMap<(usize, isize), Fq>,
+}
+
+/// Numerator and denominator of an evaluated constraint. Constraints are
+/// rational functions that only apply on rows where their denominator (the
+/// zerofier) vanishes.
+#[derive(Clone, Copy)]
+struct Fraction<F> {
+    numerator: F,
+    denominator: F,
+}
+
+impl<F: Field> Fraction<F> {
+    fn new(value: F) -> Self {
+        Self {
+            numerator: value,
+            denominator: F::one(),
+        }
+    }
+
+    /// A constraint fails on a row where its zerofier vanishes and the
+    /// constraint expression (including any exemptions) doesn't
+    fn fails(&self) -> bool {
+        self.denominator.is_zero() && !self.numerator.is_zero()
+    }
+}
+
+/// Evaluates every constraint of the AIR on every row of the trace. Returns
+/// the constraints that don't vanish on rows they apply to.
+pub fn failing_constraints<A: ConstraintRegistry>(
+    challenges: &Challenges<A::Fq>,
+    hints: &Hints<A::Fq>,
+    base_trace: &Matrix<A::Fp>,
+    extension_trace: Option<&Matrix<A::Fq>>,
+) -> Vec<ConstraintFailure<A::Fq>> {
+    let trace_len = base_trace.num_rows();
+    let constraints = A::constraints(trace_len);
+    assert_eq!(
+        constraints.len(),
+        A::CONSTRAINTS.len(),
+        "every constraint needs to be in the registry"
+    );
+
+    let trace_domain = Radix2EvaluationDomain::<A::Fp>::new(trace_len).unwrap();
+    let g = trace_domain.group_gen();
+    let num_base_columns = base_trace.num_cols();
+    let cell = |column: usize, offset: isize, row: usize| -> A::Fq {
+        let row = (row as isize + offset).rem_euclid(trace_len as isize) as usize;
+        if column < num_base_columns {
+            base_trace[column][row].into()
+        } else {
+            let extension_trace = extension_trace.expect("constraint uses extension columns");
+            extension_trace[column - num_base_columns][row]
+        }
+    };
+
+    ark_std::cfg_into_iter!(0..trace_len)
+        .flat_map(|row| {
+            let x = g.pow([row as u64]);
+            let mut failures = Vec::new();
+            for (index, constraint) in constraints.iter().enumerate() {
+                let mut cells = BTreeMap::new();
+                let eval = evaluate(constraint, &mut |item| match item {
+                    AlgebraicItem::X => x.into(),
+                    AlgebraicItem::Constant(v) => lift(*v),
+                    AlgebraicItem::Challenge(i) => challenges[*i],
+                    AlgebraicItem::Hint(i) => hints[*i],
+                    AlgebraicItem::Periodic(column) => {
+                        let point = x.pow([(trace_len / column.interval_size()) as u64]);
+                        let coeffs = column.coeffs();
+                        coeffs.iter().rev().fold(A::Fq::zero(), |acc, &coeff| {
+                            acc * A::Fq::from(point) + lift(coeff)
+                        })
+                    }
+                    &AlgebraicItem::Trace(column, offset) => {
+                        let value = cell(column, offset, row);
+                        cells.insert((column, offset), value);
+                        value
+                    }
+                });
+
+                if eval.fails() {
+                    failures.push(ConstraintFailure {
+                        index,
+                        name: A::CONSTRAINTS[index].name,
+                        row,
+                        cells,
+                    });
+                }
+            }
+            failures
+        })
+        .collect()
+}
+
+fn evaluate<T, F: Field>(expr: &Expr<T>, leaf: &mut impl FnMut(&T) -> F) -> Fraction<F> {
+    match expr {
+        Expr::Leaf(item) => Fraction::new(leaf(item)),
+        Expr::Neg(a) => {
+            let a = evaluate(a, leaf);
+            Fraction {
+                numerator: -a.numerator,
+                denominator: a.denominator,
+            }
+        }
+        Expr::Add(a, b) => {
+            let a = evaluate(a, leaf);
+            let b = evaluate(b, leaf);
+            Fraction {
+                numerator: a.numerator * b.denominator + b.numerator * a.denominator,
+                denominator: a.denominator * b.denominator,
+            }
+        }
+        Expr::Mul(a, b) => {
+            let a = evaluate(a, leaf);
+            let b = evaluate(b, leaf);
+            Fraction {
+                numerator: a.numerator * b.numerator,
+                denominator: a.denominator * b.denominator,
+            }
+        }
+        Expr::Div(a, b) => {
+            let a = evaluate(a, leaf);
+            let b = evaluate(b, leaf);
+            Fraction {
+                numerator: a.numerator * b.denominator,
+                denominator: a.denominator * b.numerator,
+            }
+        }
+        Expr::Pow(a, exp) => {
+            let a = evaluate(a, leaf);
+            Fraction {
+                numerator: a.numerator.pow([*exp as u64]),
+                denominator: a.denominator.pow([*exp as u64]),
+            }
+        }
+    }
+}
+
+fn lift<Fp: Field, Fq: Field + From<Fp>>(value: FieldVariant<Fp, Fq>) -> Fq {
+    match value {
+        FieldVariant::Fp(v) => v.into(),
+        FieldVariant::Fq(v) => v,
+    }
+}
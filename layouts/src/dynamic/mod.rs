@@ -0,0 +1,241 @@
+//! Layout where the builtin ratios are chosen at runtime rather than being
+//! hard-coded. Programs that make heavy use of a single builtin can otherwise
+//! be forced into a huge trace by a fixed layout. The chosen ratios are
+//! stored in [binary::AirPublicInput::dynamic_params].
+//!
+//! Matches the parameters of the `starknet` layout other than the ratios.
+
+// TODO: add the trace, AIR and public input encoding for this layout. The
+// periodic columns and zerofiers of each builtin need to be built from the
+// runtime ratios and the ratios need to be part of the public input so the
+// public coin commits to them. Until then the layout can't be proven and
+// isn't one of the serializable SHARP layouts.
+
+pub use crate::starknet::DILUTED_CHECK_N_BITS;
+pub use crate::starknet::DILUTED_CHECK_SPACING;
+pub use crate::starknet::RANGE_CHECK_BUILTIN_PARTS;
+use binary::AirPrivateInput;
+use binary::DynamicParams;
+use std::fmt::Display;
+
+// must be a power-of-two
+pub const CYCLE_HEIGHT: usize = 16;
+pub const PUBLIC_MEMORY_STEP: usize = 8;
+pub const MEMORY_STEP: usize = 2;
+pub const RANGE_CHECK_STEP: usize = 4;
+pub const DILUTED_CHECK_STEP: usize = 8;
+
+// Smallest ratio (i.e. most instances per cycle) supported for each builtin.
+// Limited by the number of trace cells each builtin needs per instance.
+pub const MIN_PEDERSEN_BUILTIN_RATIO: usize = 32;
+pub const MIN_RANGE_CHECK_BUILTIN_RATIO: usize = 8;
+pub const MIN_ECDSA_BUILTIN_RATIO: usize = 2048;
+pub const MIN_BITWISE_RATIO: usize = 8;
+pub const MIN_EC_OP_BUILTIN_RATIO: usize = 1024;
+pub const MIN_KECCAK_RATIO: usize = 2048;
+pub const MIN_POSEIDON_RATIO: usize = 8;
+
+/// Reasons builtin ratios can't be chosen for an execution
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum DynamicParamsError {
+    /// The number of steps isn't a power-of-two
+    InvalidSteps(usize),
+    /// The builtin instances don't fit in the execution at the builtin's
+    /// minimum ratio. The program must be run for more steps.
+    TooManyInstances {
+        builtin: &'static str,
+        num_instances: usize,
+        n_steps: usize,
+    },
+    /// The largest ratio that fits the builtin instances doesn't fit in a
+    /// `u32`
+    RatioOverflow {
+        builtin: &'static str,
+        ratio_log2: u32,
+    },
+}
+
+impl Display for DynamicParamsError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::InvalidSteps(n_steps) => {
+                write!(f, "number of steps must be a power-of-two but is {n_steps}")
+            }
+            Self::TooManyInstances {
+                builtin,
+                num_instances,
+                n_steps,
+            } => write!(
+                f,
+                "{num_instances} {builtin} instances don't fit in {n_steps} steps"
+            ),
+            Self::RatioOverflow {
+                builtin,
+                ratio_log2,
+            } => write!(f, "{builtin} ratio 2^{ratio_log2} doesn't fit in a u32"),
+        }
+    }
+}
+
+impl std::error::Error for DynamicParamsError {}
+
+/// Chooses the builtin ratios for an execution of `n_steps` steps that uses
+/// the builtin instances in `air_private_input`. Each ratio is the largest
+/// power-of-two that fits all instances of the builtin. Returns an error if a
+/// builtin doesn't fit at its minimum ratio.
+pub fn choose_params(
+    n_steps: usize,
+    air_private_input: &AirPrivateInput,
+) -> Result<DynamicParams, DynamicParamsError> {
+    if !n_steps.is_power_of_two() {
+        return Err(DynamicParamsError::InvalidSteps(n_steps));
+    }
+
+    let AirPrivateInput {
+        pedersen,
+        range_check,
+        ecdsa,
+        bitwise,
+        ec_op,
+        keccak,
+        poseidon,
+        ..
+    } = air_private_input;
+
+    let ratio =
+        |builtin, num_instances, min_ratio| ratio(builtin, n_steps, num_instances, min_ratio);
+
+    Ok(DynamicParams {
+        pedersen_ratio: ratio("pedersen", pedersen.len(), MIN_PEDERSEN_BUILTIN_RATIO)?,
+        range_check_ratio: ratio(
+            "range_check",
+            range_check.len(),
+            MIN_RANGE_CHECK_BUILTIN_RATIO,
+        )?,
+        ecdsa_ratio: ratio("ecdsa", ecdsa.len(), MIN_ECDSA_BUILTIN_RATIO)?,
+        bitwise_ratio: ratio("bitwise", bitwise.len(), MIN_BITWISE_RATIO)?,
+        ec_op_ratio: ratio("ec_op", ec_op.len(), MIN_EC_OP_BUILTIN_RATIO)?,
+        keccak_ratio: ratio("keccak", keccak.len(), MIN_KECCAK_RATIO)?,
+        poseidon_ratio: ratio("poseidon", poseidon.len(), MIN_POSEIDON_RATIO)?,
+    })
+}
+
+/// Largest power-of-two ratio that fits `num_instances` into `n_steps` steps.
+/// `n_steps` must be a power-of-two so the ratio divides the number of steps.
+fn ratio(
+    builtin: &'static str,
+    n_steps: usize,
+    num_instances: usize,
+    min_ratio: usize,
+) -> Result<Option<u32>, DynamicParamsError> {
+    if num_instances == 0 {
+        return Ok(None);
+    }
+
+    let max_ratio = n_steps / num_instances;
+    if max_ratio < min_ratio {
+        return Err(DynamicParamsError::TooManyInstances {
+            builtin,
+            num_instances,
+            n_steps,
+        });
+    }
+
+    let ratio_log2 = max_ratio.ilog2();
+    let ratio = 1u32.checked_shl(ratio_log2);
+    let ratio = ratio.ok_or(DynamicParamsError::RatioOverflow {
+        builtin,
+        ratio_log2,
+    })?;
+    Ok(Some(ratio))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::choose_params;
+    use super::DynamicParamsError;
+    use super::MIN_BITWISE_RATIO;
+    use super::MIN_PEDERSEN_BUILTIN_RATIO;
+    use binary::AirPrivateInput;
+    use binary::BitwiseInstance;
+    use binary::PedersenInstance;
+
+    fn private_input(num_pedersen: u32, num_bitwise: u32) -> AirPrivateInput {
+        AirPrivateInput {
+            trace_path: "trace.bin".into(),
+            memory_path: "memory.bin".into(),
+            pedersen: (0..num_pedersen).map(PedersenInstance::new_empty).collect(),
+            range_check: Vec::new(),
+            ecdsa: Vec::new(),
+            bitwise: (0..num_bitwise).map(BitwiseInstance::new_empty).collect(),
+            ec_op: Vec::new(),
+            keccak: Vec::new(),
+            poseidon: Vec::new(),
+        }
+    }
+
+    #[test]
+    fn unused_builtins_have_no_ratio() {
+        let params = choose_params(1024, &private_input(0, 0)).unwrap();
+
+        assert_eq!(params, Default::default());
+    }
+
+    #[test]
+    fn ratio_is_minimum_ratio_when_builtin_is_full() {
+        let n_steps = 1024;
+        let num_bitwise = (n_steps / MIN_BITWISE_RATIO) as u32;
+
+        let params = choose_params(n_steps, &private_input(0, num_bitwise)).unwrap();
+
+        assert_eq!(params.bitwise_ratio, Some(MIN_BITWISE_RATIO as u32));
+    }
+
+    #[test]
+    fn ratio_is_rounded_down_to_a_power_of_two() {
+        // 1024 / 3 = 341 so the ratio is 256
+        let params = choose_params(1024, &private_input(3, 1)).unwrap();
+
+        assert_eq!(params.pedersen_ratio, Some(256));
+        assert_eq!(params.bitwise_ratio, Some(1024));
+    }
+
+    #[test]
+    fn too_many_instances_is_rejected() {
+        let n_steps = 1024;
+        let num_pedersen = (n_steps / MIN_PEDERSEN_BUILTIN_RATIO + 1) as u32;
+
+        let err = choose_params(n_steps, &private_input(num_pedersen, 0)).unwrap_err();
+
+        assert_eq!(
+            err,
+            DynamicParamsError::TooManyInstances {
+                builtin: "pedersen",
+                num_instances: num_pedersen as usize,
+                n_steps,
+            }
+        );
+    }
+
+    #[test]
+    fn ratio_that_overflows_u32_is_rejected() {
+        let n_steps = 1 << 33;
+
+        let err = choose_params(n_steps, &private_input(0, 1)).unwrap_err();
+
+        assert_eq!(
+            err,
+            DynamicParamsError::RatioOverflow {
+                builtin: "bitwise",
+                ratio_log2: 33,
+            }
+        );
+    }
+
+    #[test]
+    fn steps_must_be_a_power_of_two() {
+        let err = choose_params(1000, &private_input(0, 0)).unwrap_err();
+
+        assert_eq!(err, DynamicParamsError::InvalidSteps(1000));
+    }
+}
@@ -19,9 +19,18 @@ use ministark::challenges::Challenges;
 use ministark::hints::Hints;
 use ministark::Trace;
 
+pub mod all_solidity;
+pub mod check;
+pub mod constraints;
+pub mod debug;
+pub mod dex;
+pub mod dynamic;
 pub mod plain;
 pub mod recursive;
+pub mod recursive_large_output;
+pub mod small;
 pub mod starknet;
+pub mod starknet_with_keccak;
 pub mod utils;
 
 pub trait CairoAirConfig: AirConfig {
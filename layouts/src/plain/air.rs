@@ -2,6 +2,9 @@ use super::CYCLE_HEIGHT;
 use super::MEMORY_STEP;
 use super::PUBLIC_MEMORY_STEP;
 use super::RANGE_CHECK_STEP;
+use crate::constraints::ConstraintGroup;
+use crate::constraints::ConstraintInfo;
+use crate::constraints::ConstraintRegistry;
 use crate::utils;
 use ark_ff::PrimeField;
 use ark_poly::EvaluationDomain;
@@ -568,6 +571,103 @@ impl<Fp: GpuFftField + PrimeField, Fq: StarkExtensionOf<Fp>> ministark::air::Air
     }
 }
 
+impl<Fp: GpuFftField + PrimeField, Fq: StarkExtensionOf<Fp>> ConstraintRegistry
+    for AirConfig<Fp, Fq>
+{
+    const CONSTRAINTS: &'static [ConstraintInfo] = {
+        use ConstraintGroup::*;
+        &[
+            ConstraintInfo::new("cpu_decode_opcode_rc_b", Cpu, "every row except flag 15"),
+            ConstraintInfo::new("cpu_decode_opcode_rc_zero", Cpu, "flag 15 of each cycle"),
+            ConstraintInfo::new("cpu_decode_opcode_rc_input", Cpu, "all cycles"),
+            ConstraintInfo::new("cpu_decode_flag_op1_base_op0_bit", Cpu, "all cycles"),
+            ConstraintInfo::new("cpu_decode_flag_res_op1_bit", Cpu, "all cycles"),
+            ConstraintInfo::new("cpu_decode_flag_pc_update_regular_bit", Cpu, "all cycles"),
+            ConstraintInfo::new("cpu_decode_fp_update_regular_bit", Cpu, "all cycles"),
+            ConstraintInfo::new("cpu_operands_mem_dst_addr", Cpu, "all cycles"),
+            ConstraintInfo::new("cpu_operands_mem_op0_addr", Cpu, "all cycles"),
+            ConstraintInfo::new("cpu_operands_mem_op1_addr", Cpu, "all cycles"),
+            ConstraintInfo::new("cpu_operands_ops_mul", Cpu, "all cycles"),
+            ConstraintInfo::new("cpu_operands_res", Cpu, "all cycles"),
+            ConstraintInfo::new(
+                "cpu_update_registers_update_pc_tmp0",
+                Cpu,
+                "all cycles except last",
+            ),
+            ConstraintInfo::new(
+                "cpu_update_registers_update_pc_tmp1",
+                Cpu,
+                "all cycles except last",
+            ),
+            ConstraintInfo::new(
+                "cpu_update_registers_update_pc_pc_cond_negative",
+                Cpu,
+                "all cycles except last",
+            ),
+            ConstraintInfo::new(
+                "cpu_update_registers_update_pc_pc_cond_positive",
+                Cpu,
+                "all cycles except last",
+            ),
+            ConstraintInfo::new(
+                "cpu_update_registers_update_ap_ap_update",
+                Cpu,
+                "all cycles except last",
+            ),
+            ConstraintInfo::new(
+                "cpu_update_registers_update_fp_fp_update",
+                Cpu,
+                "all cycles except last",
+            ),
+            ConstraintInfo::new("cpu_opcodes_call_push_fp", Cpu, "all cycles"),
+            ConstraintInfo::new("cpu_opcodes_call_push_pc", Cpu, "all cycles"),
+            ConstraintInfo::new("cpu_opcodes_call_off0", Cpu, "all cycles"),
+            ConstraintInfo::new("cpu_opcodes_call_off1", Cpu, "all cycles"),
+            ConstraintInfo::new("cpu_opcodes_call_flags", Cpu, "all cycles"),
+            ConstraintInfo::new("cpu_opcodes_ret_off0", Cpu, "all cycles"),
+            ConstraintInfo::new("cpu_opcodes_ret_off2", Cpu, "all cycles"),
+            ConstraintInfo::new("cpu_opcodes_ret_flags", Cpu, "all cycles"),
+            ConstraintInfo::new("cpu_opcodes_assert_eq_assert_eq", Cpu, "all cycles"),
+            ConstraintInfo::new("initial_ap", Cpu, "first row"),
+            ConstraintInfo::new("initial_fp", Cpu, "first row"),
+            ConstraintInfo::new("initial_pc", Cpu, "first row"),
+            ConstraintInfo::new("final_ap", Cpu, "last cycle"),
+            ConstraintInfo::new("final_fp", Cpu, "last cycle"),
+            ConstraintInfo::new("final_pc", Cpu, "last cycle"),
+            ConstraintInfo::new("memory_multi_column_perm_perm_init0", Memory, "first row"),
+            ConstraintInfo::new(
+                "memory_multi_column_perm_perm_step0",
+                Memory,
+                "every second row except last",
+            ),
+            ConstraintInfo::new(
+                "memory_multi_column_perm_perm_last",
+                Memory,
+                "second last row",
+            ),
+            ConstraintInfo::new("memory_diff_is_bit", Memory, "every second row except last"),
+            ConstraintInfo::new("memory_is_func", Memory, "every second row except last"),
+            ConstraintInfo::new("memory_initial_addr", Memory, "first row"),
+            ConstraintInfo::new("public_memory_addr_zero", PublicMemory, "every eighth row"),
+            ConstraintInfo::new("public_memory_value_zero", PublicMemory, "every eighth row"),
+            ConstraintInfo::new("rc16_perm_init0", RangeCheck, "first row"),
+            ConstraintInfo::new(
+                "rc16_perm_step0",
+                RangeCheck,
+                "every fourth row except last",
+            ),
+            ConstraintInfo::new("rc16_perm_last", RangeCheck, "fourth last row"),
+            ConstraintInfo::new(
+                "rc16_diff_is_bit",
+                RangeCheck,
+                "every fourth row except last",
+            ),
+            ConstraintInfo::new("rc16_minimum", RangeCheck, "first row"),
+            ConstraintInfo::new("rc16_maximum", RangeCheck, "fourth last row"),
+        ]
+    };
+}
+
 /// Cairo flag
 /// https://eprint.iacr.org/2021/1063.pdf section 9
 #[derive(Clone, Copy, EnumIter, PartialEq, Eq)]
@@ -5,6 +5,8 @@ mod air;
 mod trace;
 
 pub use air::AirConfig;
+use binary::vm::LayoutParams;
+use binary::Layout;
 pub use trace::ExecutionTrace;
 
 // must be a power-of-two
@@ -15,3 +17,23 @@ pub const RANGE_CHECK_STEP: usize = 4;
 
 pub const NUM_BASE_COLUMNS: usize = 5;
 pub const NUM_EXTENSION_COLUMNS: usize = 1;
+
+/// Parameters used by the Cairo VM to pad executions to fit this layout
+pub const VM_PARAMS: LayoutParams = LayoutParams {
+    layout: Layout::Plain,
+    rc_units: CYCLE_HEIGHT / RANGE_CHECK_STEP,
+    memory_units: CYCLE_HEIGHT / MEMORY_STEP,
+    public_memory_fraction: PUBLIC_MEMORY_STEP / MEMORY_STEP,
+    diluted_units: 0,
+    diluted_spacing: 0,
+    diluted_n_bits: 0,
+    range_check_parts: 0,
+    output: false,
+    pedersen_ratio: None,
+    range_check_ratio: None,
+    ecdsa_ratio: None,
+    bitwise_ratio: None,
+    ec_op_ratio: None,
+    keccak_ratio: None,
+    poseidon_ratio: None,
+};
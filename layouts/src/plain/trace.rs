@@ -92,8 +92,8 @@ impl<Fp: GpuFftField + PrimeField, Fq: StarkExtensionOf<Fp>> CairoTrace for Exec
         // skip the memory at address 0 - this is a special memory address in Cairo
         // TODO: a little brittle. investigate more.
         let mut npc_gap_iter = npc_column.array_chunks_mut().skip(7).step_by(8);
-        for (a, v) in memory.iter().enumerate().skip(1) {
-            if v.is_none() {
+        for a in 1..memory.max_address().unwrap_or(0) {
+            if memory.get(a).is_none() {
                 *npc_gap_iter.next().unwrap() = [(a as u64).into(), Fp::zero()];
             }
         }
@@ -101,7 +101,7 @@ impl<Fp: GpuFftField + PrimeField, Fq: StarkExtensionOf<Fp>> CairoTrace for Exec
         // add offsets to the range check pool
         let mut rc_pool = RangeCheckPool::new();
         for &RegisterState { pc, .. } in register_states.iter() {
-            let word = memory[pc].unwrap();
+            let word = memory.get(pc).unwrap();
             rc_pool.push(word.get_off_dst());
             rc_pool.push(word.get_off_op0());
             rc_pool.push(word.get_off_op1());
@@ -131,7 +131,7 @@ impl<Fp: GpuFftField + PrimeField, Fq: StarkExtensionOf<Fp>> CairoTrace for Exec
             .for_each(
                 |((((rc_cycle, aux_cycle), npc_cycle), flag_cycle), registers)| {
                     let &RegisterState { pc, ap, fp } = registers;
-                    let word = memory[pc].unwrap();
+                    let word = memory.get(pc).unwrap();
                     debug_assert!(!word.get_flag(Flag::Zero.into()));
 
                     // range check all offset values
@@ -9,6 +9,9 @@ use super::RANGE_CHECK_STEP;
 use super::DILUTED_CHECK_STEP;
 use super::DILUTED_CHECK_N_BITS;
 use super::DILUTED_CHECK_SPACING;
+use crate::constraints::ConstraintGroup;
+use crate::constraints::ConstraintInfo;
+use crate::constraints::ConstraintRegistry;
 use crate::CairoAirConfig;
 use ministark::constraints::PeriodicColumn;
 use crate::utils;
@@ -1584,6 +1587,223 @@ impl CairoAirConfig for AirConfig {
     }
 }
 
+impl ConstraintRegistry for AirConfig {
+    const CONSTRAINTS: &'static [ConstraintInfo] = {
+        use ConstraintGroup::*;
+        &[
+            ConstraintInfo::new("cpu_decode_opcode_rc_b", Cpu, "every row except flag 15"),
+            ConstraintInfo::new("cpu_decode_opcode_rc_zero", Cpu, "flag 15 of each cycle"),
+            ConstraintInfo::new("cpu_decode_opcode_rc_input", Cpu, "all cycles"),
+            ConstraintInfo::new("cpu_decode_flag_op1_base_op0_bit", Cpu, "all cycles"),
+            ConstraintInfo::new("cpu_decode_flag_res_op1_bit", Cpu, "all cycles"),
+            ConstraintInfo::new("cpu_decode_flag_pc_update_regular_bit", Cpu, "all cycles"),
+            ConstraintInfo::new("cpu_decode_fp_update_regular_bit", Cpu, "all cycles"),
+            ConstraintInfo::new("cpu_operands_mem_dst_addr", Cpu, "all cycles"),
+            ConstraintInfo::new("cpu_operands_mem_op0_addr", Cpu, "all cycles"),
+            ConstraintInfo::new("cpu_operands_mem_op1_addr", Cpu, "all cycles"),
+            ConstraintInfo::new("cpu_operands_ops_mul", Cpu, "all cycles"),
+            ConstraintInfo::new("cpu_operands_res", Cpu, "all cycles"),
+            ConstraintInfo::new(
+                "cpu_update_registers_update_pc_tmp0",
+                Cpu,
+                "all cycles except last",
+            ),
+            ConstraintInfo::new(
+                "cpu_update_registers_update_pc_tmp1",
+                Cpu,
+                "all cycles except last",
+            ),
+            ConstraintInfo::new(
+                "cpu_update_registers_update_pc_pc_cond_negative",
+                Cpu,
+                "all cycles except last",
+            ),
+            ConstraintInfo::new(
+                "cpu_update_registers_update_pc_pc_cond_positive",
+                Cpu,
+                "all cycles except last",
+            ),
+            ConstraintInfo::new(
+                "cpu_update_registers_update_ap_ap_update",
+                Cpu,
+                "all cycles except last",
+            ),
+            ConstraintInfo::new(
+                "cpu_update_registers_update_fp_fp_update",
+                Cpu,
+                "all cycles except last",
+            ),
+            ConstraintInfo::new("cpu_opcodes_call_push_fp", Cpu, "all cycles"),
+            ConstraintInfo::new("cpu_opcodes_call_push_pc", Cpu, "all cycles"),
+            ConstraintInfo::new("cpu_opcodes_call_off0", Cpu, "all cycles"),
+            ConstraintInfo::new("cpu_opcodes_call_off1", Cpu, "all cycles"),
+            ConstraintInfo::new("cpu_opcodes_call_flags", Cpu, "all cycles"),
+            ConstraintInfo::new("cpu_opcodes_ret_off0", Cpu, "all cycles"),
+            ConstraintInfo::new("cpu_opcodes_ret_off2", Cpu, "all cycles"),
+            ConstraintInfo::new("cpu_opcodes_ret_flags", Cpu, "all cycles"),
+            ConstraintInfo::new("cpu_opcodes_assert_eq_assert_eq", Cpu, "all cycles"),
+            ConstraintInfo::new("initial_ap", Cpu, "first row"),
+            ConstraintInfo::new("initial_fp", Cpu, "first row"),
+            ConstraintInfo::new("initial_pc", Cpu, "first row"),
+            ConstraintInfo::new("final_ap", Cpu, "last cycle"),
+            ConstraintInfo::new("final_fp", Cpu, "last cycle"),
+            ConstraintInfo::new("final_pc", Cpu, "last cycle"),
+            ConstraintInfo::new("memory_multi_column_perm_perm_init0", Memory, "first row"),
+            ConstraintInfo::new(
+                "memory_multi_column_perm_perm_step0",
+                Memory,
+                "every second row except last",
+            ),
+            ConstraintInfo::new(
+                "memory_multi_column_perm_perm_last",
+                Memory,
+                "second last row",
+            ),
+            ConstraintInfo::new("memory_diff_is_bit", Memory, "every second row except last"),
+            ConstraintInfo::new("memory_is_func", Memory, "every second row except last"),
+            ConstraintInfo::new("memory_initial_addr", Memory, "first row"),
+            ConstraintInfo::new("public_memory_addr_zero", PublicMemory, "all cycles"),
+            ConstraintInfo::new("public_memory_value_zero", PublicMemory, "all cycles"),
+            ConstraintInfo::new("rc16_perm_init0", RangeCheck, "first row"),
+            ConstraintInfo::new(
+                "rc16_perm_step0",
+                RangeCheck,
+                "every fourth row except last",
+            ),
+            ConstraintInfo::new("rc16_perm_last", RangeCheck, "fourth last row"),
+            ConstraintInfo::new(
+                "rc16_diff_is_bit",
+                RangeCheck,
+                "every fourth row except last",
+            ),
+            ConstraintInfo::new("rc16_minimum", RangeCheck, "first row"),
+            ConstraintInfo::new("rc16_maximum", RangeCheck, "fourth last row"),
+            ConstraintInfo::new("diluted_check_permutation_init0", DilutedCheck, "first row"),
+            ConstraintInfo::new(
+                "diluted_check_permutation_step0",
+                DilutedCheck,
+                "every row except last",
+            ),
+            ConstraintInfo::new("diluted_check_permutation_last", DilutedCheck, "last row"),
+            ConstraintInfo::new("diluted_check_init", DilutedCheck, "first row"),
+            ConstraintInfo::new("diluted_check_first_element", DilutedCheck, "first row"),
+            ConstraintInfo::new("diluted_check_step", DilutedCheck, "every row except last"),
+            ConstraintInfo::new("diluted_check_last", DilutedCheck, "last row"),
+            ConstraintInfo::new(
+                "pedersen_hash0_ec_subset_sub_bit_unpacking_last_one_is_zero",
+                Pedersen,
+                "every 1024 rows",
+            ),
+            ConstraintInfo::new(
+                "pedersen_hash0_ec_subset_sub_bit_unpacking_zeros_between_ones",
+                Pedersen,
+                "every 1024 rows",
+            ),
+            ConstraintInfo::new(
+                "pedersen_hash0_ec_subset_sum_bit_unpacking_cumulative_bit192",
+                Pedersen,
+                "every 1024 rows",
+            ),
+            ConstraintInfo::new(
+                "pedersen_hash0_ec_subset_sum_bit_unpacking_zeroes_between_ones192",
+                Pedersen,
+                "every 1024 rows",
+            ),
+            ConstraintInfo::new(
+                "pedersen_hash0_ec_subset_sum_bit_unpacking_cumulative_bit196",
+                Pedersen,
+                "every 1024 rows",
+            ),
+            ConstraintInfo::new(
+                "pedersen_hash0_ec_subset_sum_bit_unpacking_zeroes_between_ones196",
+                Pedersen,
+                "every 1024 rows",
+            ),
+            ConstraintInfo::new(
+                "pedersen_hash0_ec_subset_sum_booleanity_test",
+                Pedersen,
+                "pedersen transition",
+            ),
+            ConstraintInfo::new(
+                "pedersen_hash0_ec_subset_sum_bit_extraction_end",
+                Pedersen,
+                "pedersen zero suffix",
+            ),
+            ConstraintInfo::new(
+                "pedersen_hash0_ec_subset_sum_zeros_tail",
+                Pedersen,
+                "zeros tail of each instance",
+            ),
+            ConstraintInfo::new(
+                "pedersen_hash0_ec_subset_sum_add_points_slope",
+                Pedersen,
+                "pedersen transition",
+            ),
+            ConstraintInfo::new(
+                "pedersen_hash0_ec_subset_sum_add_points_x",
+                Pedersen,
+                "pedersen transition",
+            ),
+            ConstraintInfo::new(
+                "pedersen_hash0_ec_subset_sum_add_points_y",
+                Pedersen,
+                "pedersen transition",
+            ),
+            ConstraintInfo::new(
+                "pedersen_hash0_ec_subset_sum_copy_point_x",
+                Pedersen,
+                "pedersen transition",
+            ),
+            ConstraintInfo::new(
+                "pedersen_hash0_ec_subset_sum_copy_point_y",
+                Pedersen,
+                "pedersen transition",
+            ),
+            ConstraintInfo::new("pedersen_hash0_copy_point_x", Pedersen, "every 2048 rows"),
+            ConstraintInfo::new("pedersen_hash0_copy_point_y", Pedersen, "every 2048 rows"),
+            ConstraintInfo::new("pedersen_hash0_init_x", Pedersen, "every 2048 rows"),
+            ConstraintInfo::new("pedersen_hash0_init_y", Pedersen, "every 2048 rows"),
+            ConstraintInfo::new("pedersen_input0_value0", Pedersen, "every 2048 rows"),
+            ConstraintInfo::new(
+                "pedersen_input0_addr",
+                Pedersen,
+                "every 2048 rows except last",
+            ),
+            ConstraintInfo::new("pedersen_init_addr", Pedersen, "first row"),
+            ConstraintInfo::new("pedersen_input1_value0", Pedersen, "every 2048 rows"),
+            ConstraintInfo::new("pedersen_input1_addr", Pedersen, "every 2048 rows"),
+            ConstraintInfo::new("pedersen_output_value0", Pedersen, "every 2048 rows"),
+            ConstraintInfo::new("pedersen_output_addr", Pedersen, "every 2048 rows"),
+            ConstraintInfo::new("rc_builtin_value", RangeCheckBuiltin, "every 128 rows"),
+            ConstraintInfo::new(
+                "rc_builtin_addr_step",
+                RangeCheckBuiltin,
+                "every 128 rows except last",
+            ),
+            ConstraintInfo::new("rc_builtin_init_addr", RangeCheckBuiltin, "first row"),
+            ConstraintInfo::new("bitwise_init_var_pool_addr", Bitwise, "first row"),
+            ConstraintInfo::new("bitwise_step_var_pool_addr", Bitwise, "bitwise transition"),
+            ConstraintInfo::new("bitwise_x_or_y_addr", Bitwise, "all bitwise"),
+            ConstraintInfo::new(
+                "bitwise_next_var_pool_addr",
+                Bitwise,
+                "all bitwise except last",
+            ),
+            ConstraintInfo::new("bitwise_partition", Bitwise, "every 32 rows"),
+            ConstraintInfo::new("bitwise_or_is_and_plus_xor", Bitwise, "all bitwise"),
+            ConstraintInfo::new(
+                "bitwise_addition_is_xor_with_and",
+                Bitwise,
+                "every 16 bit segment",
+            ),
+            ConstraintInfo::new("bitwise_unique_unpacking192", Bitwise, "all bitwise"),
+            ConstraintInfo::new("bitwise_unique_unpacking193", Bitwise, "all bitwise"),
+            ConstraintInfo::new("bitwise_unique_unpacking194", Bitwise, "all bitwise"),
+            ConstraintInfo::new("bitwise_unique_unpacking195", Bitwise, "all bitwise"),
+        ]
+    };
+}
+
 // Trace column 4 - memory
 #[derive(Clone, Copy)]
 pub enum Mem {
@@ -1,5 +1,7 @@
-//! Matches `bitwise` layout from StarkWare's open source verifier
-//! <https://github.com/starkware-libs/cairo-lang/blob/361fe32d5930db340ea78fe05aedfe706f6c9405/src/starkware/cairo/lang/instances.py#L157>
+//! Matches `recursive` layout from StarkWare's open source verifier
+//! <https://github.com/starkware-libs/cairo-lang/blob/361fe32d5930db340ea78fe05aedfe706f6c9405/src/starkware/cairo/lang/instances.py>
+//!
+//! Builtins: output, pedersen, range_check and bitwise.
 
 //TODO This is still the starknet layout and has to be updated to the recursive
 // parameters.
@@ -8,15 +10,20 @@ pub mod air;
 pub mod trace;
 
 pub use air::AirConfig;
+use binary::vm::LayoutParams;
+use binary::Layout;
 pub use ministark_gpu::fields::p3618502788666131213697322783095070105623107215331596699973092056135872020481::ark::Fp;
 pub use trace::ExecutionTrace;
 
 // TODO Are these correct?
 // must be a power-of-two
 pub const CYCLE_HEIGHT: usize = 16;
+/// 8 memory units per step with a public memory fraction of 8
 pub const PUBLIC_MEMORY_STEP: usize = 16;
 pub const MEMORY_STEP: usize = 2;
+/// 4 range check units per step
 pub const RANGE_CHECK_STEP: usize = 4;
+/// 16 diluted units per step
 pub const DILUTED_CHECK_STEP: usize = 1; //TODO is that correct?
 
 /// How many cycles per pedersen hash
@@ -26,10 +33,30 @@ pub const PEDERSEN_BUILTIN_RATIO: usize = 128;
 pub const RANGE_CHECK_BUILTIN_RATIO: usize = 8;
 pub const RANGE_CHECK_BUILTIN_PARTS: usize = 8;
 
-pub const NUM_BASE_COLUMNS: usize = 9;
-pub const NUM_EXTENSION_COLUMNS: usize = 1;
+pub const NUM_BASE_COLUMNS: usize = 7;
+pub const NUM_EXTENSION_COLUMNS: usize = 3;
 
 pub const DILUTED_CHECK_N_BITS: usize = 16;
 pub const DILUTED_CHECK_SPACING: usize = 4;
 
 pub const BITWISE_RATIO: usize = 8;
+
+/// Parameters used by the Cairo VM to pad executions to fit this layout
+pub const VM_PARAMS: LayoutParams = LayoutParams {
+    layout: Layout::Recursive,
+    rc_units: CYCLE_HEIGHT / RANGE_CHECK_STEP,
+    memory_units: CYCLE_HEIGHT / MEMORY_STEP,
+    public_memory_fraction: PUBLIC_MEMORY_STEP / MEMORY_STEP,
+    diluted_units: CYCLE_HEIGHT / DILUTED_CHECK_STEP,
+    diluted_spacing: DILUTED_CHECK_SPACING,
+    diluted_n_bits: DILUTED_CHECK_N_BITS,
+    range_check_parts: RANGE_CHECK_BUILTIN_PARTS,
+    output: true,
+    pedersen_ratio: Some(PEDERSEN_BUILTIN_RATIO),
+    range_check_ratio: Some(RANGE_CHECK_BUILTIN_RATIO),
+    ecdsa_ratio: None,
+    bitwise_ratio: Some(BITWISE_RATIO),
+    ec_op_ratio: None,
+    keccak_ratio: None,
+    poseidon_ratio: None,
+};
@@ -134,7 +134,7 @@ impl CairoTrace for ExecutionTrace {
 
         // add offsets to the range check pool
         for &RegisterState { pc, .. } in register_states.iter() {
-            let word = memory[pc].unwrap();
+            let word = memory.get(pc).unwrap();
             rc_pool.push(word.get_off_dst());
             rc_pool.push(word.get_off_op0());
             rc_pool.push(word.get_off_op1());
@@ -177,7 +177,7 @@ impl CairoTrace for ExecutionTrace {
             .for_each(
                 |((((rc_cycle, aux_cycle), npc_cycle), flag_cycle), registers)| {
                     let &RegisterState { pc, ap, fp } = registers;
-                    let insrtuction = memory[pc].unwrap();
+                    let insrtuction = memory.get(pc).unwrap();
                     let insrtuction_felt = insrtuction.into_felt();
                     debug_assert!(!insrtuction.get_flag(Flag::Zero.into()));
 
@@ -0,0 +1,30 @@
+//! Parameters of the `recursive_large_output` layout from StarkWare's open
+//! source verifier <https://github.com/starkware-libs/cairo-lang/blob/361fe32d5930db340ea78fe05aedfe706f6c9405/src/starkware/cairo/lang/instances.py>
+//!
+//! Builtins: output, pedersen, range_check, bitwise and poseidon. Same as the
+//! `recursive` layout plus a poseidon builtin so large outputs can be hashed
+//! with poseidon rather than pedersen.
+//!
+//! Only the parameters and the public input encoding exist for this layout.
+//! There's no trace or AIR yet so programs can't be proven with it.
+
+// TODO: add the trace and AIR for this layout. The CPU, memory and builtin
+// components other than poseidon are laid out like the `recursive` layout.
+
+pub use crate::recursive::BITWISE_RATIO;
+pub use crate::recursive::CYCLE_HEIGHT;
+pub use crate::recursive::DILUTED_CHECK_N_BITS;
+pub use crate::recursive::DILUTED_CHECK_SPACING;
+pub use crate::recursive::DILUTED_CHECK_STEP;
+pub use crate::recursive::MEMORY_STEP;
+pub use crate::recursive::PEDERSEN_BUILTIN_RATIO;
+pub use crate::recursive::PUBLIC_MEMORY_STEP;
+pub use crate::recursive::RANGE_CHECK_BUILTIN_PARTS;
+pub use crate::recursive::RANGE_CHECK_BUILTIN_RATIO;
+pub use crate::recursive::RANGE_CHECK_STEP;
+pub use crate::starknet::POSEIDON_M;
+pub use crate::starknet::POSEIDON_ROUNDS_FULL;
+pub use crate::starknet::POSEIDON_ROUNDS_PARTIAL;
+
+/// How many cycles per poseidon hash
+pub const POSEIDON_RATIO: usize = 8;
@@ -0,0 +1,32 @@
+//! Parameters of the `small` layout from StarkWare's open source verifier
+//! <https://github.com/starkware-libs/cairo-lang/blob/361fe32d5930db340ea78fe05aedfe706f6c9405/src/starkware/cairo/lang/instances.py#L40>
+//!
+//! Builtins: output, pedersen, range_check and ecdsa.
+//!
+//! Only the parameters and the public input encoding exist for this layout.
+//! There's no trace or AIR yet so programs can't be proven with it.
+
+// TODO: add the trace and AIR for this layout. Unlike the `starknet` and
+// `recursive` layouts this layout isn't "packed" into a handful of columns.
+// Each pedersen repetition gets its own set of columns so the trace generation
+// can't be shared with the other layouts.
+
+// must be a power-of-two
+pub const CYCLE_HEIGHT: usize = 16;
+pub const PUBLIC_MEMORY_STEP: usize = 8;
+pub const MEMORY_STEP: usize = 2;
+pub const RANGE_CHECK_STEP: usize = 1;
+
+/// How many cycles per pedersen hash
+pub const PEDERSEN_BUILTIN_RATIO: usize = 8;
+pub const PEDERSEN_BUILTIN_REPETITIONS: usize = 4;
+
+/// How many cycles per 128 bit range check
+pub const RANGE_CHECK_BUILTIN_RATIO: usize = 8;
+pub const RANGE_CHECK_BUILTIN_PARTS: usize = 8;
+
+pub const ECDSA_BUILTIN_RATIO: usize = 512;
+pub const ECDSA_BUILTIN_REPETITIONS: usize = 1;
+
+pub const NUM_BASE_COLUMNS: usize = 23;
+pub const NUM_EXTENSION_COLUMNS: usize = 2;
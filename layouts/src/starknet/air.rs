@@ -16,6 +16,9 @@ use super::DILUTED_CHECK_SPACING;
 use super::ECDSA_SIG_CONFIG_ALPHA;
 use super::ECDSA_SIG_CONFIG_BETA;
 use crate::CairoAirConfig;
+use crate::constraints::ConstraintGroup;
+use crate::constraints::ConstraintInfo;
+use crate::constraints::ConstraintRegistry;
 use crate::utils;
 use crate::utils::compute_diluted_cumulative_value;
 use crate::utils::map_into_fp_array;
@@ -3113,6 +3116,557 @@ impl CairoAirConfig for AirConfig {
     }
 }
 
+impl ConstraintRegistry for AirConfig {
+    const CONSTRAINTS: &'static [ConstraintInfo] = {
+        use ConstraintGroup::*;
+        &[
+            ConstraintInfo::new("cpu_decode_opcode_rc_b", Cpu, "every row except flag 15"),
+            ConstraintInfo::new("cpu_decode_opcode_rc_zero", Cpu, "flag 15 of each cycle"),
+            ConstraintInfo::new("cpu_decode_opcode_rc_input", Cpu, "all cycles"),
+            ConstraintInfo::new("cpu_decode_flag_op1_base_op0_bit", Cpu, "all cycles"),
+            ConstraintInfo::new("cpu_decode_flag_res_op1_bit", Cpu, "all cycles"),
+            ConstraintInfo::new("cpu_decode_flag_pc_update_regular_bit", Cpu, "all cycles"),
+            ConstraintInfo::new("cpu_decode_fp_update_regular_bit", Cpu, "all cycles"),
+            ConstraintInfo::new("cpu_operands_mem_dst_addr", Cpu, "all cycles"),
+            ConstraintInfo::new("cpu_operands_mem_op0_addr", Cpu, "all cycles"),
+            ConstraintInfo::new("cpu_operands_mem_op1_addr", Cpu, "all cycles"),
+            ConstraintInfo::new("cpu_operands_ops_mul", Cpu, "all cycles"),
+            ConstraintInfo::new("cpu_operands_res", Cpu, "all cycles"),
+            ConstraintInfo::new(
+                "cpu_update_registers_update_pc_tmp0",
+                Cpu,
+                "all cycles except last",
+            ),
+            ConstraintInfo::new(
+                "cpu_update_registers_update_pc_tmp1",
+                Cpu,
+                "all cycles except last",
+            ),
+            ConstraintInfo::new(
+                "cpu_update_registers_update_pc_pc_cond_negative",
+                Cpu,
+                "all cycles except last",
+            ),
+            ConstraintInfo::new(
+                "cpu_update_registers_update_pc_pc_cond_positive",
+                Cpu,
+                "all cycles except last",
+            ),
+            ConstraintInfo::new(
+                "cpu_update_registers_update_ap_ap_update",
+                Cpu,
+                "all cycles except last",
+            ),
+            ConstraintInfo::new(
+                "cpu_update_registers_update_fp_fp_update",
+                Cpu,
+                "all cycles except last",
+            ),
+            ConstraintInfo::new("cpu_opcodes_call_push_fp", Cpu, "all cycles"),
+            ConstraintInfo::new("cpu_opcodes_call_push_pc", Cpu, "all cycles"),
+            ConstraintInfo::new("cpu_opcodes_call_off0", Cpu, "all cycles"),
+            ConstraintInfo::new("cpu_opcodes_call_off1", Cpu, "all cycles"),
+            ConstraintInfo::new("cpu_opcodes_call_flags", Cpu, "all cycles"),
+            ConstraintInfo::new("cpu_opcodes_ret_off0", Cpu, "all cycles"),
+            ConstraintInfo::new("cpu_opcodes_ret_off2", Cpu, "all cycles"),
+            ConstraintInfo::new("cpu_opcodes_ret_flags", Cpu, "all cycles"),
+            ConstraintInfo::new("cpu_opcodes_assert_eq_assert_eq", Cpu, "all cycles"),
+            ConstraintInfo::new("initial_ap", Cpu, "first row"),
+            ConstraintInfo::new("initial_fp", Cpu, "first row"),
+            ConstraintInfo::new("initial_pc", Cpu, "first row"),
+            ConstraintInfo::new("final_ap", Cpu, "last cycle"),
+            ConstraintInfo::new("final_fp", Cpu, "last cycle"),
+            ConstraintInfo::new("final_pc", Cpu, "last cycle"),
+            ConstraintInfo::new("memory_multi_column_perm_perm_init0", Memory, "first row"),
+            ConstraintInfo::new(
+                "memory_multi_column_perm_perm_step0",
+                Memory,
+                "every second row except last",
+            ),
+            ConstraintInfo::new(
+                "memory_multi_column_perm_perm_last",
+                Memory,
+                "second last row",
+            ),
+            ConstraintInfo::new("memory_diff_is_bit", Memory, "every second row except last"),
+            ConstraintInfo::new("memory_is_func", Memory, "every second row except last"),
+            ConstraintInfo::new("memory_initial_addr", Memory, "first row"),
+            ConstraintInfo::new("public_memory_addr_zero", PublicMemory, "every eighth row"),
+            ConstraintInfo::new("public_memory_value_zero", PublicMemory, "every eighth row"),
+            ConstraintInfo::new("rc16_perm_init0", RangeCheck, "first row"),
+            ConstraintInfo::new(
+                "rc16_perm_step0",
+                RangeCheck,
+                "every fourth row except last",
+            ),
+            ConstraintInfo::new("rc16_perm_last", RangeCheck, "fourth last row"),
+            ConstraintInfo::new(
+                "rc16_diff_is_bit",
+                RangeCheck,
+                "every fourth row except last",
+            ),
+            ConstraintInfo::new("rc16_minimum", RangeCheck, "first row"),
+            ConstraintInfo::new("rc16_maximum", RangeCheck, "fourth last row"),
+            ConstraintInfo::new("diluted_check_permutation_init0", DilutedCheck, "first row"),
+            ConstraintInfo::new(
+                "diluted_check_permutation_step0",
+                DilutedCheck,
+                "every 8 rows except last",
+            ),
+            ConstraintInfo::new(
+                "diluted_check_permutation_last",
+                DilutedCheck,
+                "8th last row",
+            ),
+            ConstraintInfo::new("diluted_check_init", DilutedCheck, "first row"),
+            ConstraintInfo::new("diluted_check_first_element", DilutedCheck, "first row"),
+            ConstraintInfo::new(
+                "diluted_check_step",
+                DilutedCheck,
+                "every 8 rows except last",
+            ),
+            ConstraintInfo::new("diluted_check_last", DilutedCheck, "8th last row"),
+            ConstraintInfo::new(
+                "pedersen_hash0_ec_subset_sub_bit_unpacking_last_one_is_zero",
+                Pedersen,
+                "every 256 rows",
+            ),
+            ConstraintInfo::new(
+                "pedersen_hash0_ec_subset_sub_bit_unpacking_zeros_between_ones",
+                Pedersen,
+                "every 256 rows",
+            ),
+            ConstraintInfo::new(
+                "pedersen_hash0_ec_subset_sum_bit_unpacking_cumulative_bit192",
+                Pedersen,
+                "every 256 rows",
+            ),
+            ConstraintInfo::new(
+                "pedersen_hash0_ec_subset_sum_bit_unpacking_zeroes_between_ones192",
+                Pedersen,
+                "every 256 rows",
+            ),
+            ConstraintInfo::new(
+                "pedersen_hash0_ec_subset_sum_bit_unpacking_cumulative_bit196",
+                Pedersen,
+                "every 256 rows",
+            ),
+            ConstraintInfo::new(
+                "pedersen_hash0_ec_subset_sum_bit_unpacking_zeroes_between_ones196",
+                Pedersen,
+                "every 256 rows",
+            ),
+            ConstraintInfo::new(
+                "pedersen_hash0_ec_subset_sum_booleanity_test",
+                Pedersen,
+                "pedersen transition",
+            ),
+            ConstraintInfo::new(
+                "pedersen_hash0_ec_subset_sum_bit_extraction_end",
+                Pedersen,
+                "pedersen zero suffix",
+            ),
+            ConstraintInfo::new(
+                "pedersen_hash0_ec_subset_sum_zeros_tail",
+                Pedersen,
+                "zeros tail of each instance",
+            ),
+            ConstraintInfo::new(
+                "pedersen_hash0_ec_subset_sum_add_points_slope",
+                Pedersen,
+                "pedersen transition",
+            ),
+            ConstraintInfo::new(
+                "pedersen_hash0_ec_subset_sum_add_points_x",
+                Pedersen,
+                "pedersen transition",
+            ),
+            ConstraintInfo::new(
+                "pedersen_hash0_ec_subset_sum_add_points_y",
+                Pedersen,
+                "pedersen transition",
+            ),
+            ConstraintInfo::new(
+                "pedersen_hash0_ec_subset_sum_copy_point_x",
+                Pedersen,
+                "pedersen transition",
+            ),
+            ConstraintInfo::new(
+                "pedersen_hash0_ec_subset_sum_copy_point_y",
+                Pedersen,
+                "pedersen transition",
+            ),
+            ConstraintInfo::new("pedersen_hash0_copy_point_x", Pedersen, "every 512 rows"),
+            ConstraintInfo::new("pedersen_hash0_copy_point_y", Pedersen, "every 512 rows"),
+            ConstraintInfo::new("pedersen_hash0_init_x", Pedersen, "every 512 rows"),
+            ConstraintInfo::new("pedersen_hash0_init_y", Pedersen, "every 512 rows"),
+            ConstraintInfo::new("pedersen_input0_value0", Pedersen, "every 512 rows"),
+            ConstraintInfo::new(
+                "pedersen_input0_addr",
+                Pedersen,
+                "every 512 rows except last",
+            ),
+            ConstraintInfo::new("pedersen_init_addr", Pedersen, "first row"),
+            ConstraintInfo::new("pedersen_input1_value0", Pedersen, "every 512 rows"),
+            ConstraintInfo::new("pedersen_input1_addr", Pedersen, "every 512 rows"),
+            ConstraintInfo::new("pedersen_output_value0", Pedersen, "every 512 rows"),
+            ConstraintInfo::new("pedersen_output_addr", Pedersen, "every 512 rows"),
+            ConstraintInfo::new("rc_builtin_value", RangeCheckBuiltin, "every 256 rows"),
+            ConstraintInfo::new(
+                "rc_builtin_addr_step",
+                RangeCheckBuiltin,
+                "every 256 rows except last",
+            ),
+            ConstraintInfo::new("rc_builtin_init_addr", RangeCheckBuiltin, "first row"),
+            ConstraintInfo::new(
+                "ecdsa_signature0_doubling_key_slope",
+                Ecdsa,
+                "ec op transition",
+            ),
+            ConstraintInfo::new("ecdsa_signature0_doubling_key_x", Ecdsa, "ec op transition"),
+            ConstraintInfo::new("ecdsa_signature0_doubling_key_y", Ecdsa, "ec op transition"),
+            ConstraintInfo::new(
+                "ecdsa_signature0_exponentiate_generator_booleanity_test",
+                Ecdsa,
+                "ecdsa transition",
+            ),
+            ConstraintInfo::new(
+                "ecdsa_signature0_exponentiate_generator_bit_extraction_end",
+                Ecdsa,
+                "ecdsa zero suffix",
+            ),
+            ConstraintInfo::new(
+                "ecdsa_signature0_exponentiate_generator_zeros_tail",
+                Ecdsa,
+                "zeros tail of each instance",
+            ),
+            ConstraintInfo::new(
+                "ecdsa_signature0_exponentiate_generator_add_points_slope",
+                Ecdsa,
+                "ecdsa transition",
+            ),
+            ConstraintInfo::new(
+                "ecdsa_signature0_exponentiate_generator_add_points_x",
+                Ecdsa,
+                "ecdsa transition",
+            ),
+            ConstraintInfo::new(
+                "ecdsa_signature0_exponentiate_generator_add_points_y",
+                Ecdsa,
+                "ecdsa transition",
+            ),
+            ConstraintInfo::new(
+                "ecdsa_signature0_exponentiate_generator_add_points_x_diff_inv",
+                Ecdsa,
+                "ecdsa transition",
+            ),
+            ConstraintInfo::new(
+                "ecdsa_signature0_exponentiate_generator_copy_point_x",
+                Ecdsa,
+                "ecdsa transition",
+            ),
+            ConstraintInfo::new(
+                "ecdsa_signature0_exponentiate_generator_copy_point_y",
+                Ecdsa,
+                "ecdsa transition",
+            ),
+            ConstraintInfo::new(
+                "ecdsa_signature0_exponentiate_key_booleanity_test",
+                Ecdsa,
+                "ec op transition",
+            ),
+            ConstraintInfo::new(
+                "ecdsa_signature0_exponentiate_key_bit_extraction_end",
+                Ecdsa,
+                "ec op zero suffix",
+            ),
+            ConstraintInfo::new(
+                "ecdsa_signature0_exponentiate_key_zeros_tail",
+                Ecdsa,
+                "zeros tail of each instance",
+            ),
+            ConstraintInfo::new(
+                "ecdsa_signature0_exponentiate_key_add_points_slope",
+                Ecdsa,
+                "ec op transition",
+            ),
+            ConstraintInfo::new(
+                "ecdsa_signature0_exponentiate_key_add_points_x",
+                Ecdsa,
+                "ec op transition",
+            ),
+            ConstraintInfo::new(
+                "ecdsa_signature0_exponentiate_key_add_points_y",
+                Ecdsa,
+                "ec op transition",
+            ),
+            ConstraintInfo::new(
+                "ecdsa_signature0_exponentiate_key_add_points_x_diff_inv",
+                Ecdsa,
+                "ec op transition",
+            ),
+            ConstraintInfo::new(
+                "ecdsa_signature0_exponentiate_key_copy_point_x",
+                Ecdsa,
+                "ec op transition",
+            ),
+            ConstraintInfo::new(
+                "ecdsa_signature0_exponentiate_key_copy_point_y",
+                Ecdsa,
+                "ec op transition",
+            ),
+            ConstraintInfo::new("ecdsa_signature0_init_gen_x", Ecdsa, "all ecdsa"),
+            ConstraintInfo::new("ecdsa_signature0_init_gen_y", Ecdsa, "all ecdsa"),
+            ConstraintInfo::new("ecdsa_signature0_init_key_x", Ecdsa, "all ec op"),
+            ConstraintInfo::new("ecdsa_signature0_init_key_y", Ecdsa, "all ec op"),
+            ConstraintInfo::new("ecdsa_signature0_add_results_slope", Ecdsa, "all ecdsa"),
+            ConstraintInfo::new("ecdsa_signature0_add_results_x", Ecdsa, "all ecdsa"),
+            ConstraintInfo::new("ecdsa_signature0_add_results_y", Ecdsa, "all ecdsa"),
+            ConstraintInfo::new(
+                "ecdsa_signature0_add_results_x_diff_inv",
+                Ecdsa,
+                "all ecdsa",
+            ),
+            ConstraintInfo::new("ecdsa_signature0_extract_r_slope", Ecdsa, "all ecdsa"),
+            ConstraintInfo::new("ecdsa_signature0_extract_r_x", Ecdsa, "all ecdsa"),
+            ConstraintInfo::new("ecdsa_signature0_extract_r_x_diff_inv", Ecdsa, "all ecdsa"),
+            ConstraintInfo::new("ecdsa_signature0_z_nonzero", Ecdsa, "all ecdsa"),
+            ConstraintInfo::new("ecdsa_signature0_r_and_w_nonzero", Ecdsa, "all ec op"),
+            ConstraintInfo::new("ecdsa_signature0_q_on_curve_x_squared", Ecdsa, "all ecdsa"),
+            ConstraintInfo::new("ecdsa_signature0_q_on_curve_on_curve", Ecdsa, "all ecdsa"),
+            ConstraintInfo::new("ecdsa_init_addr", Ecdsa, "first row"),
+            ConstraintInfo::new("ecdsa_message_addr", Ecdsa, "all ecdsa"),
+            ConstraintInfo::new("ecdsa_pubkey_addr", Ecdsa, "all ecdsa except last"),
+            ConstraintInfo::new("ecdsa_message_value0", Ecdsa, "all ecdsa"),
+            ConstraintInfo::new("ecdsa_pubkey_value0", Ecdsa, "all ecdsa"),
+            ConstraintInfo::new("bitwise_init_var_pool_addr", Bitwise, "first row"),
+            ConstraintInfo::new("bitwise_step_var_pool_addr", Bitwise, "bitwise transition"),
+            ConstraintInfo::new("bitwise_x_or_y_addr", Bitwise, "all bitwise"),
+            ConstraintInfo::new(
+                "bitwise_next_var_pool_addr",
+                Bitwise,
+                "all bitwise except last",
+            ),
+            ConstraintInfo::new("bitwise_partition", Bitwise, "every 256 rows"),
+            ConstraintInfo::new("bitwise_or_is_and_plus_xor", Bitwise, "all bitwise"),
+            ConstraintInfo::new(
+                "bitwise_addition_is_xor_with_and",
+                Bitwise,
+                "every 16 bit segment",
+            ),
+            ConstraintInfo::new("bitwise_unique_unpacking192", Bitwise, "all bitwise"),
+            ConstraintInfo::new("bitwise_unique_unpacking193", Bitwise, "all bitwise"),
+            ConstraintInfo::new("bitwise_unique_unpacking194", Bitwise, "all bitwise"),
+            ConstraintInfo::new("bitwise_unique_unpacking195", Bitwise, "all bitwise"),
+            ConstraintInfo::new("ec_op_init_addr", EcOp, "first row"),
+            ConstraintInfo::new("ec_op_p_x_addr", EcOp, "all ec op except last"),
+            ConstraintInfo::new("ec_op_p_y_addr", EcOp, "all ec op"),
+            ConstraintInfo::new("ec_op_q_x_addr", EcOp, "all ec op"),
+            ConstraintInfo::new("ec_op_q_y_addr", EcOp, "all ec op"),
+            ConstraintInfo::new("ec_op_m_addr", EcOp, "all ec op"),
+            ConstraintInfo::new("ec_op_r_x_addr", EcOp, "all ec op"),
+            ConstraintInfo::new("ec_op_r_y_addr", EcOp, "all ec op"),
+            ConstraintInfo::new("ec_op_doubling_q_slope", EcOp, "ec op transition"),
+            ConstraintInfo::new("ec_op_doubling_q_x", EcOp, "ec op transition"),
+            ConstraintInfo::new("ec_op_doubling_q_y", EcOp, "ec op transition"),
+            ConstraintInfo::new("ec_op_get_q_x", EcOp, "all ec op"),
+            ConstraintInfo::new("ec_op_get_q_y", EcOp, "all ec op"),
+            ConstraintInfo::new(
+                "ec_op_ec_subset_sum_bit_unpacking_last_one_is_zero",
+                EcOp,
+                "all ec op",
+            ),
+            ConstraintInfo::new(
+                "ec_op_ec_subset_sum_bit_unpacking_zeroes_between_ones0",
+                EcOp,
+                "all ec op",
+            ),
+            ConstraintInfo::new(
+                "ec_op_ec_subset_sum_bit_unpacking_cumulative_bit192",
+                EcOp,
+                "all ec op",
+            ),
+            ConstraintInfo::new(
+                "ec_op_ec_subset_sum_bit_unpacking_zeroes_between_ones192",
+                EcOp,
+                "all ec op",
+            ),
+            ConstraintInfo::new(
+                "ec_op_ec_subset_sum_bit_unpacking_cumulative_bit196",
+                EcOp,
+                "all ec op",
+            ),
+            ConstraintInfo::new(
+                "ec_op_ec_subset_sum_bit_unpacking_zeroes_between_ones196",
+                EcOp,
+                "all ec op",
+            ),
+            ConstraintInfo::new(
+                "ec_op_ec_subset_sum_booleanity_test",
+                EcOp,
+                "ec op transition",
+            ),
+            ConstraintInfo::new(
+                "ec_op_ec_subset_sum_bit_extraction_end",
+                EcOp,
+                "ec op zero suffix",
+            ),
+            ConstraintInfo::new(
+                "ec_op_ec_subset_sum_zeros_tail",
+                EcOp,
+                "zeros tail of each instance",
+            ),
+            ConstraintInfo::new(
+                "ec_op_ec_subset_sum_add_points_slope",
+                EcOp,
+                "ec op transition",
+            ),
+            ConstraintInfo::new("ec_op_ec_subset_sum_add_points_x", EcOp, "ec op transition"),
+            ConstraintInfo::new("ec_op_ec_subset_sum_add_points_y", EcOp, "ec op transition"),
+            ConstraintInfo::new(
+                "ec_op_ec_subset_sum_add_points_x_diff_inv",
+                EcOp,
+                "ec op transition",
+            ),
+            ConstraintInfo::new("ec_op_ec_subset_sum_copy_point_x", EcOp, "ec op transition"),
+            ConstraintInfo::new("ec_op_ec_subset_sum_copy_point_y", EcOp, "ec op transition"),
+            ConstraintInfo::new("ec_op_get_m", EcOp, "all ec op"),
+            ConstraintInfo::new("ec_op_get_p_x", EcOp, "all ec op"),
+            ConstraintInfo::new("ec_op_get_p_y", EcOp, "all ec op"),
+            ConstraintInfo::new("ec_op_set_r_x", EcOp, "all ec op"),
+            ConstraintInfo::new("ec_op_set_r_y", EcOp, "all ec op"),
+            ConstraintInfo::new("poseidon_init_input_output_addr", Poseidon, "first row"),
+            ConstraintInfo::new(
+                "poseidon_addr_input_output_step_inner",
+                Poseidon,
+                "poseidon inputs outputs step",
+            ),
+            ConstraintInfo::new(
+                "poseidon_addr_input_output_step_outter",
+                Poseidon,
+                "all poseidon except last",
+            ),
+            ConstraintInfo::new(
+                "poseidon_poseidon_full_rounds_state0_squaring",
+                Poseidon,
+                "every 64 rows",
+            ),
+            ConstraintInfo::new(
+                "poseidon_poseidon_full_rounds_state1_squaring",
+                Poseidon,
+                "every 64 rows",
+            ),
+            ConstraintInfo::new(
+                "poseidon_poseidon_full_rounds_state2_squaring",
+                Poseidon,
+                "every 64 rows",
+            ),
+            ConstraintInfo::new(
+                "poseidon_poseidon_partial_rounds_state0_squaring",
+                Poseidon,
+                "every 8 rows",
+            ),
+            ConstraintInfo::new(
+                "poseidon_poseidon_partial_rounds_state1_squaring",
+                Poseidon,
+                "all cycles",
+            ),
+            ConstraintInfo::new(
+                "poseidon_poseidon_add_first_round_key0",
+                Poseidon,
+                "all poseidon",
+            ),
+            ConstraintInfo::new(
+                "poseidon_poseidon_add_first_round_key1",
+                Poseidon,
+                "all poseidon",
+            ),
+            ConstraintInfo::new(
+                "poseidon_poseidon_add_first_round_key2",
+                Poseidon,
+                "all poseidon",
+            ),
+            ConstraintInfo::new(
+                "poseidon_poseidon_full_round0",
+                Poseidon,
+                "poseidon half full round transition",
+            ),
+            ConstraintInfo::new(
+                "poseidon_poseidon_full_round1",
+                Poseidon,
+                "poseidon half full round transition",
+            ),
+            ConstraintInfo::new(
+                "poseidon_poseidon_full_round2",
+                Poseidon,
+                "poseidon half full round transition",
+            ),
+            ConstraintInfo::new(
+                "poseidon_poseidon_last_full_round0",
+                Poseidon,
+                "all poseidon",
+            ),
+            ConstraintInfo::new(
+                "poseidon_poseidon_last_full_round1",
+                Poseidon,
+                "all poseidon",
+            ),
+            ConstraintInfo::new(
+                "poseidon_poseidon_last_full_round2",
+                Poseidon,
+                "all poseidon",
+            ),
+            ConstraintInfo::new(
+                "poseidon_poseidon_copy_partial_rounds0_i0",
+                Poseidon,
+                "all poseidon",
+            ),
+            ConstraintInfo::new(
+                "poseidon_poseidon_copy_partial_rounds0_i1",
+                Poseidon,
+                "all poseidon",
+            ),
+            ConstraintInfo::new(
+                "poseidon_poseidon_copy_partial_rounds0_i2",
+                Poseidon,
+                "all poseidon",
+            ),
+            ConstraintInfo::new(
+                "poseidon_poseidon_margin_full_to_partial0",
+                Poseidon,
+                "all poseidon",
+            ),
+            ConstraintInfo::new(
+                "poseidon_poseidon_margin_full_to_partial1",
+                Poseidon,
+                "all poseidon",
+            ),
+            ConstraintInfo::new(
+                "poseidon_poseidon_margin_full_to_partial2",
+                Poseidon,
+                "all poseidon",
+            ),
+            ConstraintInfo::new("poseidon_poseidon_partial_round0", Poseidon, "every 8 rows"),
+            ConstraintInfo::new("poseidon_poseidon_partial_round1", Poseidon, "all cycles"),
+            ConstraintInfo::new(
+                "poseidon_poseidon_margin_partial_to_full0",
+                Poseidon,
+                "all poseidon",
+            ),
+            ConstraintInfo::new(
+                "poseidon_poseidon_margin_partial_to_full1",
+                Poseidon,
+                "all poseidon",
+            ),
+            ConstraintInfo::new(
+                "poseidon_poseidon_margin_partial_to_full2",
+                Poseidon,
+                "all poseidon",
+            ),
+        ]
+    };
+}
+
 // Trace column 6 - memory
 #[derive(Clone, Copy)]
 pub enum Mem {
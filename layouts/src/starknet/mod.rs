@@ -9,6 +9,8 @@ use builtins::{utils::curve::StarkwareCurve, pedersen};
 pub use ministark_gpu::fields::p3618502788666131213697322783095070105623107215331596699973092056135872020481::ark::Fp;
 pub use trace::ExecutionTrace;
 use ark_ec::models::short_weierstrass::SWCurveConfig;
+use binary::vm::LayoutParams;
+use binary::Layout;
 
 // must be a power-of-two
 pub const CYCLE_HEIGHT: usize = 16;
@@ -47,3 +49,23 @@ pub const POSEIDON_RATIO: usize = 32;
 pub const POSEIDON_M: usize = 3;
 pub const POSEIDON_ROUNDS_FULL: usize = 8;
 pub const POSEIDON_ROUNDS_PARTIAL: usize = 83;
+
+/// Parameters used by the Cairo VM to pad executions to fit this layout
+pub const VM_PARAMS: LayoutParams = LayoutParams {
+    layout: Layout::Starknet,
+    rc_units: CYCLE_HEIGHT / RANGE_CHECK_STEP,
+    memory_units: CYCLE_HEIGHT / MEMORY_STEP,
+    public_memory_fraction: PUBLIC_MEMORY_STEP / MEMORY_STEP,
+    diluted_units: CYCLE_HEIGHT / DILUTED_CHECK_STEP,
+    diluted_spacing: DILUTED_CHECK_SPACING,
+    diluted_n_bits: DILUTED_CHECK_N_BITS,
+    range_check_parts: RANGE_CHECK_BUILTIN_PARTS,
+    output: true,
+    pedersen_ratio: Some(PEDERSEN_BUILTIN_RATIO),
+    range_check_ratio: Some(RANGE_CHECK_BUILTIN_RATIO),
+    ecdsa_ratio: Some(ECDSA_BUILTIN_RATIO),
+    bitwise_ratio: Some(BITWISE_RATIO),
+    ec_op_ratio: Some(EC_OP_BUILTIN_RATIO),
+    keccak_ratio: None,
+    poseidon_ratio: Some(POSEIDON_RATIO),
+};
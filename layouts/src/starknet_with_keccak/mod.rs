@@ -0,0 +1,48 @@
+//! Parameters of the `starknet_with_keccak` layout from StarkWare's open source
+//! verifier <https://github.com/starkware-libs/cairo-lang/blob/361fe32d5930db340ea78fe05aedfe706f6c9405/src/starkware/cairo/lang/instances.py#L211>
+//!
+//! Same builtins as the `starknet` layout plus the keccak builtin. The keccak
+//! component shares the diluted pool with the bitwise builtin. Diluted values
+//! have the same spacing and number of bits as the `starknet` layout but the
+//! pool has 16 units per cycle (vs. 2 in the `starknet` layout) to fit the
+//! keccak component (see [crate::utils::DilutedCheckPool]).
+//!
+//! Only the parameters, the public input encoding and the keccak instance
+//! traces exist for this layout. There's no trace or AIR yet so programs that
+//! use the keccak builtin can't be proven.
+
+// TODO: add the trace and AIR for this layout. The keccak component processes
+// 16 instances at a time (15 trace columns in total). Instance traces for the
+// keccak builtin are generated by `builtins::keccak::InstanceTrace`.
+
+pub use crate::starknet::BITWISE_RATIO;
+pub use crate::starknet::DILUTED_CHECK_N_BITS;
+pub use crate::starknet::DILUTED_CHECK_SPACING;
+pub use crate::starknet::ECDSA_BUILTIN_RATIO;
+pub use crate::starknet::ECDSA_BUILTIN_REPETITIONS;
+pub use crate::starknet::EC_OP_BUILTIN_RATIO;
+pub use crate::starknet::EC_OP_N_BITS;
+pub use crate::starknet::EC_OP_SCALAR_HEIGHT;
+pub use crate::starknet::PEDERSEN_BUILTIN_RATIO;
+pub use crate::starknet::POSEIDON_M;
+pub use crate::starknet::POSEIDON_RATIO;
+pub use crate::starknet::POSEIDON_ROUNDS_FULL;
+pub use crate::starknet::POSEIDON_ROUNDS_PARTIAL;
+pub use crate::starknet::RANGE_CHECK_BUILTIN_PARTS;
+pub use crate::starknet::RANGE_CHECK_BUILTIN_RATIO;
+
+// must be a power-of-two
+pub const CYCLE_HEIGHT: usize = 16;
+pub const PUBLIC_MEMORY_STEP: usize = 8;
+pub const MEMORY_STEP: usize = 2;
+pub const RANGE_CHECK_STEP: usize = 4;
+pub const DILUTED_CHECK_STEP: usize = 1;
+
+/// How many cycles per keccak component
+pub const KECCAK_RATIO: usize = 2048;
+/// Number of keccak instances handled by a single keccak component
+pub const KECCAK_INSTANCES_PER_COMPONENT: usize = 16;
+/// Number of bits in each of the 8 field elements of the keccak state
+pub const KECCAK_STATE_REP_BITS: usize = builtins::keccak::STATE_REP_BITS;
+
+pub const NUM_TRACE_COLUMNS: usize = 15;
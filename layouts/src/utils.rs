@@ -11,6 +11,9 @@ use ruint::uint;
 
 /// Computes the value of the public memory quotient:
 /// Adapted from https://github.com/starkware-libs/starkex-contracts
+///
+/// `public_memory` must contain the entries of all public memory pages. The
+/// denominator is the product of the products of each page.
 pub fn compute_public_memory_quotient<
     const PUBLIC_MEMORY_STEP: usize,
     Fp: GpuFftField + PrimeField,
@@ -30,6 +33,8 @@ pub fn compute_public_memory_quotient<
     // numerator = (z - (0 + alpha * 0))^S,
     let numerator = z.pow([s as u64]);
     // denominator = \prod_i( z - (addr_i + alpha * value_i) ),
+    // entries of continuous pages are included so this is equivalent to
+    // computing the product of each page and multiplying them together
     let denominator = public_memory
         .iter()
         .map(|e| z - (alpha * e.value + Fp::from(e.address)))
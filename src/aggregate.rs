@@ -0,0 +1,208 @@
+//! Aggregates proofs of many programs into a single proof. Each proof is a
+//! recursive proof that gets verified by StarkWare's Cairo verifier program
+//! running as a task of the bootloader in `example/bootloader`. The
+//! bootloader run is then proved:
+//! 1. with the `recursive` layout so the aggregated proof can itself be
+//!    aggregated
+//! 2. with the `starknet` layout for submission to the Ethereum verifier
+//!
+//! Building the witness of the bootloader run is a non-goal. The bootloader
+//! and the Cairo verifier rely on hints that [binary::vm] doesn't support so
+//! this module only covers the steps either side of the run:
+//! - [Aggregation] verifies the proofs and writes the bootloader's program
+//!   input ([Aggregation::bootloader_input])
+//! - `cairo-run --proof_mode` runs the bootloader on that input and writes the
+//!   trace, memory and AIR inputs
+//! - [BootloaderRun] proves the existing run from those files
+
+use crate::cairo_verifier::CairoVerifierInput;
+use crate::claims::recursive;
+use crate::claims::starknet;
+use binary::AirPublicInput;
+use binary::CompiledProgram;
+use binary::Layout;
+use layouts::CairoWitness;
+use ministark::prover::ProvingError;
+use ministark::stark::Stark;
+use ministark::verifier::VerificationError;
+use ministark::Proof;
+use ministark::ProofOptions;
+use ministark_gpu::fields::p3618502788666131213697322783095070105623107215331596699973092056135872020481::ark::Fp;
+use serde::Serialize;
+use std::fmt::Display;
+
+/// Compiled bootloader the proofs are aggregated with
+const BOOTLOADER: &str = include_str!("../example/bootloader/bootloader_compiled.json");
+
+#[derive(Debug)]
+pub enum AggregationError {
+    /// A proof added to the aggregation doesn't verify
+    InvalidProof(VerificationError),
+    /// The bootloader was run with a layout that doesn't match the claim
+    UnexpectedLayout {
+        expected: Layout,
+        actual: Layout,
+    },
+    ProvingFailed(ProvingError),
+}
+
+impl Display for AggregationError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::InvalidProof(e) => write!(f, "proof doesn't verify: {e:?}"),
+            Self::UnexpectedLayout { expected, actual } => {
+                write!(
+                    f,
+                    "expected a bootloader run with layout {expected} but got {actual}"
+                )
+            }
+            Self::ProvingFailed(e) => write!(f, "proof generation failed: {e:?}"),
+        }
+    }
+}
+
+impl std::error::Error for AggregationError {}
+
+/// Task of the bootloader that runs a program on `program_input`
+#[derive(Serialize, Clone, Debug)]
+pub struct BootloaderTask<I> {
+    #[serde(rename = "type")]
+    pub task_type: &'static str,
+    pub program: serde_json::Value,
+    pub program_input: I,
+    pub use_poseidon: bool,
+}
+
+impl<I> BootloaderTask<I> {
+    /// Task that runs a compiled program. The program hash is computed with
+    /// Pedersen.
+    pub fn run_program(program: serde_json::Value, program_input: I) -> Self {
+        Self {
+            task_type: "RunProgramTask",
+            program,
+            program_input,
+            use_poseidon: false,
+        }
+    }
+}
+
+/// Program input of the bootloader
+#[derive(Serialize, Clone, Debug)]
+pub struct BootloaderInput<I> {
+    pub tasks: Vec<BootloaderTask<I>>,
+    /// Outputs of all tasks are written to a single memory page
+    pub single_page: bool,
+}
+
+/// Recursive proofs that are to be aggregated
+pub struct Aggregation {
+    required_security_bits: usize,
+    verifier_inputs: Vec<CairoVerifierInput>,
+}
+
+impl Aggregation {
+    /// Proofs are only added if they verify with at least
+    /// `required_security_bits` bits of security
+    pub fn new(required_security_bits: usize) -> Self {
+        Self {
+            required_security_bits,
+            verifier_inputs: Vec::new(),
+        }
+    }
+
+    /// Verifies the proof and adds it to the aggregation. The Cairo verifier
+    /// can't produce a proof of verification for an invalid proof so it's
+    /// rejected before the bootloader is run.
+    pub fn add_proof(
+        &mut self,
+        claim: &recursive::CairoVerifierClaim,
+        proof: Proof<recursive::CairoVerifierClaim>,
+    ) -> Result<(), AggregationError> {
+        let input = CairoVerifierInput::new(claim, &proof);
+        claim
+            .verify(proof, self.required_security_bits)
+            .map_err(AggregationError::InvalidProof)?;
+        self.verifier_inputs.push(input);
+        Ok(())
+    }
+
+    pub fn len(&self) -> usize {
+        self.verifier_inputs.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.verifier_inputs.is_empty()
+    }
+
+    /// Program input of the bootloader with a task running the Cairo verifier
+    /// on each proof. `cairo_verifier` is the compiled Cairo verifier program.
+    pub fn bootloader_input(
+        &self,
+        cairo_verifier: &serde_json::Value,
+    ) -> BootloaderInput<CairoVerifierInput> {
+        let tasks = self
+            .verifier_inputs
+            .iter()
+            .map(|input| BootloaderTask::run_program(cairo_verifier.clone(), input.clone()))
+            .collect();
+        BootloaderInput {
+            tasks,
+            single_page: true,
+        }
+    }
+}
+
+/// Existing execution of the bootloader on the input from
+/// [Aggregation::bootloader_input] (made with `cairo-run --proof_mode`)
+pub struct BootloaderRun {
+    program: CompiledProgram<Fp>,
+    air_public_input: AirPublicInput<Fp>,
+    witness: CairoWitness<Fp>,
+}
+
+impl BootloaderRun {
+    /// Run of the bootloader in `example/bootloader`. The claims check the
+    /// public memory against the bootloader so a run of any other program
+    /// fails to prove.
+    pub fn new(air_public_input: AirPublicInput<Fp>, witness: CairoWitness<Fp>) -> Self {
+        Self {
+            program: bootloader(),
+            air_public_input,
+            witness,
+        }
+    }
+
+    fn expect_layout(&self, expected: Layout) -> Result<(), AggregationError> {
+        let actual = self.air_public_input.layout;
+        if actual != expected {
+            return Err(AggregationError::UnexpectedLayout { expected, actual });
+        }
+        Ok(())
+    }
+
+    /// Proves the bootloader run so the result can be aggregated again
+    pub async fn prove_recursive(
+        self,
+        options: ProofOptions,
+    ) -> Result<Proof<recursive::CairoVerifierClaim>, AggregationError> {
+        self.expect_layout(Layout::Recursive)?;
+        let claim = recursive::CairoVerifierClaim::new(self.program, self.air_public_input);
+        (claim.prove(options, self.witness).await).map_err(AggregationError::ProvingFailed)
+    }
+
+    /// Proves the bootloader run for StarkWare's Ethereum verifier. This is the
+    /// final step of aggregation.
+    pub async fn prove_for_ethereum(
+        self,
+        options: ProofOptions,
+    ) -> Result<Proof<starknet::EthVerifierClaim>, AggregationError> {
+        self.expect_layout(Layout::Starknet)?;
+        let claim = starknet::EthVerifierClaim::new(self.program, self.air_public_input);
+        (claim.prove(options, self.witness).await).map_err(AggregationError::ProvingFailed)
+    }
+}
+
+/// Compiled bootloader from `example/bootloader`
+pub fn bootloader() -> CompiledProgram<Fp> {
+    serde_json::from_str(BOOTLOADER).expect("bootloader fixture is a compiled program")
+}
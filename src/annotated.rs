@@ -0,0 +1,416 @@
+//! Proofs in the JSON format used by StarkWare's tooling (an "annotated
+//! proof"). The proof is written as the sequence of prover to verifier
+//! messages in the order they're sent over the channel. Each message gets an
+//! annotation with its byte range in `proof_hex`. Annotations follow the
+//! grammar of stone-prover's annotations so they can be read by StarkWare's
+//! parsers.
+//!
+//! Verifier to prover messages (challenges, query positions) aren't part of
+//! the proof. Their annotations are recovered by replaying the public coin.
+//! The random elements of the composition and DEEP composition polynomials
+//! aren't annotated.
+//!
+//! Values the verifier computes from the decommitments (the hashes of the
+//! queried rows, the queried FRI values and their evaluation points) are
+//! listed separately as "extra annotations". StarkWare's tooling uses them to
+//! split the FRI decommitments into statements for the Solidity verifier.
+
+use crate::stone::fold_row;
+use crate::stone::x_inv;
+use crate::transcript::Commitments;
+use crate::transcript::Transcript;
+use ark_ff::PrimeField;
+use crypto::merkle::decommitment_nodes;
+use crypto::merkle::Decommitment;
+use crypto::utils::to_montgomery;
+use ministark::air::AirConfig;
+use ministark::hash::Digest;
+use ministark::merkle::MerkleTree;
+use ministark::stark::Stark;
+use ministark::Proof;
+use ministark_gpu::fields::p3618502788666131213697322783095070105623107215331596699973092056135872020481::ark::Fp;
+use num_bigint::BigUint;
+use serde::Deserialize;
+use serde::Serialize;
+use std::fmt::Write;
+use std::iter::zip;
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq)]
+pub struct FriParameters {
+    /// Log2 of the folding factor of each FRI layer. The first layer is the
+    /// DEEP composition polynomial and isn't folded.
+    pub fri_step_list: Vec<u32>,
+    pub last_layer_degree_bound: usize,
+    pub n_queries: u8,
+    pub proof_of_work_bits: u8,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq)]
+pub struct StarkParameters {
+    pub fri: FriParameters,
+    /// Log2 of the LDE blowup factor
+    pub log_n_cosets: u32,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq)]
+pub struct ProofParameters {
+    pub stark: StarkParameters,
+    #[serde(default)]
+    pub use_extension_field: bool,
+}
+
+impl ProofParameters {
+    pub fn new<Claim: Stark>(proof: &Proof<Claim>) -> Self {
+        let options = proof.options;
+        let fri_folding_step = options.fri_folding_factor.ilog2();
+        let fri_step_list = std::iter::once(0)
+            .chain(std::iter::repeat(fri_folding_step).take(proof.fri_proof.layers.len()))
+            .collect();
+        Self {
+            stark: StarkParameters {
+                fri: FriParameters {
+                    fri_step_list,
+                    last_layer_degree_bound: proof.fri_proof.remainder_coeffs.len(),
+                    n_queries: options.num_queries,
+                    proof_of_work_bits: options.grinding_factor,
+                },
+                log_n_cosets: options.lde_blowup_factor.ilog2(),
+            },
+            use_extension_field: false,
+        }
+    }
+}
+
+#[derive(Serialize, Clone, Debug, PartialEq, Eq)]
+pub struct AnnotatedProof {
+    pub proof_parameters: ProofParameters,
+    pub annotations: Vec<String>,
+    /// Annotations of the values computed by the verifier
+    pub extra_annotations: Vec<String>,
+    /// Prover to verifier messages as a hex string
+    pub proof_hex: String,
+}
+
+impl AnnotatedProof {
+    pub fn new<Claim: Stark<Fp = Fp, Fq = Fp>>(claim: &Claim, proof: &Proof<Claim>) -> Self
+    where
+        <Claim::MerkleTree as MerkleTree>::Proof: Decommitment,
+    {
+        let commitments = Commitments::from_proof(proof);
+        let transcript = Transcript::replay(claim, &commitments);
+        let mut channel = ProverChannel::default();
+        channel.send_commitments(proof, &transcript);
+        let positions = &transcript.query_positions;
+
+        // decommitments of the traces (the first FRI layer is a virtual oracle)
+        let queries = &proof.trace_queries;
+        let height = commitments.lde_domain_size().ilog2();
+        let mut traces = vec![(
+            Claim::AirConfig::NUM_BASE_COLUMNS,
+            &queries.base_trace_values,
+            &queries.base_trace_proof,
+        )];
+        if let Some(extension_trace_proof) = &queries.extension_trace_proof {
+            traces.push((
+                Claim::AirConfig::NUM_EXTENSION_COLUMNS,
+                &queries.extension_trace_values,
+                extension_trace_proof,
+            ));
+        }
+        traces.push((
+            proof.composition_trace_ood_evals.len(),
+            &queries.composition_trace_values,
+            &queries.composition_trace_proof,
+        ));
+        for (i, (num_columns, values, merkle_proof)) in traces.into_iter().enumerate() {
+            let scope = format!("FRI/Decommitment/Layer 0/Virtual Oracle/Trace {i}");
+            channel.send_rows(&scope, positions, values, num_columns);
+            channel.send_authentication_path(&scope, merkle_proof, positions, height, num_columns);
+            channel.annotate_queried_nodes(&scope, merkle_proof, positions, height, num_columns);
+        }
+
+        // FRI layer decommitments only include the values that aren't queried
+        let folding_factor = usize::from(proof.options.fri_folding_factor);
+        let mut layer_positions = positions.clone();
+        let mut queried_values = Vec::new();
+        let mut domain_size = commitments.lde_domain_size();
+        let fri_layers = zip(&proof.fri_proof.layers, &transcript.fri_alphas);
+        for (i, (layer, &alpha)) in fri_layers.enumerate() {
+            let scope = format!("FRI/Decommitment/Layer {}", i + 1);
+            let mut rows = layer_positions
+                .iter()
+                .map(|p| p / folding_factor)
+                .collect::<Vec<usize>>();
+            rows.dedup();
+            let is_queried = |position| layer_positions.binary_search(&position).is_ok();
+            let flattened_rows = layer.flattenend_rows.chunks(folding_factor);
+            for (row, values) in rows.iter().zip(flattened_rows.clone()) {
+                for (column, &value) in values.iter().enumerate() {
+                    let position = row * folding_factor + column;
+                    let description = format!("Row {row}, Column {column}");
+                    if is_queried(position) {
+                        channel.annotate_fri_query(
+                            &scope,
+                            &description,
+                            position,
+                            domain_size,
+                            value,
+                        );
+                    } else {
+                        channel.send_field_element(&scope, &description, value);
+                    }
+                }
+            }
+            queried_values = zip(&rows, flattened_rows)
+                .map(|(&row, values)| fold_row(values, row, domain_size, alpha))
+                .collect();
+            domain_size /= folding_factor;
+            let height = domain_size.ilog2();
+            let merkle_proof = &layer.merkle_proof;
+            channel.send_authentication_path(&scope, merkle_proof, &rows, height, folding_factor);
+            channel.annotate_queried_nodes(&scope, merkle_proof, &rows, height, folding_factor);
+            layer_positions = rows;
+        }
+
+        // the verifier folds the last layer's queried values out of the rows
+        // of the layer before
+        let scope = "FRI/Decommitment/Last Layer";
+        for (&position, &value) in zip(&layer_positions, &queried_values) {
+            let description = format!("Row {position}, Column 0");
+            channel.annotate_fri_query(scope, &description, position, domain_size, value);
+        }
+
+        Self {
+            proof_parameters: ProofParameters::new(proof),
+            annotations: channel.annotations,
+            extra_annotations: channel.extra_annotations,
+            proof_hex: to_hex(&channel.bytes),
+        }
+    }
+}
+
+/// Collects the prover to verifier messages and the annotations of the
+/// messages sent in both directions
+#[derive(Default)]
+pub(crate) struct ProverChannel {
+    pub bytes: Vec<u8>,
+    pub annotations: Vec<String>,
+    pub extra_annotations: Vec<String>,
+}
+
+impl ProverChannel {
+    /// Sends the commitments, out of domain evaluations, FRI layer commitments
+    /// and the proof of work nonce i.e. everything before the decommitments.
+    /// The verifier's messages in between are annotated from the transcript.
+    pub fn send_commitments<Claim: Stark<Fp = Fp, Fq = Fp>>(
+        &mut self,
+        proof: &Proof<Claim>,
+        transcript: &Transcript<Claim>,
+    ) {
+        // commitments
+        self.send_hash(
+            "Original/Commit on Trace",
+            "Commitment",
+            proof.base_trace_commitment.as_bytes(),
+        );
+        for (i, &element) in transcript.challenges.iter().enumerate() {
+            let description = format!("Interaction element #{i}");
+            self.receive_field_element("Interaction", &description, element);
+        }
+        if let Some(commitment) = &proof.extension_trace_commitment {
+            self.send_hash(
+                "Interaction/Commit on Trace",
+                "Commitment",
+                commitment.as_bytes(),
+            );
+        }
+        self.send_hash(
+            "Out Of Domain Sampling/Commit on Trace",
+            "Commitment",
+            proof.composition_trace_commitment.as_bytes(),
+        );
+
+        // out of domain evaluations
+        let scope = "Out Of Domain Sampling/OODS values";
+        self.receive_field_element(scope, "Evaluation point", transcript.ood_point);
+        let ood_evals = [
+            proof.execution_trace_ood_evals.as_slice(),
+            proof.composition_trace_ood_evals.as_slice(),
+        ];
+        for (i, &value) in ood_evals.into_iter().flatten().enumerate() {
+            self.send_field_element(scope, &i.to_string(), value);
+        }
+
+        // FRI commitments. The evaluation point of a layer is drawn after the
+        // previous layer is committed.
+        let fri_layers = &proof.fri_proof.layers;
+        for (i, (layer, &alpha)) in fri_layers.iter().zip(&transcript.fri_alphas).enumerate() {
+            let scope = format!("FRI/Commitment/Layer {}", i + 1);
+            self.send_hash(&scope, "Commitment", layer.commitment.as_bytes());
+            let scope = format!("FRI/Commitment/Layer {}", i + 2);
+            self.receive_field_element(&scope, "Evaluation point", alpha);
+        }
+        self.send_field_elements(
+            "FRI/Commitment/Last Layer",
+            "Coefficients",
+            &proof.fri_proof.remainder_coeffs,
+        );
+        self.send_data("FRI/Proof of Work", "POW", &proof.pow_nonce.to_be_bytes());
+        for (i, position) in transcript.query_positions.iter().enumerate() {
+            self.receive("FRI/QueryIndices", &format!("{i}: Number({position})"));
+        }
+    }
+
+    /// `annotation` is the description of the message followed by its value
+    fn send(&mut self, scope: &str, annotation: &str, bytes: &[u8]) {
+        let start = self.bytes.len();
+        self.bytes.extend_from_slice(bytes);
+        let end = self.bytes.len();
+        self.annotations.push(format!(
+            "P->V[{start}:{end}]: /cpu air/STARK/{scope}: {annotation}"
+        ));
+    }
+
+    /// Annotates a message sent by the verifier. Nothing is sent.
+    fn receive(&mut self, scope: &str, annotation: &str) {
+        let annotation = format!("V->P: /cpu air/STARK/{scope}: {annotation}");
+        self.annotations.push(annotation);
+    }
+
+    /// Annotates a value computed by the verifier. Nothing is sent.
+    fn annotate(&mut self, scope: &str, annotation: &str) {
+        let annotation = format!("/cpu air/STARK/{scope}: {annotation}");
+        self.extra_annotations.push(annotation);
+    }
+
+    fn receive_field_element(&mut self, scope: &str, description: &str, value: Fp) {
+        let annotation = format!("{description}: Field Element({})", field_element_hex(value));
+        self.receive(scope, &annotation)
+    }
+
+    pub fn send_hash(&mut self, scope: &str, description: &str, digest: [u8; 32]) {
+        let annotation = format!("{description}: Hash({})", to_hex(&digest));
+        self.send(scope, &annotation, &digest)
+    }
+
+    /// Data is annotated as a number so leading zeros are dropped
+    pub fn send_data(&mut self, scope: &str, description: &str, bytes: &[u8]) {
+        let data = BigUint::from_bytes_be(bytes);
+        self.send(scope, &format!("{description}: Data({data:#x})"), bytes)
+    }
+
+    /// Field elements are sent in Montgomery form but annotated with their
+    /// value
+    pub fn send_field_element(&mut self, scope: &str, description: &str, value: Fp) {
+        let annotation = format!("{description}: Field Element({})", field_element_hex(value));
+        let bytes = to_montgomery(value).to_be_bytes::<32>();
+        self.send(scope, &annotation, &bytes)
+    }
+
+    /// Sends field elements as a single message
+    pub fn send_field_elements(&mut self, scope: &str, description: &str, values: &[Fp]) {
+        let bytes = values
+            .iter()
+            .flat_map(|&value| to_montgomery(value).to_be_bytes::<32>())
+            .collect::<Vec<u8>>();
+        let values = values
+            .iter()
+            .map(|&value| field_element_hex(value))
+            .collect::<Vec<String>>();
+        let annotation = format!("{description}: Field Elements({})", values.join(", "));
+        self.send(scope, &annotation, &bytes)
+    }
+
+    /// Sends the values of the rows at the given sorted positions
+    pub fn send_rows(
+        &mut self,
+        scope: &str,
+        positions: &[usize],
+        values: &[Fp],
+        num_columns: usize,
+    ) {
+        for (row, values) in positions.iter().zip(values.chunks(num_columns)) {
+            for (column, &value) in values.iter().enumerate() {
+                let description = format!("Row {row}, Column {column}");
+                self.send_field_element(scope, &description, value);
+            }
+        }
+    }
+
+    /// Sends the merkle authentication path of the rows at the given sorted
+    /// positions. Trees of a single column have pairs of values as leaves
+    /// (a "package") so their sibling leaves are values rather than hashes.
+    pub fn send_authentication_path(
+        &mut self,
+        scope: &str,
+        proof: &impl Decommitment,
+        positions: &[usize],
+        height: u32,
+        num_columns: usize,
+    ) {
+        let nodes = decommitment_nodes(positions, height);
+        for ((depth, position), word) in nodes.into_iter().zip(proof.authentication_words()) {
+            if num_columns == 1 && depth == height {
+                let description = format!("To complete packages, element #{position}");
+                self.send_data(scope, &description, &word);
+            } else {
+                let description = format!("For node {}", (1 << depth) + position);
+                self.send_hash(scope, &description, word);
+            }
+        }
+    }
+
+    /// Annotates the lowest nodes committing to the rows at the given sorted
+    /// positions. Trees of a single column have a node for each package.
+    pub fn annotate_queried_nodes(
+        &mut self,
+        scope: &str,
+        proof: &impl Decommitment,
+        positions: &[usize],
+        height: u32,
+        num_columns: usize,
+    ) {
+        let mut nodes = positions
+            .iter()
+            .map(|position| (1 << height) + position)
+            .collect::<Vec<usize>>();
+        if num_columns == 1 {
+            nodes = nodes.iter().map(|node| node / 2).collect();
+            nodes.dedup();
+        }
+        for (node, word) in zip(nodes, proof.queried_node_words(positions)) {
+            self.annotate(scope, &format!("For node {node}: Hash({})", to_hex(&word)));
+        }
+    }
+
+    /// Annotates a queried FRI value and the inverse of its evaluation point
+    pub fn annotate_fri_query(
+        &mut self,
+        scope: &str,
+        description: &str,
+        position: usize,
+        domain_size: usize,
+        value: Fp,
+    ) {
+        let value = field_element_hex(value);
+        self.annotate(scope, &format!("{description}: Field Element({value})"));
+        let x_inv = field_element_hex(x_inv(position, domain_size));
+        let description = format!("xInv for index {position}");
+        self.annotate(scope, &format!("{description}: Field Element({x_inv})"));
+    }
+}
+
+/// Field elements are annotated with their value (not in Montgomery form)
+fn field_element_hex(value: Fp) -> String {
+    let value: BigUint = value.into_bigint().into();
+    format!("{value:#x}")
+}
+
+pub(crate) fn to_hex(bytes: &[u8]) -> String {
+    let mut res = String::with_capacity(2 + bytes.len() * 2);
+    res.push_str("0x");
+    for byte in bytes {
+        write!(res, "{byte:02x}").unwrap();
+    }
+    res
+}
@@ -0,0 +1,168 @@
+//! Facts of several programs proven at once as tasks of StarkWare's simple
+//! bootloader. The bootloader writes the output of each task to its own output
+//! segment as `[output_size, program_hash, ...output]` (the size includes the
+//! two header cells) after the number of tasks. Each task is then registered
+//! on-chain as a [Fact].
+//!
+//! Running the bootloader is out of scope. Like [crate::aggregate] it has to
+//! be run with `cairo-run --proof_mode` on the input from
+//! [Batch::bootloader_input] and the existing run is then proven. The output
+//! of the run is untrusted until the program hash of each task is checked with
+//! [BatchFacts::expect_program_hashes].
+
+use crate::aggregate::BootloaderInput;
+use crate::aggregate::BootloaderTask;
+use crate::fact::field_element;
+use crate::fact::program_output;
+use crate::fact::Fact;
+use binary::AirPublicInput;
+use ministark_gpu::fields::p3618502788666131213697322783095070105623107215331596699973092056135872020481::ark::Fp;
+use ruint::aliases::U256;
+use serde::Serialize;
+use std::fmt::Display;
+
+#[derive(Debug)]
+pub enum BatchError {
+    /// The output segment isn't laid out like the simple bootloader's output
+    MalformedOutput(String),
+    /// The bootloader ran a different number of tasks than expected
+    UnexpectedTaskCount { expected: usize, actual: usize },
+    /// A task's program hash doesn't match the program it should have run
+    ProgramHashMismatch {
+        task: usize,
+        expected: U256,
+        actual: U256,
+    },
+}
+
+impl Display for BatchError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::MalformedOutput(reason) => write!(f, "malformed bootloader output: {reason}"),
+            Self::UnexpectedTaskCount { expected, actual } => {
+                write!(
+                    f,
+                    "expected {expected} tasks but the bootloader ran {actual}"
+                )
+            }
+            Self::ProgramHashMismatch {
+                task,
+                expected,
+                actual,
+            } => write!(
+                f,
+                "task {task} has program hash {actual:#x} but its program hashes to {expected:#x}"
+            ),
+        }
+    }
+}
+
+impl std::error::Error for BatchError {}
+
+/// Programs (and their inputs) to run under the simple bootloader
+#[derive(Default)]
+pub struct Batch {
+    tasks: Vec<BootloaderTask<serde_json::Value>>,
+}
+
+impl Batch {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Adds a task that runs the compiled `program` on `program_input`
+    pub fn add_task(&mut self, program: serde_json::Value, program_input: serde_json::Value) {
+        self.tasks
+            .push(BootloaderTask::run_program(program, program_input));
+    }
+
+    pub fn len(&self) -> usize {
+        self.tasks.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.tasks.is_empty()
+    }
+
+    pub fn bootloader_input(&self) -> BootloaderInput<serde_json::Value> {
+        BootloaderInput {
+            tasks: self.tasks.clone(),
+            single_page: true,
+        }
+    }
+}
+
+/// Facts of all tasks of a bootloader run
+#[derive(Serialize, Clone, Debug, PartialEq, Eq)]
+pub struct BatchFacts {
+    pub tasks: Vec<Fact>,
+    /// Task metadata expected by the Ethereum verifier's
+    /// `verifyProofAndRegister` (see [crate::evm::SplitProof])
+    pub task_metadata: Vec<U256>,
+}
+
+impl BatchFacts {
+    /// Reads the facts from the output segment of a simple bootloader run
+    pub fn from_public_input(air_public_input: &AirPublicInput<Fp>) -> Result<Self, BatchError> {
+        let output = program_output(air_public_input)
+            .map_err(|e| BatchError::MalformedOutput(e.to_string()))?
+            .into_iter()
+            .map(field_element)
+            .collect::<Vec<U256>>();
+
+        let (n_tasks, mut remaining) = output
+            .split_first()
+            .ok_or_else(|| BatchError::MalformedOutput("the output is empty".into()))?;
+        let n_tasks = usize::try_from(*n_tasks)
+            .map_err(|_| BatchError::MalformedOutput("invalid number of tasks".into()))?;
+        let mut tasks = Vec::new();
+        let mut task_metadata = vec![U256::from(n_tasks)];
+        for i in 0..n_tasks {
+            let malformed = || BatchError::MalformedOutput(format!("task {i} is truncated"));
+            let &[output_size, program_hash, ..] = remaining else {
+                return Err(malformed());
+            };
+            let size = usize::try_from(output_size)
+                .ok()
+                .filter(|size| (2..=remaining.len()).contains(size))
+                .ok_or_else(malformed)?;
+            tasks.push(Fact::new(program_hash, remaining[2..size].to_vec()));
+            // the output is a single page so the fact topology is trivial
+            let tree_structure = [U256::from(1), U256::ZERO];
+            let n_tree_pairs = U256::from(tree_structure.len() / 2);
+            task_metadata.extend([output_size, program_hash, n_tree_pairs]);
+            task_metadata.extend(tree_structure);
+            remaining = &remaining[size..];
+        }
+        if !remaining.is_empty() {
+            return Err(BatchError::MalformedOutput(format!(
+                "{} values left over after the last task",
+                remaining.len()
+            )));
+        }
+
+        Ok(Self {
+            tasks,
+            task_metadata,
+        })
+    }
+
+    /// Checks the bootloader ran one task for each of the given program
+    /// hashes (see [crate::fact::program_hash]) in order
+    pub fn expect_program_hashes(&self, program_hashes: &[U256]) -> Result<(), BatchError> {
+        let (expected, actual) = (program_hashes.len(), self.tasks.len());
+        if actual != expected {
+            return Err(BatchError::UnexpectedTaskCount { expected, actual });
+        }
+        for (task, (fact, &expected)) in self.tasks.iter().zip(program_hashes).enumerate() {
+            if fact.program_hash != expected {
+                return Err(BatchError::ProgramHashMismatch {
+                    task,
+                    expected,
+                    actual: fact.program_hash,
+                });
+            }
+        }
+        Ok(())
+    }
+}
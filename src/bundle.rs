@@ -0,0 +1,301 @@
+//! Self-contained proof bundles. A bundle packs a proof with everything needed
+//! to verify it: the compiled program, the AIR public input and the proof
+//! options. The header records how the proof was generated so verifiers can
+//! pick the matching claim and reject bundles whose components don't match.
+//!
+//! Bundles are laid out as:
+//! - magic `b"SANDSTRM"`
+//! - version (`u16`)
+//! - layout, prime (string), verifier (`u8`) and commitment hash (`u8`)
+//! - program (JSON), AIR public input (JSON), proof options and proof
+//!
+//! Everything after the magic is serialized with `ark-serialize` (strings and
+//! byte vectors are prefixed with their length as a `u64`).
+
+use crate::claims::ClaimError;
+use crate::claims::ClaimSelection;
+use ark_serialize::CanonicalDeserialize;
+use ark_serialize::CanonicalSerialize;
+use binary::AirPublicInput;
+use binary::CompiledProgram;
+use binary::Layout;
+use ministark::stark::Stark;
+use ministark::Proof;
+use ministark::ProofOptions;
+use ministark_gpu::fields::p3618502788666131213697322783095070105623107215331596699973092056135872020481::ark::Fp;
+use std::collections::BTreeSet;
+use std::fmt::Display;
+use std::io::Read;
+use std::io::Write;
+
+pub const BUNDLE_MAGIC: [u8; 8] = *b"SANDSTRM";
+pub const BUNDLE_VERSION: u16 = 1;
+
+#[derive(Debug)]
+pub enum BundleError {
+    Io(std::io::Error),
+    InvalidMagic,
+    UnsupportedVersion(u16),
+    /// A component couldn't be (de)serialized
+    Malformed(String),
+    LayoutMismatch {
+        header: Layout,
+        public_input: Layout,
+    },
+    PrimeMismatch {
+        header: String,
+        program: String,
+    },
+    /// The program doesn't match the program in the public memory
+    ProgramMismatch {
+        address: u32,
+    },
+    /// The public memory doesn't hold exactly the program's cells
+    ProgramSizeMismatch {
+        segment: usize,
+        program: usize,
+    },
+    /// The proof was generated with different options than the bundled ones
+    OptionsMismatch,
+}
+
+impl Display for BundleError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Io(err) => write!(f, "{err}"),
+            Self::InvalidMagic => write!(f, "not a proof bundle"),
+            Self::UnsupportedVersion(version) => {
+                write!(f, "bundle version {version} is not supported")
+            }
+            Self::Malformed(reason) => write!(f, "malformed bundle: {reason}"),
+            Self::LayoutMismatch {
+                header,
+                public_input,
+            } => write!(
+                f,
+                "bundle is for layout {header} but the public input is for layout {public_input}"
+            ),
+            Self::PrimeMismatch { header, program } => write!(
+                f,
+                "bundle is for prime {header} but the program is for prime {program}"
+            ),
+            Self::ProgramMismatch { address } => write!(
+                f,
+                "program doesn't match the public memory at address {address}"
+            ),
+            Self::ProgramSizeMismatch { segment, program } => write!(
+                f,
+                "program segment has {segment} cells but the program has {program}"
+            ),
+            Self::OptionsMismatch => write!(f, "proof options don't match the bundled options"),
+        }
+    }
+}
+
+impl std::error::Error for BundleError {}
+
+impl From<std::io::Error> for BundleError {
+    fn from(err: std::io::Error) -> Self {
+        Self::Io(err)
+    }
+}
+
+impl From<ark_serialize::SerializationError> for BundleError {
+    fn from(err: ark_serialize::SerializationError) -> Self {
+        Self::Malformed(err.to_string())
+    }
+}
+
+impl From<serde_json::Error> for BundleError {
+    fn from(err: serde_json::Error) -> Self {
+        Self::Malformed(err.to_string())
+    }
+}
+
+impl From<ClaimError> for BundleError {
+    fn from(err: ClaimError) -> Self {
+        Self::Malformed(err.to_string())
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct BundleHeader {
+    pub version: u16,
+    pub layout: Layout,
+    pub prime: String,
+    /// Verifier and hash function the proof was generated for
+    pub claim: ClaimSelection,
+}
+
+pub struct ProofBundle {
+    pub header: BundleHeader,
+    /// Compiled program as JSON
+    pub program: serde_json::Value,
+    pub air_public_input: AirPublicInput<Fp>,
+    /// Copy of the proof's options that can be read without knowing the claim
+    /// the proof deserializes for
+    pub options: ProofOptions,
+    /// Serialized proof. Deserialize with [ProofBundle::proof].
+    pub proof: Vec<u8>,
+}
+
+impl ProofBundle {
+    pub fn new<Claim: Stark<Fp = Fp>>(
+        claim: ClaimSelection,
+        program: serde_json::Value,
+        air_public_input: AirPublicInput<Fp>,
+        proof: &Proof<Claim>,
+    ) -> Result<Self, BundleError> {
+        let prime = program_prime(&program)?;
+        let mut proof_bytes = Vec::new();
+        proof.serialize_compressed(&mut proof_bytes)?;
+        let bundle = Self {
+            header: BundleHeader {
+                version: BUNDLE_VERSION,
+                layout: air_public_input.layout,
+                prime,
+                claim,
+            },
+            program,
+            air_public_input,
+            options: proof.options,
+            proof: proof_bytes,
+        };
+        bundle.validate()?;
+        Ok(bundle)
+    }
+
+    pub fn write(&self, mut writer: impl Write) -> Result<(), BundleError> {
+        writer.write_all(&BUNDLE_MAGIC)?;
+        let header = &self.header;
+        header.version.serialize_compressed(&mut writer)?;
+        header.layout.serialize_compressed(&mut writer)?;
+        header.prime.serialize_compressed(&mut writer)?;
+        (header.claim.verifier() as u8).serialize_compressed(&mut writer)?;
+        (header.claim.hash() as u8).serialize_compressed(&mut writer)?;
+        serde_json::to_vec(&self.program)?.serialize_compressed(&mut writer)?;
+        serde_json::to_vec(&self.air_public_input)?.serialize_compressed(&mut writer)?;
+        self.options.serialize_compressed(&mut writer)?;
+        self.proof.serialize_compressed(&mut writer)?;
+        Ok(())
+    }
+
+    /// Reads a bundle and checks its components match each other
+    pub fn read(mut reader: impl Read) -> Result<Self, BundleError> {
+        let mut magic = [0; BUNDLE_MAGIC.len()];
+        reader.read_exact(&mut magic)?;
+        if magic != BUNDLE_MAGIC {
+            return Err(BundleError::InvalidMagic);
+        }
+        let version = u16::deserialize_compressed(&mut reader)?;
+        if version != BUNDLE_VERSION {
+            return Err(BundleError::UnsupportedVersion(version));
+        }
+        let layout = Layout::deserialize_compressed(&mut reader)?;
+        let prime = String::deserialize_compressed(&mut reader)?;
+        let verifier = u8::deserialize_compressed(&mut reader)?.try_into()?;
+        let hash = u8::deserialize_compressed(&mut reader)?.try_into()?;
+        let claim = ClaimSelection::new(verifier, hash)?;
+        let header = BundleHeader {
+            version,
+            layout,
+            prime,
+            claim,
+        };
+        let program = serde_json::from_slice(&Vec::<u8>::deserialize_compressed(&mut reader)?)?;
+        let air_public_input =
+            serde_json::from_slice(&Vec::<u8>::deserialize_compressed(&mut reader)?)?;
+        let options = ProofOptions::deserialize_compressed(&mut reader)?;
+        let proof = Vec::<u8>::deserialize_compressed(&mut reader)?;
+        let bundle = Self {
+            header,
+            program,
+            air_public_input,
+            options,
+            proof,
+        };
+        bundle.validate()?;
+        Ok(bundle)
+    }
+
+    pub fn program(&self) -> Result<CompiledProgram<Fp>, BundleError> {
+        Ok(serde_json::from_value(self.program.clone())?)
+    }
+
+    /// Deserializes the proof for a claim matching the header's selection. The
+    /// proof has its own options which are the ones verified so the bundled
+    /// copy is checked against them. Otherwise a bundle could list options
+    /// (e.g. the number of queries) that its proof wasn't generated with.
+    pub fn proof<Claim: Stark<Fp = Fp>>(&self) -> Result<Proof<Claim>, BundleError> {
+        let proof = Proof::<Claim>::deserialize_compressed(&*self.proof)?;
+        let mut proof_options = Vec::new();
+        let mut bundled_options = Vec::new();
+        proof.options.serialize_compressed(&mut proof_options)?;
+        self.options.serialize_compressed(&mut bundled_options)?;
+        if proof_options != bundled_options {
+            return Err(BundleError::OptionsMismatch);
+        }
+        Ok(proof)
+    }
+
+    /// Checks the header, program and public input are consistent
+    fn validate(&self) -> Result<(), BundleError> {
+        let header = &self.header;
+        let public_input = &self.air_public_input;
+        if header.layout != public_input.layout {
+            return Err(BundleError::LayoutMismatch {
+                header: header.layout,
+                public_input: public_input.layout,
+            });
+        }
+        let prime = program_prime(&self.program)?;
+        if !header.prime.eq_ignore_ascii_case(&prime) {
+            return Err(BundleError::PrimeMismatch {
+                header: header.prime.clone(),
+                program: prime,
+            });
+        }
+
+        // the program is loaded at the start of the program segment and every
+        // program cell must be in the public memory
+        let program = self.program()?;
+        let segments = &public_input.memory_segments;
+        let begin_addr = segments.program.begin_addr;
+        let segment_size = segments.execution.begin_addr.saturating_sub(begin_addr) as usize;
+        if segment_size < program.data.len() {
+            return Err(BundleError::ProgramSizeMismatch {
+                segment: segment_size,
+                program: program.data.len(),
+            });
+        }
+        // public memory can repeat an address so cells are counted once
+        let mut program_cells = BTreeSet::new();
+        for entry in &public_input.public_memory {
+            let Some(offset) = entry.address.checked_sub(begin_addr) else {
+                continue;
+            };
+            if let Some(&value) = program.data.get(offset as usize) {
+                if value != entry.value {
+                    return Err(BundleError::ProgramMismatch {
+                        address: entry.address,
+                    });
+                }
+                program_cells.insert(entry.address);
+            }
+        }
+        if program_cells.len() != program.data.len() {
+            return Err(BundleError::ProgramSizeMismatch {
+                segment: program_cells.len(),
+                program: program.data.len(),
+            });
+        }
+        Ok(())
+    }
+}
+
+fn program_prime(program: &serde_json::Value) -> Result<String, BundleError> {
+    program["prime"]
+        .as_str()
+        .map(str::to_string)
+        .ok_or_else(|| BundleError::Malformed("program has no prime".into()))
+}
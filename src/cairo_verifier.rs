@@ -0,0 +1,58 @@
+//! Program input for StarkWare's Cairo verifier (`cairo_verifier` in
+//! cairo-lang). Verifying a proof with the Cairo verifier outputs a proof of
+//! verification which is how proofs are recursively aggregated.
+
+use crate::annotated::AnnotatedProof;
+use crate::annotated::ProofParameters;
+use binary::AirPublicInput;
+use crypto::merkle::Decommitment;
+use crypto::public_coin::cairo::CairoVerifierPublicCoin;
+use ministark::air::AirConfig;
+use ministark::merkle::MerkleTree;
+use ministark::stark::Stark;
+use ministark::Proof;
+use ministark_gpu::fields::p3618502788666131213697322783095070105623107215331596699973092056135872020481::ark::Fp;
+use serde::Serialize;
+
+/// Proof in the format parsed by the Cairo verifier's hints
+#[derive(Serialize, Clone, Debug)]
+pub struct CairoVerifierProof {
+    pub proof_parameters: ProofParameters,
+    pub public_input: AirPublicInput<Fp>,
+    /// Annotations of the messages sent in both directions
+    pub annotations: Vec<String>,
+    /// Annotations of the values computed by the verifier
+    pub extra_annotations: Vec<String>,
+    pub proof_hex: String,
+}
+
+#[derive(Serialize, Clone, Debug)]
+pub struct CairoVerifierInput {
+    pub proof: CairoVerifierProof,
+}
+
+impl CairoVerifierInput {
+    pub fn new<Claim>(claim: &Claim, proof: &Proof<Claim>) -> Self
+    where
+        Claim: Stark<Fp = Fp, Fq = Fp, PublicCoin = CairoVerifierPublicCoin>,
+        Claim::AirConfig: AirConfig<PublicInputs = AirPublicInput<Fp>>,
+        <Claim::MerkleTree as MerkleTree>::Proof: Decommitment,
+    {
+        let AnnotatedProof {
+            proof_parameters,
+            annotations,
+            extra_annotations,
+            proof_hex,
+        } = AnnotatedProof::new(claim, proof);
+
+        Self {
+            proof: CairoVerifierProof {
+                proof_parameters,
+                public_input: claim.get_public_inputs(),
+                annotations,
+                extra_annotations,
+                proof_hex,
+            },
+        }
+    }
+}
@@ -1,11 +1,29 @@
-use crypto::public_coin::solidity::SolidityVerifierPublicCoin;
 use crate::CairoClaim;
-use crypto::merkle::LeafVariantMerkleTree;
-use crypto::merkle::FriendlyMerkleTree; 
-use crypto::hash::pedersen::PedersenHashFn;
-use ministark_gpu::fields::p3618502788666131213697322783095070105623107215331596699973092056135872020481::ark::Fp;
+use crate::VerifyOutput;
+use binary::AirPublicInput;
+use binary::CompiledProgram;
+use binary::Layout;
+use crypto::hash::blake2s::Blake2sHashFn;
 use crypto::hash::keccak::Keccak256HashFn;
+use crypto::hash::pedersen::PedersenHashFn;
+use crypto::hash::poseidon::PoseidonHashFn;
+use crypto::merkle::Decommitment;
+use crypto::merkle::FriendlyMerkleTree;
+use crypto::merkle::LeafVariantMerkleTree;
+use crypto::merkle::ReadDecommitment;
 use crypto::public_coin::cairo::CairoVerifierPublicCoin;
+use crypto::public_coin::solidity::SolidityVerifierPublicCoin;
+use layouts::constraints::ConstraintRegistry;
+use layouts::CairoAirConfig;
+use layouts::CairoWitness;
+use ministark::air::AirConfig;
+use ministark::merkle::MatrixMerkleTreeImpl;
+use ministark::merkle::MerkleTree;
+use ministark::random::PublicCoinImpl;
+use ministark::stark::Stark;
+use ministark_gpu::fields::p3618502788666131213697322783095070105623107215331596699973092056135872020481::ark::Fp;
+use std::fmt::Display;
+use std::str::FromStr;
 
 pub const NUM_FRIENDLY_COMMITMENT_LAYERS: u32 = 22;
 
@@ -19,6 +37,12 @@ pub mod starknet {
         CairoClaim<Fp, AirConfig, ExecutionTrace, LeafVariantMerkleTree<MaskedKeccak256HashFn<20>>, SolidityVerifierPublicCoin>;
     pub type CairoVerifierClaim =
         CairoClaim<Fp, AirConfig, ExecutionTrace, FriendlyMerkleTree<NUM_FRIENDLY_COMMITMENT_LAYERS, PedersenHashFn>, CairoVerifierPublicCoin>;
+    pub type PoseidonCairoVerifierClaim =
+        CairoClaim<Fp, AirConfig, ExecutionTrace, FriendlyMerkleTree<NUM_FRIENDLY_COMMITMENT_LAYERS, PoseidonHashFn>, CairoVerifierPublicCoin>;
+    pub type KeccakNativeClaim =
+        CairoClaim<Fp, AirConfig, ExecutionTrace, MatrixMerkleTreeImpl<Keccak256HashFn>, PublicCoinImpl<Fp, Keccak256HashFn>>;
+    pub type Blake2sNativeClaim =
+        CairoClaim<Fp, AirConfig, ExecutionTrace, MatrixMerkleTreeImpl<Blake2sHashFn>, PublicCoinImpl<Fp, Blake2sHashFn>>;
 }
 
 pub mod recursive {
@@ -30,4 +54,305 @@ pub mod recursive {
         CairoClaim<Fp, AirConfig, ExecutionTrace, LeafVariantMerkleTree<Keccak256HashFn>, SolidityVerifierPublicCoin>;
     pub type CairoVerifierClaim =
         CairoClaim<Fp, AirConfig, ExecutionTrace, FriendlyMerkleTree<NUM_FRIENDLY_COMMITMENT_LAYERS, PedersenHashFn>, CairoVerifierPublicCoin>;
-}
\ No newline at end of file
+    pub type PoseidonCairoVerifierClaim =
+        CairoClaim<Fp, AirConfig, ExecutionTrace, FriendlyMerkleTree<NUM_FRIENDLY_COMMITMENT_LAYERS, PoseidonHashFn>, CairoVerifierPublicCoin>;
+    pub type KeccakNativeClaim =
+        CairoClaim<Fp, AirConfig, ExecutionTrace, MatrixMerkleTreeImpl<Keccak256HashFn>, PublicCoinImpl<Fp, Keccak256HashFn>>;
+    pub type Blake2sNativeClaim =
+        CairoClaim<Fp, AirConfig, ExecutionTrace, MatrixMerkleTreeImpl<Blake2sHashFn>, PublicCoinImpl<Fp, Blake2sHashFn>>;
+}
+
+// TODO: add claims for the `small`, `dex`, `starknet_with_keccak` and
+// `recursive_large_output` layouts once they have an AIR and execution trace
+// (see the layout modules in `layouts`)
+
+// TODO: add `all_solidity::EthVerifierClaim` once the layout has an AIR and
+// execution trace (see `layouts::all_solidity`)
+
+/// Verifier the proof is generated for. StarkWare's verifiers fix the public
+/// coin while `native` proofs are only intended to be verified by sandstorm.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum Verifier {
+    /// StarkWare's Ethereum (Solidity) verifier
+    Evm = 0,
+    /// StarkWare's Cairo verifier
+    Cairo = 1,
+    /// Sandstorm's own verifier
+    Native = 2,
+}
+
+impl Verifier {
+    /// Hash functions the verifier supports. The first is the default.
+    pub const fn hashes(self) -> &'static [CommitmentHash] {
+        match self {
+            Self::Evm => &[CommitmentHash::Keccak],
+            Self::Cairo => &[CommitmentHash::Pedersen, CommitmentHash::Poseidon],
+            Self::Native => &[CommitmentHash::Blake2s, CommitmentHash::Keccak],
+        }
+    }
+
+    /// Verifier used when none is selected
+    pub const fn default_for(layout: Layout) -> Self {
+        match layout {
+            // proofs for the Ethereum verifier use the starknet layout
+            Layout::Starknet => Self::Evm,
+            _ => Self::Cairo,
+        }
+    }
+}
+
+impl Display for Verifier {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Evm => write!(f, "evm"),
+            Self::Cairo => write!(f, "cairo"),
+            Self::Native => write!(f, "native"),
+        }
+    }
+}
+
+impl FromStr for Verifier {
+    type Err = ClaimError;
+
+    fn from_str(s: &str) -> Result<Self, ClaimError> {
+        match s {
+            "evm" => Ok(Self::Evm),
+            "cairo" => Ok(Self::Cairo),
+            "native" => Ok(Self::Native),
+            _ => Err(ClaimError::UnknownVerifier(s.to_string())),
+        }
+    }
+}
+
+impl TryFrom<u8> for Verifier {
+    type Error = ClaimError;
+
+    fn try_from(value: u8) -> Result<Self, ClaimError> {
+        match value {
+            0 => Ok(Self::Evm),
+            1 => Ok(Self::Cairo),
+            2 => Ok(Self::Native),
+            _ => Err(ClaimError::UnknownVerifier(value.to_string())),
+        }
+    }
+}
+
+/// Hash function of the commitments. For the Cairo verifier this is the hash
+/// of the friendly layers (the low layers always use Blake2s).
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum CommitmentHash {
+    Keccak = 0,
+    Blake2s = 1,
+    Pedersen = 2,
+    Poseidon = 3,
+}
+
+impl Display for CommitmentHash {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Keccak => write!(f, "keccak"),
+            Self::Blake2s => write!(f, "blake2s"),
+            Self::Pedersen => write!(f, "pedersen"),
+            Self::Poseidon => write!(f, "poseidon"),
+        }
+    }
+}
+
+impl FromStr for CommitmentHash {
+    type Err = ClaimError;
+
+    fn from_str(s: &str) -> Result<Self, ClaimError> {
+        match s {
+            "keccak" => Ok(Self::Keccak),
+            "blake2s" => Ok(Self::Blake2s),
+            "pedersen" => Ok(Self::Pedersen),
+            "poseidon" => Ok(Self::Poseidon),
+            _ => Err(ClaimError::UnknownHash(s.to_string())),
+        }
+    }
+}
+
+impl TryFrom<u8> for CommitmentHash {
+    type Error = ClaimError;
+
+    fn try_from(value: u8) -> Result<Self, ClaimError> {
+        match value {
+            0 => Ok(Self::Keccak),
+            1 => Ok(Self::Blake2s),
+            2 => Ok(Self::Pedersen),
+            3 => Ok(Self::Poseidon),
+            _ => Err(ClaimError::UnknownHash(value.to_string())),
+        }
+    }
+}
+
+#[derive(Debug, PartialEq, Eq)]
+pub enum ClaimError {
+    UnknownVerifier(String),
+    UnknownHash(String),
+    /// The verifier doesn't support the hash function
+    UnsupportedHash {
+        verifier: Verifier,
+        hash: CommitmentHash,
+    },
+    /// The layout has no claims
+    UnsupportedLayout(Layout),
+}
+
+impl Display for ClaimError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::UnknownVerifier(verifier) => write!(
+                f,
+                "unknown verifier {verifier} (expected evm, cairo or native)"
+            ),
+            Self::UnknownHash(hash) => write!(
+                f,
+                "unknown hash {hash} (expected keccak, blake2s, pedersen or poseidon)"
+            ),
+            Self::UnsupportedHash { verifier, hash } => {
+                let supported = verifier.hashes().iter().map(ToString::to_string);
+                let supported = supported.collect::<Vec<String>>().join(", ");
+                write!(
+                    f,
+                    "the {verifier} verifier doesn't support {hash} commitments (supported: \
+                     {supported})"
+                )
+            }
+            Self::UnsupportedLayout(layout) => write!(f, "layout {layout} has no claims"),
+        }
+    }
+}
+
+impl std::error::Error for ClaimError {}
+
+/// Verifier and commitment hash function of a claim
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct ClaimSelection {
+    verifier: Verifier,
+    hash: CommitmentHash,
+}
+
+impl ClaimSelection {
+    pub fn new(verifier: Verifier, hash: CommitmentHash) -> Result<Self, ClaimError> {
+        if verifier.hashes().contains(&hash) {
+            Ok(Self { verifier, hash })
+        } else {
+            Err(ClaimError::UnsupportedHash { verifier, hash })
+        }
+    }
+
+    /// Fills in the defaults for the layout. Without a selection the starknet
+    /// layout is proven for the Ethereum verifier and other layouts for the
+    /// Cairo verifier.
+    pub fn resolve(
+        layout: Layout,
+        verifier: Option<Verifier>,
+        hash: Option<CommitmentHash>,
+    ) -> Result<Self, ClaimError> {
+        let verifier = verifier.unwrap_or(Verifier::default_for(layout));
+        Self::new(verifier, hash.unwrap_or(verifier.hashes()[0]))
+    }
+
+    pub const fn verifier(&self) -> Verifier {
+        self.verifier
+    }
+
+    pub const fn hash(&self) -> CommitmentHash {
+        self.hash
+    }
+}
+
+impl Display for ClaimSelection {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}/{}", self.verifier, self.hash)
+    }
+}
+
+/// Operation run on the claim picked by [visit_claim]. Claims for StarkWare's
+/// verifiers are passed to [ClaimVisitor::visit_evm] and
+/// [ClaimVisitor::visit_cairo_verifier] which can use their proof formats and
+/// default to [ClaimVisitor::visit].
+pub trait ClaimVisitor: Sized {
+    type Output;
+
+    fn visit<Claim>(self, claim: Claim) -> Self::Output
+    where
+        Claim: Stark<Fp = Fp, Fq = Fp, Witness = CairoWitness<Fp>> + VerifyOutput,
+        Claim::AirConfig: ConstraintRegistry + AirConfig<PublicInputs = AirPublicInput<Fp>>;
+
+    /// Visits a claim for StarkWare's Ethereum verifier
+    fn visit_evm<Claim>(self, claim: Claim) -> Self::Output
+    where
+        Claim: Stark<
+                Fp = Fp,
+                Fq = Fp,
+                Witness = CairoWitness<Fp>,
+                PublicCoin = SolidityVerifierPublicCoin,
+            > + VerifyOutput,
+        Claim::AirConfig:
+            ConstraintRegistry + CairoAirConfig + AirConfig<PublicInputs = AirPublicInput<Fp>>,
+        Claim::MerkleTree: ReadDecommitment<Root = Claim::Digest>,
+        <Claim::MerkleTree as MerkleTree>::Proof: Decommitment,
+    {
+        self.visit(claim)
+    }
+
+    /// Visits a claim for StarkWare's Cairo verifier
+    fn visit_cairo_verifier<Claim>(self, claim: Claim) -> Self::Output
+    where
+        Claim: Stark<
+                Fp = Fp,
+                Fq = Fp,
+                Witness = CairoWitness<Fp>,
+                PublicCoin = CairoVerifierPublicCoin,
+            > + VerifyOutput,
+        Claim::AirConfig:
+            ConstraintRegistry + CairoAirConfig + AirConfig<PublicInputs = AirPublicInput<Fp>>,
+        Claim::MerkleTree: ReadDecommitment<Root = Claim::Digest>,
+        <Claim::MerkleTree as MerkleTree>::Proof: Decommitment,
+    {
+        self.visit(claim)
+    }
+}
+
+/// Instantiates the claim for the layout of the public input and the
+/// selected verifier and hash function then passes it to the visitor
+pub fn visit_claim<V: ClaimVisitor>(
+    selection: ClaimSelection,
+    program: CompiledProgram<Fp>,
+    air_public_input: AirPublicInput<Fp>,
+    visitor: V,
+) -> Result<V::Output, ClaimError> {
+    macro_rules! visit_layout {
+        ($layout:ident) => {{
+            use $layout::*;
+            let (program, air_public_input) = (program, air_public_input);
+            // a selection only holds hash functions its verifier supports
+            match (selection.verifier(), selection.hash()) {
+                (Verifier::Evm, CommitmentHash::Keccak) => {
+                    visitor.visit_evm(EthVerifierClaim::new(program, air_public_input))
+                }
+                (Verifier::Cairo, CommitmentHash::Pedersen) => {
+                    visitor.visit_cairo_verifier(CairoVerifierClaim::new(program, air_public_input))
+                }
+                (Verifier::Cairo, CommitmentHash::Poseidon) => visitor.visit_cairo_verifier(
+                    PoseidonCairoVerifierClaim::new(program, air_public_input),
+                ),
+                (Verifier::Native, CommitmentHash::Keccak) => {
+                    visitor.visit(KeccakNativeClaim::new(program, air_public_input))
+                }
+                (Verifier::Native, CommitmentHash::Blake2s) => {
+                    visitor.visit(Blake2sNativeClaim::new(program, air_public_input))
+                }
+                (verifier, hash) => unreachable!("{verifier} verifier with {hash} commitments"),
+            }
+        }};
+    }
+
+    Ok(match air_public_input.layout {
+        Layout::Starknet => visit_layout!(starknet),
+        Layout::Recursive => visit_layout!(recursive),
+        layout => return Err(ClaimError::UnsupportedLayout(layout)),
+    })
+}
@@ -0,0 +1,345 @@
+//! Calldata for StarkWare's Ethereum verifier. Proofs are too large to verify
+//! in a single transaction so they're split into:
+//! - a Merkle statement for each trace commitment (`verifyMerkle`)
+//! - a FRI statement for each FRI layer (`verifyFRI`)
+//! - the main proof which relies on the facts registered by the statements
+//!   (`verifyProofAndRegister`)
+//!
+//! Query positions and FRI evaluation points aren't part of the proof so they
+//! are recovered by replaying the verifier's public coin.
+
+use crate::annotated::to_hex;
+use crate::annotated::ProofParameters;
+use crate::annotated::ProverChannel;
+use crate::input::CairoAuxInput;
+use crate::transcript::Commitments;
+use crate::transcript::Transcript;
+use ark_ff::Field;
+use ark_ff::PrimeField;
+use ark_poly::EvaluationDomain;
+use ark_poly::Radix2EvaluationDomain;
+use binary::AirPublicInput;
+use binary::Layout;
+use crypto::hash::keccak::CanonicalKeccak256HashFn;
+use crypto::merkle::Decommitment;
+use crypto::public_coin::solidity::SolidityVerifierPublicCoin;
+use crypto::utils::to_montgomery;
+use layouts::CairoAirConfig;
+use ministark::air::AirConfig;
+use ministark::hash::Digest;
+use ministark::merkle::MerkleTree;
+use ministark::stark::Stark;
+use ministark::Proof;
+use ministark_gpu::fields::p3618502788666131213697322783095070105623107215331596699973092056135872020481::ark::Fp;
+use num_bigint::BigUint;
+use ruint::aliases::U256;
+use serde::Serialize;
+use sha3::Digest as _;
+use sha3::Keccak256;
+use std::fmt::Display;
+
+/// Proof that doesn't have the shape the Ethereum verifier expects
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ExportError {
+    /// The number of decommitted leaves doesn't match the number of queries
+    LeafCountMismatch { queries: usize, leaves: usize },
+    /// A FRI layer doesn't have a row of values for each queried coset
+    FriLayerSizeMismatch { expected: usize, actual: usize },
+    /// The evaluation domain of a FRI layer isn't a power of two
+    InvalidDomainSize(usize),
+    /// The public input can't be encoded for the verifier
+    UnsupportedPublicInput(Layout),
+}
+
+impl Display for ExportError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::LeafCountMismatch { queries, leaves } => {
+                write!(f, "{leaves} leaves decommitted for {queries} queries")
+            }
+            Self::FriLayerSizeMismatch { expected, actual } => {
+                write!(f, "FRI layer has {actual} values but {expected} are needed")
+            }
+            Self::InvalidDomainSize(size) => write!(f, "invalid FRI domain size {size}"),
+            Self::UnsupportedPublicInput(layout) => {
+                write!(f, "public input for the {layout} layout can't be encoded")
+            }
+        }
+    }
+}
+
+impl std::error::Error for ExportError {}
+
+/// ABI encoded calldata (as hex) for each transaction of a split proof
+#[derive(Serialize, Clone, Debug, PartialEq, Eq)]
+pub struct SplitProof {
+    pub verify_merkle: Vec<String>,
+    pub verify_fri: Vec<String>,
+    pub verify_proof_and_register: String,
+}
+
+impl SplitProof {
+    /// `task_metadata` and `cairo_verifier_id` are passed through to
+    /// `verifyProofAndRegister`. They describe the tasks of the bootloader run
+    /// and which of the registered Cairo verifiers to use.
+    pub fn new<Claim>(
+        claim: &Claim,
+        proof: &Proof<Claim>,
+        task_metadata: &[U256],
+        cairo_verifier_id: U256,
+    ) -> Result<Self, ExportError>
+    where
+        Claim: Stark<Fp = Fp, Fq = Fp, PublicCoin = SolidityVerifierPublicCoin>,
+        Claim::AirConfig: CairoAirConfig + AirConfig<PublicInputs = AirPublicInput<Fp>>,
+        <Claim::MerkleTree as MerkleTree>::Proof: Decommitment,
+    {
+        let public_input = claim.get_public_inputs();
+        let mut cairo_aux_input = CairoAuxInput(&public_input)
+            .public_input_elements::<CanonicalKeccak256HashFn>()
+            .ok_or(ExportError::UnsupportedPublicInput(public_input.layout))?;
+        let commitments = Commitments::from_proof(proof);
+        let transcript = Transcript::replay(claim, &commitments);
+        let options = proof.options;
+        let lde_domain_size = commitments.lde_domain_size();
+        let queries = &proof.trace_queries;
+
+        // merkle statements for the trace commitments
+        let mut trace_commitments = vec![(
+            proof.base_trace_commitment.as_bytes(),
+            queries.base_trace_proof.leaf_words(),
+            queries.base_trace_proof.authentication_words(),
+        )];
+        if let (Some(commitment), Some(merkle_proof)) = (
+            &proof.extension_trace_commitment,
+            &queries.extension_trace_proof,
+        ) {
+            trace_commitments.push((
+                commitment.as_bytes(),
+                merkle_proof.leaf_words(),
+                merkle_proof.authentication_words(),
+            ));
+        }
+        trace_commitments.push((
+            proof.composition_trace_commitment.as_bytes(),
+            queries.composition_trace_proof.leaf_words(),
+            queries.composition_trace_proof.authentication_words(),
+        ));
+        let verify_merkle = trace_commitments
+            .into_iter()
+            .map(|(root, leaves, authentication_words)| {
+                let height = lde_domain_size.ilog2();
+                let queue = merkle_queue(&transcript.query_positions, &leaves, height)?;
+                Ok(encode_call(
+                    "verifyMerkle(uint256[],uint256[],uint256,uint256)",
+                    &[
+                        Token::Array(authentication_words.iter().map(word).collect()),
+                        Token::Array(queue),
+                        Token::Uint(U256::from(height)),
+                        Token::Uint(word(&root)),
+                    ],
+                ))
+            })
+            .collect::<Result<_, ExportError>>()?;
+
+        // FRI statements for each layer
+        let folding_factor = usize::from(options.fri_folding_factor);
+        let mut positions = transcript.query_positions.clone();
+        let mut domain_size = lde_domain_size;
+        let mut verify_fri = Vec::new();
+        for (layer, alpha) in proof.fri_proof.layers.iter().zip(&transcript.fri_alphas) {
+            let (queue, sibling_values) = fri_queue(
+                &positions,
+                &layer.flattenend_rows,
+                folding_factor,
+                domain_size,
+            )?;
+            let layer_proof = sibling_values
+                .into_iter()
+                .map(to_montgomery)
+                .chain(layer.merkle_proof.authentication_words().iter().map(word))
+                .collect();
+            // the evaluation point isn't in Montgomery form
+            verify_fri.push(encode_call(
+                "verifyFRI(uint256[],uint256[],uint256,uint256,uint256)",
+                &[
+                    Token::Array(layer_proof),
+                    Token::Array(queue),
+                    Token::Uint(field_element(*alpha)),
+                    Token::Uint(U256::from(folding_factor.ilog2())),
+                    Token::Uint(word(&layer.commitment.as_bytes())),
+                ],
+            ));
+
+            positions = positions.iter().map(|p| p / folding_factor).collect();
+            positions.dedup();
+            domain_size /= folding_factor;
+        }
+
+        // main proof without any of the merkle or FRI decommitments
+        let mut channel = ProverChannel::default();
+        channel.send_commitments(proof, &transcript);
+        let trace_values = [
+            &queries.base_trace_values,
+            &queries.extension_trace_values,
+            &queries.composition_trace_values,
+        ];
+        for &value in trace_values.into_iter().flatten() {
+            channel.send_field_element("Decommitment", "Value", value);
+        }
+        let mut proof_bytes = channel.bytes;
+        proof_bytes.resize(proof_bytes.len().next_multiple_of(32), 0);
+        let main_proof = proof_bytes
+            .chunks(32)
+            .map(|chunk| U256::try_from_be_slice(chunk).unwrap())
+            .collect();
+
+        let parameters = ProofParameters::new(proof);
+        let fri_parameters = &parameters.stark.fri;
+        let proof_params = [
+            U256::from(fri_parameters.n_queries),
+            U256::from(parameters.stark.log_n_cosets),
+            U256::from(fri_parameters.proof_of_work_bits),
+            U256::from(fri_parameters.last_layer_degree_bound.ilog2()),
+            U256::from(fri_parameters.fri_step_list.len()),
+        ]
+        .into_iter()
+        .chain(
+            fri_parameters
+                .fri_step_list
+                .iter()
+                .map(|&step| U256::from(step)),
+        )
+        .collect();
+
+        // the verifier expects the public memory challenges at the end of the aux input
+        let (z, alpha) = Claim::AirConfig::public_memory_challenges(&transcript.challenges);
+        cairo_aux_input.push(field_element(z));
+        cairo_aux_input.push(field_element(alpha));
+
+        let verify_proof_and_register = encode_call(
+            "verifyProofAndRegister(uint256[],uint256[],uint256[],uint256[],uint256)",
+            &[
+                Token::Array(proof_params),
+                Token::Array(main_proof),
+                Token::Array(task_metadata.to_vec()),
+                Token::Array(cairo_aux_input),
+                Token::Uint(cairo_verifier_id),
+            ],
+        );
+
+        Ok(Self {
+            verify_merkle,
+            verify_fri,
+            verify_proof_and_register,
+        })
+    }
+}
+
+/// Initial queue of a merkle statement. Pairs of the form `(node index, leaf)`
+/// where the node index of a leaf is `2^height + position`.
+pub fn merkle_queue(
+    positions: &[usize],
+    leaves: &[[u8; 32]],
+    height: u32,
+) -> Result<Vec<U256>, ExportError> {
+    if positions.len() != leaves.len() {
+        return Err(ExportError::LeafCountMismatch {
+            queries: positions.len(),
+            leaves: leaves.len(),
+        });
+    }
+    let first_leaf_index = U256::from(1) << height as usize;
+    Ok(positions
+        .iter()
+        .zip(leaves)
+        .flat_map(|(&position, leaf)| [first_leaf_index + U256::from(position), word(leaf)])
+        .collect())
+}
+
+/// Queue of a FRI statement and the values of the layer that aren't in the
+/// queue. The queue is made up of triples `(node index, value, inverse point)`
+/// followed by a zero where the node index of a value is
+/// `2^log_domain_size + position`. Values are in Montgomery form like the
+/// rest of the proof but inverse points aren't. Layers are evaluated over the
+/// domain in bit-reversed order and the verifier ignores the coset offset.
+/// `positions` must be sorted.
+pub fn fri_queue(
+    positions: &[usize],
+    flattened_rows: &[Fp],
+    folding_factor: usize,
+    domain_size: usize,
+) -> Result<(Vec<U256>, Vec<Fp>), ExportError> {
+    let mut rows = positions
+        .iter()
+        .map(|p| p / folding_factor)
+        .collect::<Vec<usize>>();
+    rows.dedup();
+    if rows.len() * folding_factor != flattened_rows.len() {
+        return Err(ExportError::FriLayerSizeMismatch {
+            expected: rows.len() * folding_factor,
+            actual: flattened_rows.len(),
+        });
+    }
+
+    let domain = Radix2EvaluationDomain::<Fp>::new(domain_size)
+        .filter(|_| domain_size.is_power_of_two())
+        .ok_or(ExportError::InvalidDomainSize(domain_size))?;
+    let log_domain_size = domain_size.ilog2();
+    let first_index = U256::from(1) << log_domain_size as usize;
+    let mut queue = Vec::new();
+    let mut sibling_values = Vec::new();
+    for (row, values) in rows.iter().zip(flattened_rows.chunks(folding_factor)) {
+        for (i, &value) in values.iter().enumerate() {
+            let position = row * folding_factor + i;
+            if positions.binary_search(&position).is_ok() {
+                let exponent = position.reverse_bits() >> (usize::BITS - log_domain_size);
+                let point = domain.group_gen().pow([exponent as u64]);
+                queue.push(first_index + U256::from(position));
+                queue.push(to_montgomery(value));
+                queue.push(field_element(point.inverse().unwrap()));
+            } else {
+                sibling_values.push(value);
+            }
+        }
+    }
+    queue.push(U256::ZERO);
+    Ok((queue, sibling_values))
+}
+
+fn word(bytes: &[u8; 32]) -> U256 {
+    U256::from_be_bytes(*bytes)
+}
+
+fn field_element(v: Fp) -> U256 {
+    U256::from::<BigUint>(v.into_bigint().into())
+}
+
+enum Token {
+    Uint(U256),
+    Array(Vec<U256>),
+}
+
+/// ABI encodes a call to a function that only takes `uint256` and `uint256[]`
+/// arguments
+fn encode_call(signature: &str, args: &[Token]) -> String {
+    let selector = &Keccak256::digest(signature.as_bytes())[0..4];
+    let mut head = Vec::new();
+    let mut tail = Vec::new();
+    for arg in args {
+        match arg {
+            Token::Uint(v) => head.push(*v),
+            Token::Array(values) => {
+                let offset = 32 * (args.len() + tail.len());
+                head.push(U256::from(offset));
+                tail.push(U256::from(values.len()));
+                tail.extend(values);
+            }
+        }
+    }
+
+    let mut calldata = selector.to_vec();
+    for v in head.into_iter().chain(tail) {
+        calldata.extend_from_slice(&v.to_be_bytes::<32>());
+    }
+    to_hex(&calldata)
+}
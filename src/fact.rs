@@ -0,0 +1,154 @@
+//! Facts identify a proven statement on-chain. StarkWare's verifiers register
+//! a fact `keccak(program_hash, keccak(output))` for each program they verify
+//! so contracts can check a program produced an output.
+
+use ark_ff::Field;
+use ark_ff::PrimeField;
+use binary::AirPublicInput;
+use binary::CompiledProgram;
+use builtins::pedersen::pedersen_hash;
+use builtins::poseidon::poseidon_hash_many;
+use ministark_gpu::fields::p3618502788666131213697322783095070105623107215331596699973092056135872020481::ark::Fp;
+use num_bigint::BigUint;
+use ruint::aliases::U256;
+use serde::Serialize;
+use sha3::Digest;
+use sha3::Keccak256;
+use std::fmt::Display;
+
+#[derive(Debug)]
+pub enum FactError {
+    /// The program has no `main` function
+    MissingMain(String),
+    /// A value of the output segment isn't in the public memory
+    MissingOutput { address: u32 },
+}
+
+impl Display for FactError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::MissingMain(name) => write!(f, "program has no function {name}"),
+            Self::MissingOutput { address } => {
+                write!(f, "output at address {address} is not in the public memory")
+            }
+        }
+    }
+}
+
+impl std::error::Error for FactError {}
+
+/// Hash function used for the program hash
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum ProgramHashFn {
+    #[default]
+    Pedersen,
+    Poseidon,
+}
+
+/// Program hash as computed by `compute_program_hash_chain` in cairo-lang
+/// (with bootloader version 0). The program data is prefixed with a header of
+/// the form `[bootloader_version, main, n_builtins, ...builtins]`.
+pub fn program_hash(
+    program: &CompiledProgram<Fp>,
+    hash_fn: ProgramHashFn,
+) -> Result<Fp, FactError> {
+    let main_name = format!("{}.main", program.main_scope);
+    let main = program
+        .identifiers
+        .get(&main_name)
+        .and_then(|identifier| identifier.pc)
+        .ok_or(FactError::MissingMain(main_name))?;
+    // builtins are encoded as the big-endian integer of their ASCII name
+    let builtins = program
+        .builtins
+        .iter()
+        .map(|builtin| Fp::from(BigUint::from_bytes_be(builtin.as_bytes())));
+    let bootloader_version = Fp::from(0u64);
+    let data_chain = [
+        bootloader_version,
+        Fp::from(main as u64),
+        Fp::from(program.builtins.len() as u64),
+    ]
+    .into_iter()
+    .chain(builtins)
+    .chain(program.data.iter().copied())
+    .collect::<Vec<Fp>>();
+
+    Ok(match hash_fn {
+        ProgramHashFn::Pedersen => {
+            // hash chain of the data prefixed with its length
+            let length = Fp::from(data_chain.len() as u64);
+            std::iter::once(length)
+                .chain(data_chain)
+                .rev()
+                .reduce(|acc, v| pedersen_hash(v, acc))
+                .unwrap()
+        }
+        ProgramHashFn::Poseidon => poseidon_hash_many(&data_chain),
+    })
+}
+
+/// Values of the output segment. Programs that don't use the output builtin
+/// have an empty output.
+pub fn program_output<F: Field>(air_public_input: &AirPublicInput<F>) -> Result<Vec<F>, FactError> {
+    let Some(segment) = air_public_input.memory_segments.output else {
+        return Ok(Vec::new());
+    };
+    let entries = air_public_input.output_entries();
+    (segment.begin_addr..segment.stop_ptr)
+        .map(|address| {
+            let offset = (address - segment.begin_addr) as usize;
+            (entries.get(offset))
+                .filter(|entry| entry.address == address)
+                .map(|entry| entry.value)
+                .ok_or(FactError::MissingOutput { address })
+        })
+        .collect()
+}
+
+/// Fact registered for a program and its output
+#[derive(Serialize, Clone, Debug, PartialEq, Eq)]
+pub struct Fact {
+    pub program_hash: U256,
+    pub output: Vec<U256>,
+    pub output_hash: U256,
+    pub fact: U256,
+}
+
+impl Fact {
+    pub fn new(program_hash: U256, output: Vec<U256>) -> Self {
+        let output_hash = keccak_words(&output);
+        let fact = keccak_words(&[program_hash, output_hash]);
+        Self {
+            program_hash,
+            output,
+            output_hash,
+            fact,
+        }
+    }
+
+    /// Fact of a run of `program` with its program hash computed with Pedersen
+    pub fn from_run(
+        program: &CompiledProgram<Fp>,
+        air_public_input: &AirPublicInput<Fp>,
+    ) -> Result<Self, FactError> {
+        let program_hash = program_hash(program, ProgramHashFn::Pedersen)?;
+        let output = program_output(air_public_input)?;
+        Ok(Self::new(
+            field_element(program_hash),
+            output.into_iter().map(field_element).collect(),
+        ))
+    }
+}
+
+fn keccak_words(words: &[U256]) -> U256 {
+    let mut hasher = Keccak256::new();
+    for word in words {
+        hasher.update(word.to_be_bytes::<32>());
+    }
+    U256::from_be_bytes::<32>(hasher.finalize().into())
+}
+
+pub(crate) fn field_element(v: Fp) -> U256 {
+    U256::from::<BigUint>(v.into_bigint().into())
+}
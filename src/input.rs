@@ -2,12 +2,24 @@ use binary::{AirPublicInput, Layout};
 use ministark::hash::{ElementHashFn, Digest};
 use ministark_gpu::fields::p3618502788666131213697322783095070105623107215331596699973092056135872020481::ark::Fp;
 use num_bigint::BigUint;
-use ruint::{aliases::U256, uint};
+use ruint::aliases::U256;
 
 pub struct CairoAuxInput<'a>(pub &'a AirPublicInput<Fp>);
 
+/// Public memory page as it's committed to by the public input
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct PageInfo {
+    /// First address of a continuous page. `None` for the main page.
+    pub start_address: Option<u32>,
+    /// Number of entries in the page
+    pub size: usize,
+    /// Hash of the (address, value) pairs of the main page or of the values of
+    /// a continuous page
+    pub hash: U256,
+}
+
 impl<'a> CairoAuxInput<'a> {
-    fn base_values(&self) -> Vec<U256> {
+    fn base_values(&self) -> Option<Vec<U256>> {
         const OFFSET_LOG_N_STEPS: usize = 0;
         const OFFSET_RC_MIN: usize = 1;
         const OFFSET_RC_MAX: usize = 2;
@@ -30,7 +42,7 @@ impl<'a> CairoAuxInput<'a> {
         vals[OFFSET_LOG_N_STEPS] = Some(U256::from(self.0.n_steps.ilog2()));
         vals[OFFSET_RC_MIN] = Some(U256::from(self.0.rc_min));
         vals[OFFSET_RC_MAX] = Some(U256::from(self.0.rc_max));
-        vals[OFFSET_LAYOUT_CODE] = Some(U256::from(self.0.layout.sharp_code()));
+        vals[OFFSET_LAYOUT_CODE] = self.0.layout.sharp_code();
         vals[OFFSET_PROGRAM_BEGIN_ADDR] = Some(U256::from(segments.program.begin_addr));
         vals[OFFSET_PROGRAM_STOP_PTR] = Some(U256::from(segments.program.stop_ptr));
         vals[OFFSET_EXECUTION_BEGIN_ADDR] = Some(U256::from(segments.execution.begin_addr));
@@ -42,12 +54,13 @@ impl<'a> CairoAuxInput<'a> {
         vals[OFFSET_RANGE_CHECK_BEGIN_ADDR] =
             segments.range_check.map(|s| U256::from(s.begin_addr));
         vals[OFFSET_RANGE_CHECK_STOP_PTR] = segments.range_check.map(|s| U256::from(s.stop_ptr));
-        vals.map(Option::unwrap).to_vec()
+        vals.into_iter().collect()
     }
 
-    fn layout_specific_values(&self) -> Vec<U256> {
+    fn layout_specific_values(&self) -> Option<Vec<U256>> {
         let segments = self.0.memory_segments;
         let public_memory_padding = self.0.public_memory_padding();
+        let n_public_memory_pages = U256::from(self.0.public_memory_pages.len());
 
         match self.0.layout {
             Layout::Starknet => {
@@ -79,10 +92,44 @@ impl<'a> CairoAuxInput<'a> {
                     Some(U256::from(public_memory_padding.address));
                 vals[OFFSET_PUBLIC_MEMORY_PADDING_VALUE] =
                     Some(U256::from::<BigUint>(public_memory_padding.value.into()));
-                // Only 1 memory page currently for the main memory page
-                // TODO: support more memory pages
-                vals[OFFSET_N_PUBLIC_MEMORY_PAGES] = Some(uint!(1_U256));
-                vals.map(Option::unwrap).to_vec()
+                vals[OFFSET_N_PUBLIC_MEMORY_PAGES] = Some(n_public_memory_pages);
+                vals.into_iter().collect()
+            }
+            Layout::StarknetWithKeccak => {
+                const OFFSET_ECDSA_BEGIN_ADDR: usize = 0;
+                const OFFSET_ECDSA_STOP_PTR: usize = 1;
+                const OFFSET_BITWISE_BEGIN_ADDR: usize = 2;
+                const OFFSET_BITWISE_STOP_ADDR: usize = 3;
+                const OFFSET_EC_OP_BEGIN_ADDR: usize = 4;
+                const OFFSET_EC_OP_STOP_ADDR: usize = 5;
+                const OFFSET_KECCAK_BEGIN_ADDR: usize = 6;
+                const OFFSET_KECCAK_STOP_PTR: usize = 7;
+                const OFFSET_POSEIDON_BEGIN_ADDR: usize = 8;
+                const OFFSET_POSEIDON_STOP_PTR: usize = 9;
+                const OFFSET_PUBLIC_MEMORY_PADDING_ADDR: usize = 10;
+                const OFFSET_PUBLIC_MEMORY_PADDING_VALUE: usize = 11;
+                const OFFSET_N_PUBLIC_MEMORY_PAGES: usize = 12;
+
+                const NUM_VALS: usize = OFFSET_N_PUBLIC_MEMORY_PAGES + 1;
+                let mut vals = [None; NUM_VALS];
+                vals[OFFSET_ECDSA_BEGIN_ADDR] = segments.ecdsa.map(|s| U256::from(s.begin_addr));
+                vals[OFFSET_ECDSA_STOP_PTR] = segments.ecdsa.map(|s| U256::from(s.stop_ptr));
+                vals[OFFSET_BITWISE_BEGIN_ADDR] =
+                    segments.bitwise.map(|s| U256::from(s.begin_addr));
+                vals[OFFSET_BITWISE_STOP_ADDR] = segments.bitwise.map(|s| U256::from(s.stop_ptr));
+                vals[OFFSET_EC_OP_BEGIN_ADDR] = segments.ec_op.map(|s| U256::from(s.begin_addr));
+                vals[OFFSET_EC_OP_STOP_ADDR] = segments.ec_op.map(|s| U256::from(s.stop_ptr));
+                vals[OFFSET_KECCAK_BEGIN_ADDR] = segments.keccak.map(|s| U256::from(s.begin_addr));
+                vals[OFFSET_KECCAK_STOP_PTR] = segments.keccak.map(|s| U256::from(s.stop_ptr));
+                vals[OFFSET_POSEIDON_BEGIN_ADDR] =
+                    segments.poseidon.map(|s| U256::from(s.begin_addr));
+                vals[OFFSET_POSEIDON_STOP_PTR] = segments.poseidon.map(|s| U256::from(s.stop_ptr));
+                vals[OFFSET_PUBLIC_MEMORY_PADDING_ADDR] =
+                    Some(U256::from(public_memory_padding.address));
+                vals[OFFSET_PUBLIC_MEMORY_PADDING_VALUE] =
+                    Some(U256::from::<BigUint>(public_memory_padding.value.into()));
+                vals[OFFSET_N_PUBLIC_MEMORY_PAGES] = Some(n_public_memory_pages);
+                vals.into_iter().collect()
             }
             Layout::Recursive => {
                 const OFFSET_BITWISE_BEGIN_ADDR: usize = 0;
@@ -101,12 +148,80 @@ impl<'a> CairoAuxInput<'a> {
                     Some(U256::from(public_memory_padding.address));
                 vals[OFFSET_PUBLIC_MEMORY_PADDING_VALUE] =
                     Some(U256::from::<BigUint>(public_memory_padding.value.into()));
-                // Only 1 memory page currently for the main memory page
-                // TODO: support more memory pages
-                vals[OFFSET_N_PUBLIC_MEMORY_PAGES] = Some(uint!(1_U256));
-                vals.map(Option::unwrap).to_vec()
+                vals[OFFSET_N_PUBLIC_MEMORY_PAGES] = Some(n_public_memory_pages);
+                vals.into_iter().collect()
             }
-            _ => unimplemented!(),
+            Layout::AllSolidity => {
+                const OFFSET_ECDSA_BEGIN_ADDR: usize = 0;
+                const OFFSET_ECDSA_STOP_PTR: usize = 1;
+                const OFFSET_BITWISE_BEGIN_ADDR: usize = 2;
+                const OFFSET_BITWISE_STOP_ADDR: usize = 3;
+                const OFFSET_EC_OP_BEGIN_ADDR: usize = 4;
+                const OFFSET_EC_OP_STOP_ADDR: usize = 5;
+                const OFFSET_PUBLIC_MEMORY_PADDING_ADDR: usize = 6;
+                const OFFSET_PUBLIC_MEMORY_PADDING_VALUE: usize = 7;
+                const OFFSET_N_PUBLIC_MEMORY_PAGES: usize = 8;
+
+                const NUM_VALS: usize = OFFSET_N_PUBLIC_MEMORY_PAGES + 1;
+                let mut vals = [None; NUM_VALS];
+                vals[OFFSET_ECDSA_BEGIN_ADDR] = segments.ecdsa.map(|s| U256::from(s.begin_addr));
+                vals[OFFSET_ECDSA_STOP_PTR] = segments.ecdsa.map(|s| U256::from(s.stop_ptr));
+                vals[OFFSET_BITWISE_BEGIN_ADDR] =
+                    segments.bitwise.map(|s| U256::from(s.begin_addr));
+                vals[OFFSET_BITWISE_STOP_ADDR] = segments.bitwise.map(|s| U256::from(s.stop_ptr));
+                vals[OFFSET_EC_OP_BEGIN_ADDR] = segments.ec_op.map(|s| U256::from(s.begin_addr));
+                vals[OFFSET_EC_OP_STOP_ADDR] = segments.ec_op.map(|s| U256::from(s.stop_ptr));
+                vals[OFFSET_PUBLIC_MEMORY_PADDING_ADDR] =
+                    Some(U256::from(public_memory_padding.address));
+                vals[OFFSET_PUBLIC_MEMORY_PADDING_VALUE] =
+                    Some(U256::from::<BigUint>(public_memory_padding.value.into()));
+                vals[OFFSET_N_PUBLIC_MEMORY_PAGES] = Some(n_public_memory_pages);
+                vals.into_iter().collect()
+            }
+            Layout::RecursiveLargeOutput => {
+                const OFFSET_BITWISE_BEGIN_ADDR: usize = 0;
+                const OFFSET_BITWISE_STOP_ADDR: usize = 1;
+                const OFFSET_POSEIDON_BEGIN_ADDR: usize = 2;
+                const OFFSET_POSEIDON_STOP_PTR: usize = 3;
+                const OFFSET_PUBLIC_MEMORY_PADDING_ADDR: usize = 4;
+                const OFFSET_PUBLIC_MEMORY_PADDING_VALUE: usize = 5;
+                const OFFSET_N_PUBLIC_MEMORY_PAGES: usize = 6;
+
+                const NUM_VALS: usize = OFFSET_N_PUBLIC_MEMORY_PAGES + 1;
+                let mut vals = [None; NUM_VALS];
+                vals[OFFSET_BITWISE_BEGIN_ADDR] =
+                    segments.bitwise.map(|s| U256::from(s.begin_addr));
+                vals[OFFSET_BITWISE_STOP_ADDR] = segments.bitwise.map(|s| U256::from(s.stop_ptr));
+                vals[OFFSET_POSEIDON_BEGIN_ADDR] =
+                    segments.poseidon.map(|s| U256::from(s.begin_addr));
+                vals[OFFSET_POSEIDON_STOP_PTR] = segments.poseidon.map(|s| U256::from(s.stop_ptr));
+                vals[OFFSET_PUBLIC_MEMORY_PADDING_ADDR] =
+                    Some(U256::from(public_memory_padding.address));
+                vals[OFFSET_PUBLIC_MEMORY_PADDING_VALUE] =
+                    Some(U256::from::<BigUint>(public_memory_padding.value.into()));
+                vals[OFFSET_N_PUBLIC_MEMORY_PAGES] = Some(n_public_memory_pages);
+                vals.into_iter().collect()
+            }
+            // both layouts have the same builtins
+            Layout::Small | Layout::Dex => {
+                const OFFSET_ECDSA_BEGIN_ADDR: usize = 0;
+                const OFFSET_ECDSA_STOP_PTR: usize = 1;
+                const OFFSET_PUBLIC_MEMORY_PADDING_ADDR: usize = 2;
+                const OFFSET_PUBLIC_MEMORY_PADDING_VALUE: usize = 3;
+                const OFFSET_N_PUBLIC_MEMORY_PAGES: usize = 4;
+
+                const NUM_VALS: usize = OFFSET_N_PUBLIC_MEMORY_PAGES + 1;
+                let mut vals = [None; NUM_VALS];
+                vals[OFFSET_ECDSA_BEGIN_ADDR] = segments.ecdsa.map(|s| U256::from(s.begin_addr));
+                vals[OFFSET_ECDSA_STOP_PTR] = segments.ecdsa.map(|s| U256::from(s.stop_ptr));
+                vals[OFFSET_PUBLIC_MEMORY_PADDING_ADDR] =
+                    Some(U256::from(public_memory_padding.address));
+                vals[OFFSET_PUBLIC_MEMORY_PADDING_VALUE] =
+                    Some(U256::from::<BigUint>(public_memory_padding.value.into()));
+                vals[OFFSET_N_PUBLIC_MEMORY_PAGES] = Some(n_public_memory_pages);
+                vals.into_iter().collect()
+            }
+            _ => None,
         }
     }
 
@@ -116,36 +231,59 @@ impl<'a> CairoAuxInput<'a> {
         // For each page:
         // * First address in the page (this field is not included for the first page).
         // * Page size. (number of memory pairs)
-        // * Page hash (hash of memory pairs)
-        // TODO: support other memory pages
-        const _PAGE_INFO_ADDRESS_OFFSET: usize = 0;
-        const _PAGE_INFO_SIZE_OFFSET: usize = 1;
-        const _PAGE_INFO_HASH_OFFSET: usize = 2;
-
-        // Hash the address value pairs of the main memory page
-        let main_page_hash: [u8; 32] = {
-            let memory_elements = self
-                .0
-                .public_memory
-                .iter()
-                .flat_map(|e| [e.address.into(), e.value]);
-            H::hash_elements(memory_elements).as_bytes()
-        };
-
-        // NOTE: no address main memory page because It's implicitly "1".
-        let mut main_page = [None; 2];
-        main_page[0] = Some(U256::from(self.0.public_memory.len()));
-        main_page[1] = Some(U256::try_from_be_slice(&main_page_hash).unwrap());
-
-        main_page.map(Option::unwrap).to_vec()
+        // * Page hash (hash of memory pairs for the main page and hash of the values
+        //   for continuous pages)
+        const PAGE_INFO_ADDRESS_OFFSET: usize = 0;
+        const PAGE_INFO_SIZE_OFFSET: usize = 1;
+        const PAGE_INFO_HASH_OFFSET: usize = 2;
+
+        let mut vals = Vec::new();
+        for page in self.memory_pages::<H>() {
+            let mut page_info = [None; 3];
+            page_info[PAGE_INFO_ADDRESS_OFFSET] = page.start_address.map(U256::from);
+            page_info[PAGE_INFO_SIZE_OFFSET] = Some(U256::from(page.size));
+            page_info[PAGE_INFO_HASH_OFFSET] = Some(page.hash);
+            vals.extend(page_info.into_iter().flatten());
+        }
+
+        vals
+    }
+
+    /// Returns the public memory pages along with their hashes. The main page
+    /// is first.
+    pub fn memory_pages<H: ElementHashFn<Fp>>(&self) -> Vec<PageInfo> {
+        let mut pages = Vec::new();
+        for page in &self.0.public_memory_pages {
+            let entries = self.0.page_entries(page);
+            let page_hash: [u8; 32] = if page.is_main() {
+                // Hash the address value pairs of the main memory page
+                let memory_elements = entries.iter().flat_map(|e| [e.address.into(), e.value]);
+                H::hash_elements(memory_elements).as_bytes()
+            } else {
+                H::hash_elements(entries.iter().map(|e| e.value)).as_bytes()
+            };
+
+            pages.push(PageInfo {
+                // NOTE: no address main memory page because It's implicitly "1".
+                start_address: (!page.is_main()).then_some(page.start_address),
+                size: entries.len(),
+                hash: U256::try_from_be_slice(&page_hash).unwrap(),
+            });
+        }
+        pages
     }
 
-    pub fn public_input_elements<H: ElementHashFn<Fp>>(&self) -> Vec<U256> {
-        [
-            self.base_values(),
-            self.layout_specific_values(),
-            self.memory_page_values::<H>(),
-        ]
-        .concat()
+    /// Returns the public input as it's committed to by StarkWare's verifiers.
+    /// `None` if the layout has no public input encoding or a builtin segment
+    /// used by the layout is missing.
+    pub fn public_input_elements<H: ElementHashFn<Fp>>(&self) -> Option<Vec<U256>> {
+        Some(
+            [
+                self.base_values()?,
+                self.layout_specific_values()?,
+                self.memory_page_values::<H>(),
+            ]
+            .concat(),
+        )
     }
 }
@@ -5,12 +5,15 @@ use ark_ff::PrimeField;
 use ark_serialize::CanonicalSerialize;
 use binary::AirPublicInput;
 use binary::CompiledProgram;
+use binary::MemoryEntry;
 use crypto::hash::blake2s::Blake2sHashFn;
 use crypto::hash::keccak::CanonicalKeccak256HashFn;
 use crypto::hash::pedersen::PedersenHashFn;
 use crypto::merkle::mixed::MixedMerkleDigest;
 use crypto::public_coin::cairo::CairoVerifierPublicCoin;
 use crypto::public_coin::solidity::SolidityVerifierPublicCoin;
+use fact::program_output;
+use fact::FactError;
 use input::CairoAuxInput;
 use layouts::CairoTrace;
 use layouts::CairoWitness;
@@ -23,13 +26,26 @@ use ministark::merkle::MerkleTree;
 use ministark::random::PublicCoin;
 use ministark::random::PublicCoinImpl;
 use ministark::stark::Stark;
+use ministark::verifier::VerificationError;
 use ministark::Air;
+use ministark::Proof;
 use ministark_gpu::GpuFftField;
 use ministark_gpu::fields::p3618502788666131213697322783095070105623107215331596699973092056135872020481::ark::Fp;
+use std::fmt::Display;
+use std::iter::zip;
 use std::marker::PhantomData;
 
+pub mod aggregate;
+pub mod annotated;
+pub mod batch;
+pub mod bundle;
+pub mod cairo_verifier;
 pub mod claims;
+pub mod evm;
+pub mod fact;
 pub mod input;
+pub mod stone;
+mod transcript;
 
 pub struct CairoClaim<
     Fp: GpuFftField + PrimeField,
@@ -72,6 +88,61 @@ where
     }
 }
 
+#[derive(Debug)]
+pub enum OutputError {
+    Verification(VerificationError),
+    /// The output segment isn't entirely in the public memory
+    Output(FactError),
+}
+
+impl Display for OutputError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Verification(e) => write!(f, "proof verification failed: {e:?}"),
+            Self::Output(e) => e.fmt(f),
+        }
+    }
+}
+
+impl std::error::Error for OutputError {}
+
+/// Claim about a Cairo program run whose output is part of the public input
+pub trait VerifyOutput: Stark {
+    /// Verifies a proof of the claim and returns the program's output. The
+    /// output segment is part of the public memory so the output is verified
+    /// along with the proof.
+    fn verify_output(
+        &self,
+        proof: Proof<Self>,
+        required_security_bits: usize,
+    ) -> Result<Vec<MemoryEntry<Self::Fp>>, OutputError>;
+}
+
+impl<
+        Fp: GpuFftField + PrimeField,
+        A: AirConfig<Fp = Fp, PublicInputs = AirPublicInput<Fp>>,
+        T: CairoTrace<Fp = A::Fp, Fq = A::Fq>,
+        M: MerkleTree + MatrixMerkleTree<A::Fp> + MatrixMerkleTree<A::Fq>,
+        P: CairoPublicCoin<Digest = M::Root, Field = A::Fq>,
+    > VerifyOutput for CairoClaim<Fp, A, T, M, P>
+where
+    A::Fp: PrimeField,
+{
+    fn verify_output(
+        &self,
+        proof: Proof<Self>,
+        required_security_bits: usize,
+    ) -> Result<Vec<MemoryEntry<Fp>>, OutputError> {
+        (self.verify(proof, required_security_bits)).map_err(OutputError::Verification)?;
+        let output = program_output(&self.air_public_input).map_err(OutputError::Output)?;
+        let output_segment = self.air_public_input.memory_segments.output;
+        let begin_addr = output_segment.map_or(0, |segment| segment.begin_addr);
+        Ok(zip(begin_addr.., output)
+            .map(|(address, value)| MemoryEntry { address, value })
+            .collect())
+    }
+}
+
 impl<
         Fp: GpuFftField + PrimeField,
         A: AirConfig<Fp = Fp, PublicInputs = AirPublicInput<Fp>>,
@@ -146,7 +217,10 @@ impl CairoPublicCoin for SolidityVerifierPublicCoin {
     fn from_public_input(public_input: &AirPublicInput<Fp>) -> Self {
         let aux_input = CairoAuxInput(public_input);
         let mut seed = Vec::new();
-        for element in aux_input.public_input_elements::<CanonicalKeccak256HashFn>() {
+        let elements = aux_input
+            .public_input_elements::<CanonicalKeccak256HashFn>()
+            .expect("claims are only built for layouts with a public input encoding");
+        for element in elements {
             seed.extend_from_slice(&element.to_be_bytes::<32>())
         }
         Self::new(CanonicalKeccak256HashFn::hash_chunks([&*seed]))
@@ -157,7 +231,10 @@ impl CairoPublicCoin for CairoVerifierPublicCoin {
     fn from_public_input(public_input: &AirPublicInput<Fp>) -> Self {
         let aux_input = CairoAuxInput(public_input);
         let mut seed = Vec::new();
-        for element in aux_input.public_input_elements::<PedersenHashFn>() {
+        let elements = aux_input
+            .public_input_elements::<PedersenHashFn>()
+            .expect("claims are only built for layouts with a public input encoding");
+        for element in elements {
             seed.extend_from_slice(&element.to_be_bytes::<32>())
         }
         Self::new(MixedMerkleDigest::LowLevel(Blake2sHashFn::hash_chunks([
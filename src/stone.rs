@@ -0,0 +1,428 @@
+//! Imports proofs generated by StarkWare's stone-prover (or exported with
+//! [crate::annotated]) so they can be verified with ministark. Proofs for the
+//! Ethereum verifier (Keccak commitments) and the Cairo verifier (friendly
+//! commitments) are supported.
+
+use crate::annotated::ProofParameters;
+use crate::transcript::Commitments;
+use crate::transcript::Transcript;
+use ark_ff::FftField;
+use ark_ff::Field;
+use crypto::merkle::ReadDecommitment;
+use crypto::utils::from_montgomery;
+use ministark::air::AirConfig;
+use ministark::fri::FriProof;
+use ministark::fri::FriProofLayer;
+use ministark::stark::Stark;
+use ministark::trace::Queries;
+use ministark::Air;
+use ministark::Proof;
+use ministark::ProofOptions;
+use ministark_gpu::fields::p3618502788666131213697322783095070105623107215331596699973092056135872020481::ark::Fp;
+use ruint::aliases::U256;
+use serde::Deserialize;
+use std::fmt::Display;
+use std::iter::zip;
+
+#[derive(Debug)]
+pub enum ImportError {
+    InvalidHex,
+    /// The proof ended before all messages were read
+    UnexpectedEnd,
+    /// Bytes were left over after all messages were read
+    TrailingBytes(usize),
+    UnsupportedParameters(String),
+}
+
+impl Display for ImportError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::InvalidHex => write!(f, "proof_hex is not a valid hex string"),
+            Self::UnexpectedEnd => write!(f, "proof ended unexpectedly"),
+            Self::TrailingBytes(n) => write!(f, "{n} bytes left over after reading the proof"),
+            Self::UnsupportedParameters(reason) => write!(f, "unsupported parameters: {reason}"),
+        }
+    }
+}
+
+impl std::error::Error for ImportError {}
+
+/// JSON proof in StarkWare's format. Annotations are ignored.
+#[derive(Deserialize, Clone, Debug)]
+pub struct StoneProof {
+    pub proof_parameters: ProofParameters,
+    pub proof_hex: String,
+}
+
+impl StoneProof {
+    /// Reads the proof's messages into a ministark proof for the claim. The
+    /// number of messages depends on the claim's AIR and the query positions
+    /// so the verifier's public coin is replayed while reading.
+    pub fn into_proof<Claim>(&self, claim: &Claim) -> Result<Proof<Claim>, ImportError>
+    where
+        Claim: Stark<Fp = Fp, Fq = Fp>,
+        Claim::MerkleTree: ReadDecommitment<Root = Claim::Digest>,
+    {
+        let (options, trace_len) = self.options()?;
+        let mut reader = ProofReader::new(&self.proof_hex)?;
+
+        let air = Air::new(trace_len, claim.get_public_inputs(), options);
+        let num_fri_layers = self.proof_parameters.stark.fri.fri_step_list.len() - 1;
+        let num_base_columns = Claim::AirConfig::NUM_BASE_COLUMNS;
+        let num_extension_columns = Claim::AirConfig::NUM_EXTENSION_COLUMNS;
+        let num_composition_columns = air.ce_blowup_factor();
+        let folding_factor = usize::from(options.fri_folding_factor);
+        let base_trace_commitment = reader.read_root::<Claim::MerkleTree>(num_base_columns)?;
+        let extension_trace_commitment = if num_extension_columns > 0 {
+            Some(reader.read_root::<Claim::MerkleTree>(num_extension_columns)?)
+        } else {
+            None
+        };
+        let composition_trace_commitment =
+            reader.read_root::<Claim::MerkleTree>(num_composition_columns)?;
+        let execution_trace_ood_evals = reader.read_field_elements(air.trace_arguments().len())?;
+        let composition_trace_ood_evals = reader.read_field_elements(air.ce_blowup_factor())?;
+        let fri_layer_commitments = (0..num_fri_layers)
+            .map(|_| reader.read_root::<Claim::MerkleTree>(folding_factor))
+            .collect::<Result<Vec<_>, _>>()?;
+        let remainder_coeffs =
+            reader.read_field_elements(self.proof_parameters.stark.fri.last_layer_degree_bound)?;
+        let pow_nonce = reader.read_u64()?;
+        let commitments = Commitments::<Claim> {
+            trace_len,
+            options,
+            base_trace_commitment,
+            extension_trace_commitment,
+            composition_trace_commitment,
+            execution_trace_ood_evals,
+            composition_trace_ood_evals,
+            fri_layer_commitments,
+            remainder_coeffs,
+            pow_nonce,
+        };
+
+        let transcript = Transcript::replay(claim, &commitments);
+        let positions = &transcript.query_positions;
+        let lde_domain_size = commitments.lde_domain_size();
+        let height = lde_domain_size.ilog2();
+
+        // trace decommitments
+        let base_trace_values = reader.read_field_elements(positions.len() * num_base_columns)?;
+        let base_trace_proof = reader.read_decommitment::<Claim::MerkleTree>(
+            &base_trace_values,
+            num_base_columns,
+            positions,
+            height,
+        )?;
+        let (extension_trace_values, extension_trace_proof) = if num_extension_columns > 0 {
+            let values = reader.read_field_elements(positions.len() * num_extension_columns)?;
+            let proof = reader.read_decommitment::<Claim::MerkleTree>(
+                &values,
+                num_extension_columns,
+                positions,
+                height,
+            )?;
+            (values, Some(proof))
+        } else {
+            (Vec::new(), None)
+        };
+        let composition_trace_values =
+            reader.read_field_elements(positions.len() * num_composition_columns)?;
+        let composition_trace_proof = reader.read_decommitment::<Claim::MerkleTree>(
+            &composition_trace_values,
+            num_composition_columns,
+            positions,
+            height,
+        )?;
+
+        // FRI layer decommitments only include the values that aren't queried.
+        // The queried values of the first layer are evaluations of the DEEP
+        // composition polynomial and the others are folded from the layer
+        // before.
+        let mut queried_values = deep_evaluations(
+            &air,
+            &commitments,
+            &transcript,
+            &base_trace_values,
+            &extension_trace_values,
+            &composition_trace_values,
+        );
+        let mut layer_positions = positions.clone();
+        let mut domain_size = lde_domain_size;
+        let mut layers = Vec::new();
+        let fri_layers = zip(&commitments.fri_layer_commitments, &transcript.fri_alphas);
+        for (commitment, &alpha) in fri_layers {
+            let mut rows = layer_positions
+                .iter()
+                .map(|p| p / folding_factor)
+                .collect::<Vec<usize>>();
+            rows.dedup();
+            let height = (domain_size / folding_factor).ilog2();
+            let num_sibling_values = rows.len() * folding_factor - layer_positions.len();
+            let mut sibling_values = reader.read_field_elements(num_sibling_values)?.into_iter();
+            let mut queried = zip(&layer_positions, &queried_values).peekable();
+            let mut flattenend_rows = Vec::with_capacity(rows.len() * folding_factor);
+            for position in rows
+                .iter()
+                .flat_map(|row| row * folding_factor..(row + 1) * folding_factor)
+            {
+                let value = match queried.next_if(|(&p, _)| p == position) {
+                    Some((_, &value)) => value,
+                    None => sibling_values.next().ok_or(ImportError::UnexpectedEnd)?,
+                };
+                flattenend_rows.push(value);
+            }
+            let merkle_proof = reader.read_decommitment::<Claim::MerkleTree>(
+                &flattenend_rows,
+                folding_factor,
+                &rows,
+                height,
+            )?;
+            queried_values = zip(&rows, flattenend_rows.chunks(folding_factor))
+                .map(|(&row, values)| fold_row(values, row, domain_size, alpha))
+                .collect();
+            layers.push(FriProofLayer {
+                flattenend_rows,
+                merkle_proof,
+                commitment: commitment.clone(),
+            });
+            layer_positions = rows;
+            domain_size /= folding_factor;
+        }
+
+        if reader.remaining() != 0 {
+            return Err(ImportError::TrailingBytes(reader.remaining()));
+        }
+
+        Ok(Proof {
+            options,
+            trace_len,
+            base_trace_commitment: commitments.base_trace_commitment,
+            extension_trace_commitment: commitments.extension_trace_commitment,
+            composition_trace_commitment: commitments.composition_trace_commitment,
+            fri_proof: FriProof {
+                layers,
+                remainder_coeffs: commitments.remainder_coeffs,
+            },
+            pow_nonce,
+            trace_queries: Queries {
+                base_trace_values,
+                extension_trace_values,
+                composition_trace_values,
+                base_trace_proof,
+                extension_trace_proof,
+                composition_trace_proof,
+            },
+            execution_trace_ood_evals: commitments.execution_trace_ood_evals,
+            composition_trace_ood_evals: commitments.composition_trace_ood_evals,
+        })
+    }
+
+    /// Proof options and trace length from the proof parameters. ministark
+    /// folds every FRI layer by the same factor so the FRI steps (after the
+    /// first) have to be equal.
+    fn options(&self) -> Result<(ProofOptions, usize), ImportError> {
+        let stark = &self.proof_parameters.stark;
+        let fri = &stark.fri;
+        let unsupported = |reason: &str| Err(ImportError::UnsupportedParameters(reason.into()));
+        if self.proof_parameters.use_extension_field {
+            return unsupported("extension fields are not supported");
+        }
+
+        let (&first_step, steps) = match fri.fri_step_list.split_first() {
+            Some(split) => split,
+            None => return unsupported("fri_step_list is empty"),
+        };
+        let step = steps.first().copied().unwrap_or(1);
+        if first_step != 0 || steps.iter().any(|&s| s != step) {
+            return unsupported("FRI steps must be of the form [0, s, s, ..., s]");
+        }
+        if !fri.last_layer_degree_bound.is_power_of_two() {
+            return unsupported("last_layer_degree_bound must be a power of two");
+        }
+        let Ok(fri_max_remainder_coeffs) = u8::try_from(fri.last_layer_degree_bound) else {
+            return unsupported("last_layer_degree_bound must be less than 256");
+        };
+
+        let fri_folding_factor = 1 << step;
+        let lde_blowup_factor = 1 << stark.log_n_cosets;
+        let options = ProofOptions::new(
+            fri.n_queries,
+            lde_blowup_factor,
+            fri.proof_of_work_bits,
+            fri_folding_factor,
+            fri_max_remainder_coeffs,
+        );
+        let shift = step as usize * steps.len();
+        let trace_len = u32::try_from(shift)
+            .ok()
+            .and_then(|shift| fri.last_layer_degree_bound.checked_shl(shift))
+            .filter(|trace_len| trace_len >> shift == fri.last_layer_degree_bound);
+        let Some(trace_len) = trace_len else {
+            return unsupported("trace length doesn't fit in a usize");
+        };
+        Ok((options, trace_len))
+    }
+}
+
+/// Evaluations of the DEEP composition polynomial at the query positions:
+/// `Σ α_i (t_i(x) - t_i(z g^k_i)) / (x - z g^k_i)` over the trace arguments
+/// plus `Σ β_j (h_j(x) - h_j(z^m)) / (x - z^m)` over the `m` columns of the
+/// composition trace. The trace values are rows in query position order.
+fn deep_evaluations<Claim: Stark<Fp = Fp, Fq = Fp>>(
+    air: &Air<Claim::AirConfig>,
+    commitments: &Commitments<Claim>,
+    transcript: &Transcript<Claim>,
+    base_trace_values: &[Fp],
+    extension_trace_values: &[Fp],
+    composition_trace_values: &[Fp],
+) -> Vec<Fp> {
+    let num_base_columns = Claim::AirConfig::NUM_BASE_COLUMNS;
+    let num_extension_columns = Claim::AirConfig::NUM_EXTENSION_COLUMNS;
+    let num_composition_columns = air.ce_blowup_factor();
+    let trace_generator = Fp::get_root_of_unity(commitments.trace_len as u64).unwrap();
+    let lde_domain_size = commitments.lde_domain_size();
+    let lde_generator = Fp::get_root_of_unity(lde_domain_size as u64).unwrap();
+    let z = transcript.ood_point;
+    let composition_ood_point = z.pow([num_composition_columns as u64]);
+    // column, out of domain point, out of domain evaluation and coefficient
+    // of each term of the trace arguments
+    let ood_evals = &commitments.execution_trace_ood_evals;
+    let trace_terms = zip(air.trace_arguments(), ood_evals)
+        .zip(&transcript.deep_coeffs.execution_trace)
+        .map(|(((column, offset), &ood_eval), &coeff)| {
+            let step = if offset < 0 {
+                trace_generator.inverse().unwrap()
+            } else {
+                trace_generator
+            };
+            let point = z * step.pow([offset.unsigned_abs() as u64]);
+            (column, point, ood_eval, coeff)
+        })
+        .collect::<Vec<(usize, Fp, Fp, Fp)>>();
+    let composition_terms = zip(
+        &commitments.composition_trace_ood_evals,
+        &transcript.deep_coeffs.composition_trace,
+    );
+
+    (transcript.query_positions.iter().enumerate())
+        .map(|(i, &position)| {
+            let base_row = &base_trace_values[i * num_base_columns..][..num_base_columns];
+            let extension_row =
+                &extension_trace_values[i * num_extension_columns..][..num_extension_columns];
+            let composition_row =
+                &composition_trace_values[i * num_composition_columns..][..num_composition_columns];
+            let exponent = position.reverse_bits() >> (usize::BITS - lde_domain_size.ilog2());
+            let x = Fp::GENERATOR * lde_generator.pow([exponent as u64]);
+            let mut res = Fp::ZERO;
+            for &(column, point, ood_eval, coeff) in &trace_terms {
+                let value = match column.checked_sub(num_base_columns) {
+                    Some(extension_column) => extension_row[extension_column],
+                    None => base_row[column],
+                };
+                res += coeff * (value - ood_eval) / (x - point);
+            }
+            for (&value, (&ood_eval, &coeff)) in zip(composition_row, composition_terms.clone()) {
+                res += coeff * (value - ood_eval) / (x - composition_ood_point);
+            }
+            res
+        })
+        .collect()
+}
+
+/// Folds a row of a FRI layer into the value of the next layer. The row is a
+/// coset of the layer's domain in bit-reversed order. Pairs of values are
+/// folded as `a + b + α (a - b) / x` with `α` squared after each step.
+pub(crate) fn fold_row(values: &[Fp], row: usize, domain_size: usize, alpha: Fp) -> Fp {
+    let mut x_invs = (0..values.len())
+        .step_by(2)
+        .map(|i| x_inv(row * values.len() + i, domain_size))
+        .collect::<Vec<Fp>>();
+    let mut values = values.to_vec();
+    let mut alpha = alpha;
+    while values.len() > 1 {
+        values = zip(values.chunks(2), &x_invs)
+            .map(|(pair, &x_inv)| pair[0] + pair[1] + alpha * x_inv * (pair[0] - pair[1]))
+            .collect();
+        x_invs = x_invs.iter().step_by(2).map(Field::square).collect();
+        alpha.square_in_place();
+    }
+    values[0]
+}
+
+/// Inverse of the FRI evaluation point at a position of a layer's domain. The
+/// domain is in bit-reversed order and the coset offset isn't included.
+pub(crate) fn x_inv(position: usize, domain_size: usize) -> Fp {
+    let generator = Fp::get_root_of_unity(domain_size as u64).unwrap();
+    let exponent = position.reverse_bits() >> (usize::BITS - domain_size.ilog2());
+    generator.pow([exponent as u64]).inverse().unwrap()
+}
+
+/// Reads the prover to verifier messages of a proof
+struct ProofReader {
+    bytes: Vec<u8>,
+    offset: usize,
+}
+
+impl ProofReader {
+    fn new(proof_hex: &str) -> Result<Self, ImportError> {
+        let hex = proof_hex.strip_prefix("0x").unwrap_or(proof_hex);
+        // slicing by byte offsets below needs single byte characters
+        if !hex.is_ascii() || hex.len() % 2 != 0 {
+            return Err(ImportError::InvalidHex);
+        }
+        let bytes = (0..hex.len())
+            .step_by(2)
+            .map(|i| u8::from_str_radix(&hex[i..i + 2], 16))
+            .collect::<Result<Vec<u8>, _>>()
+            .map_err(|_| ImportError::InvalidHex)?;
+        Ok(Self { bytes, offset: 0 })
+    }
+
+    fn remaining(&self) -> usize {
+        self.bytes.len() - self.offset
+    }
+
+    fn read_bytes<const N: usize>(&mut self) -> Result<[u8; N], ImportError> {
+        let bytes = self.bytes.get(self.offset..self.offset + N);
+        let bytes = bytes.ok_or(ImportError::UnexpectedEnd)?;
+        self.offset += N;
+        Ok(bytes.try_into().unwrap())
+    }
+
+    /// Reads the commitment to a matrix with `num_columns` columns
+    fn read_root<M: ReadDecommitment>(
+        &mut self,
+        num_columns: usize,
+    ) -> Result<M::Root, ImportError> {
+        Ok(M::read_root(num_columns, self.read_bytes::<32>()?))
+    }
+
+    /// Field elements are sent in Montgomery form
+    fn read_field_elements(&mut self, n: usize) -> Result<Vec<Fp>, ImportError> {
+        (0..n)
+            .map(|_| {
+                Ok(from_montgomery(U256::from_be_bytes(
+                    self.read_bytes::<32>()?,
+                )))
+            })
+            .collect()
+    }
+
+    fn read_u64(&mut self) -> Result<u64, ImportError> {
+        Ok(u64::from_be_bytes(self.read_bytes::<8>()?))
+    }
+
+    /// Reads the merkle authentication path of the rows made up of `values`
+    fn read_decommitment<M: ReadDecommitment>(
+        &mut self,
+        values: &[Fp],
+        num_columns: usize,
+        positions: &[usize],
+        height: u32,
+    ) -> Result<M::Proof, ImportError> {
+        let rows = values.chunks(num_columns).collect::<Vec<&[Fp]>>();
+        let mut words = std::iter::from_fn(|| self.read_bytes::<32>().ok());
+        M::read_decommitment(&rows, positions, height, &mut words).ok_or(ImportError::UnexpectedEnd)
+    }
+}
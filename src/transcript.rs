@@ -0,0 +1,112 @@
+//! Replays the verifier's public coin to recover the randomness used by a
+//! proof. Needed when converting proofs to formats where the verifier's
+//! randomness (e.g. query positions) is made explicit.
+
+use ministark::challenges::Challenges;
+use ministark::composer::DeepCompositionCoeffs;
+use ministark::random::PublicCoin;
+use ministark::stark::Stark;
+use ministark::Air;
+use ministark::Proof;
+use ministark::ProofOptions;
+
+/// Prover messages that are sent before the decommitments
+pub(crate) struct Commitments<Claim: Stark> {
+    pub trace_len: usize,
+    pub options: ProofOptions,
+    pub base_trace_commitment: Claim::Digest,
+    pub extension_trace_commitment: Option<Claim::Digest>,
+    pub composition_trace_commitment: Claim::Digest,
+    pub execution_trace_ood_evals: Vec<Claim::Fq>,
+    pub composition_trace_ood_evals: Vec<Claim::Fq>,
+    pub fri_layer_commitments: Vec<Claim::Digest>,
+    pub remainder_coeffs: Vec<Claim::Fq>,
+    pub pow_nonce: u64,
+}
+
+impl<Claim: Stark> Commitments<Claim> {
+    pub fn from_proof(proof: &Proof<Claim>) -> Self {
+        Self {
+            trace_len: proof.trace_len,
+            options: proof.options,
+            base_trace_commitment: proof.base_trace_commitment.clone(),
+            extension_trace_commitment: proof.extension_trace_commitment.clone(),
+            composition_trace_commitment: proof.composition_trace_commitment.clone(),
+            execution_trace_ood_evals: proof.execution_trace_ood_evals.clone(),
+            composition_trace_ood_evals: proof.composition_trace_ood_evals.clone(),
+            fri_layer_commitments: (proof.fri_proof.layers.iter())
+                .map(|layer| layer.commitment.clone())
+                .collect(),
+            remainder_coeffs: proof.fri_proof.remainder_coeffs.clone(),
+            pow_nonce: proof.pow_nonce,
+        }
+    }
+
+    pub fn lde_domain_size(&self) -> usize {
+        self.trace_len * usize::from(self.options.lde_blowup_factor)
+    }
+}
+
+/// Verifier randomness that isn't included in the proof
+pub(crate) struct Transcript<Claim: Stark> {
+    pub challenges: Challenges<Claim::Fq>,
+    /// Out of domain point the trace and composition polynomials are
+    /// evaluated at
+    pub ood_point: Claim::Fq,
+    /// Coefficients of the DEEP composition polynomial
+    pub deep_coeffs: DeepCompositionCoeffs<Claim::Fq>,
+    pub fri_alphas: Vec<Claim::Fq>,
+    /// Sorted and deduplicated query positions in the LDE domain
+    pub query_positions: Vec<usize>,
+}
+
+impl<Claim: Stark> Transcript<Claim> {
+    /// Follows the order messages are sent and randomness is drawn by the
+    /// verifier
+    pub fn replay(claim: &Claim, commitments: &Commitments<Claim>) -> Self {
+        let options = commitments.options;
+        let air = Air::new(commitments.trace_len, claim.get_public_inputs(), options);
+        let mut public_coin = claim.gen_public_coin(&air);
+
+        public_coin.reseed_with_digest(&commitments.base_trace_commitment);
+        let challenges = air.gen_challenges(&mut public_coin);
+        if let Some(commitment) = &commitments.extension_trace_commitment {
+            public_coin.reseed_with_digest(commitment);
+        }
+
+        claim.gen_composition_coeffs(&mut public_coin, &air);
+        public_coin.reseed_with_digest(&commitments.composition_trace_commitment);
+        let ood_point = public_coin.draw();
+        let ood_evals = [
+            commitments.execution_trace_ood_evals.as_slice(),
+            commitments.composition_trace_ood_evals.as_slice(),
+        ]
+        .concat();
+        public_coin.reseed_with_field_element_vector(&ood_evals);
+        let deep_coeffs = claim.gen_deep_coeffs(&mut public_coin, &air);
+
+        let fri_alphas = (commitments.fri_layer_commitments.iter())
+            .map(|commitment| {
+                public_coin.reseed_with_digest(commitment);
+                public_coin.draw()
+            })
+            .collect();
+        public_coin.reseed_with_field_element_vector(&commitments.remainder_coeffs);
+        if options.grinding_factor != 0 {
+            public_coin.reseed_with_int(commitments.pow_nonce);
+        }
+
+        let query_positions = public_coin
+            .draw_queries(options.num_queries.into(), commitments.lde_domain_size())
+            .into_iter()
+            .collect();
+
+        Self {
+            challenges,
+            ood_point,
+            deep_coeffs,
+            fri_alphas,
+            query_positions,
+        }
+    }
+}
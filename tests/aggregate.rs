@@ -0,0 +1,112 @@
+//! Tests for aggregating recursive proofs of `example/array-sum.cairo` with
+//! the bootloader in `example/bootloader`
+
+use ark_ff::Field;
+use ark_serialize::CanonicalDeserialize;
+use binary::AirPrivateInput;
+use binary::AirPublicInput;
+use binary::CompiledProgram;
+use binary::Layout;
+use binary::Memory;
+use binary::RegisterStates;
+use layouts::CairoWitness;
+use ministark::stark::Stark;
+use ministark::Proof;
+use ministark::ProofOptions;
+use ministark_gpu::fields::p3618502788666131213697322783095070105623107215331596699973092056135872020481::ark::Fp;
+use sandstorm::aggregate::bootloader;
+use sandstorm::aggregate::Aggregation;
+use sandstorm::aggregate::AggregationError;
+use sandstorm::aggregate::BootloaderRun;
+use sandstorm::claims::recursive::CairoVerifierClaim;
+use sandstorm::claims::starknet::EthVerifierClaim;
+
+const PROGRAM: &str = include_str!("../example/array-sum.json");
+const AIR_PUBLIC_INPUT: &str = include_str!("../example/air-public-input.json");
+const PROOF: &[u8] = include_bytes!("../example/array-sum.proof.saved");
+
+const BOOTLOADER_AIR_PUBLIC_INPUT: &str =
+    include_str!("../example/bootloader/air-public-input.json");
+const BOOTLOADER_AIR_PRIVATE_INPUT: &str =
+    include_str!("../example/bootloader/air-private-input.json");
+const BOOTLOADER_TRACE: &[u8] = include_bytes!("../example/bootloader/trace.bin");
+const BOOTLOADER_MEMORY: &[u8] = include_bytes!("../example/bootloader/memory.bin");
+
+const REQUIRED_SECURITY_BITS: usize = 80;
+
+fn array_sum_claim(air_public_input: AirPublicInput<Fp>) -> CairoVerifierClaim {
+    let program: CompiledProgram<Fp> = serde_json::from_str(PROGRAM).unwrap();
+    CairoVerifierClaim::new(program, air_public_input)
+}
+
+fn bootloader_run() -> BootloaderRun {
+    let air_public_input = serde_json::from_str(BOOTLOADER_AIR_PUBLIC_INPUT).unwrap();
+    let air_private_input: AirPrivateInput =
+        serde_json::from_str(BOOTLOADER_AIR_PRIVATE_INPUT).unwrap();
+    let register_states = RegisterStates::from_reader(BOOTLOADER_TRACE).unwrap();
+    let memory = Memory::from_reader(BOOTLOADER_MEMORY).unwrap();
+    let witness = CairoWitness::new(air_private_input, register_states, memory);
+    BootloaderRun::new(air_public_input, witness)
+}
+
+#[test]
+fn bootloader_input_has_a_task_per_proof() {
+    let air_public_input = serde_json::from_str(AIR_PUBLIC_INPUT).unwrap();
+    let claim = array_sum_claim(air_public_input);
+    let mut aggregation = Aggregation::new(REQUIRED_SECURITY_BITS);
+    for _ in 0..2 {
+        let proof = Proof::<CairoVerifierClaim>::deserialize_compressed(PROOF).unwrap();
+        aggregation.add_proof(&claim, proof).unwrap();
+    }
+    let cairo_verifier = serde_json::json!({ "prime": "0x800000000000011000000000000000000000000000000000000000000000001" });
+
+    let input = aggregation.bootloader_input(&cairo_verifier);
+    let json = serde_json::to_value(&input).unwrap();
+
+    let tasks = json["tasks"].as_array().unwrap();
+    assert_eq!(tasks.len(), 2);
+    assert_eq!(tasks[0]["type"], "RunProgramTask");
+    assert_eq!(tasks[0]["program"], cairo_verifier);
+    assert!(tasks[1]["program_input"]["proof"]["proof_hex"].is_string());
+}
+
+#[test]
+fn invalid_proof_is_not_aggregated() {
+    let mut air_public_input: AirPublicInput<Fp> = serde_json::from_str(AIR_PUBLIC_INPUT).unwrap();
+    air_public_input.public_memory.last_mut().unwrap().value += Fp::ONE;
+    let claim = array_sum_claim(air_public_input);
+    let proof = Proof::<CairoVerifierClaim>::deserialize_compressed(PROOF).unwrap();
+    let mut aggregation = Aggregation::new(REQUIRED_SECURITY_BITS);
+
+    let res = aggregation.add_proof(&claim, proof);
+
+    assert!(matches!(res, Err(AggregationError::InvalidProof(_))));
+    assert!(aggregation.is_empty());
+}
+
+#[test]
+fn bootloader_run_proves_for_ethereum() {
+    let run = bootloader_run();
+    let options = ProofOptions::new(65, 2, 16, 8, 16);
+
+    let proof = pollster::block_on(run.prove_for_ethereum(options)).unwrap();
+
+    let air_public_input = serde_json::from_str(BOOTLOADER_AIR_PUBLIC_INPUT).unwrap();
+    let claim = EthVerifierClaim::new(bootloader(), air_public_input);
+    claim.verify(proof, REQUIRED_SECURITY_BITS).unwrap();
+}
+
+#[test]
+fn recursive_proof_requires_recursive_layout() {
+    let run = bootloader_run();
+
+    let res = pollster::block_on(run.prove_recursive(ProofOptions::new(65, 2, 16, 8, 16)));
+
+    assert!(matches!(
+        res,
+        Err(AggregationError::UnexpectedLayout {
+            expected: Layout::Recursive,
+            actual: Layout::Starknet,
+        })
+    ));
+}
@@ -0,0 +1,83 @@
+//! Tests for reading the task facts of a simple bootloader run. The output
+//! segment of the array-sum run is replaced with bootloader output.
+
+use binary::AirPublicInput;
+use binary::MemoryEntry;
+use binary::Segment;
+use ministark_gpu::fields::p3618502788666131213697322783095070105623107215331596699973092056135872020481::ark::Fp;
+use ruint::aliases::U256;
+use sandstorm::batch::BatchError;
+use sandstorm::batch::BatchFacts;
+
+const AIR_PUBLIC_INPUT: &str = include_str!("../example/air-public-input.json");
+
+fn bootloader_run(output: &[u64]) -> AirPublicInput<Fp> {
+    let mut air_public_input: AirPublicInput<Fp> = serde_json::from_str(AIR_PUBLIC_INPUT).unwrap();
+    let begin_addr = 1_000_000;
+    let stop_ptr = begin_addr + output.len() as u32;
+    air_public_input.memory_segments.output = Some(Segment {
+        begin_addr,
+        stop_ptr,
+    });
+    for (address, &value) in (begin_addr..stop_ptr).zip(output) {
+        air_public_input.public_memory.push(MemoryEntry {
+            address,
+            value: value.into(),
+        });
+    }
+    air_public_input
+}
+
+#[test]
+fn facts_are_read_for_each_task() {
+    // two tasks with program hashes 11 and 22
+    let air_public_input = bootloader_run(&[2, 3, 11, 7, 4, 22, 8, 9]);
+
+    let facts = BatchFacts::from_public_input(&air_public_input).unwrap();
+
+    let program_hashes = [11, 22].map(U256::from);
+    assert!(facts.expect_program_hashes(&program_hashes).is_ok());
+    assert_eq!(facts.tasks[0].program_hash, U256::from(11));
+    assert_eq!(facts.tasks[0].output, [U256::from(7)]);
+    assert_eq!(facts.tasks[1].program_hash, U256::from(22));
+    assert_eq!(facts.tasks[1].output, [U256::from(8), U256::from(9)]);
+    assert_ne!(facts.tasks[0].fact, facts.tasks[1].fact);
+    let task_metadata = [2, 3, 11, 1, 1, 0, 4, 22, 1, 1, 0].map(U256::from);
+    assert_eq!(facts.task_metadata, task_metadata);
+}
+
+#[test]
+fn truncated_task_is_rejected() {
+    let air_public_input = bootloader_run(&[2, 3, 11, 7, 4, 22, 8]);
+
+    let res = BatchFacts::from_public_input(&air_public_input);
+
+    assert!(matches!(res, Err(BatchError::MalformedOutput(_))));
+}
+
+#[test]
+fn unexpected_number_of_tasks_is_rejected() {
+    let air_public_input = bootloader_run(&[1, 2, 11]);
+
+    let facts = BatchFacts::from_public_input(&air_public_input).unwrap();
+
+    assert!(matches!(
+        facts.expect_program_hashes(&[11, 22].map(U256::from)),
+        Err(BatchError::UnexpectedTaskCount {
+            expected: 2,
+            actual: 1
+        })
+    ));
+}
+
+#[test]
+fn task_with_another_program_hash_is_rejected() {
+    let air_public_input = bootloader_run(&[2, 3, 11, 7, 4, 22, 8, 9]);
+
+    let facts = BatchFacts::from_public_input(&air_public_input).unwrap();
+
+    assert!(matches!(
+        facts.expect_program_hashes(&[11, 33].map(U256::from)),
+        Err(BatchError::ProgramHashMismatch { task: 1, .. })
+    ));
+}
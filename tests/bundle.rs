@@ -0,0 +1,107 @@
+//! Tests for bundling the saved proof of `example/array-sum.cairo`
+
+use ark_ff::Field;
+use ark_serialize::CanonicalDeserialize;
+use binary::AirPublicInput;
+use binary::CompiledProgram;
+use binary::Layout;
+use ministark::stark::Stark;
+use ministark::Proof;
+use ministark_gpu::fields::p3618502788666131213697322783095070105623107215331596699973092056135872020481::ark::Fp;
+use num_bigint::BigUint;
+use sandstorm::bundle::BundleError;
+use sandstorm::bundle::ProofBundle;
+use sandstorm::claims::recursive::CairoVerifierClaim;
+use sandstorm::claims::ClaimSelection;
+use sandstorm::claims::CommitmentHash;
+use sandstorm::claims::Verifier;
+
+const PROGRAM: &str = include_str!("../example/array-sum.json");
+const AIR_PUBLIC_INPUT: &str = include_str!("../example/air-public-input.json");
+const PROOF: &[u8] = include_bytes!("../example/array-sum.proof.saved");
+
+fn cairo_verifier() -> ClaimSelection {
+    ClaimSelection::new(Verifier::Cairo, CommitmentHash::Pedersen).unwrap()
+}
+
+fn array_sum_bundle() -> ProofBundle {
+    let program = serde_json::from_str(PROGRAM).unwrap();
+    let air_public_input = serde_json::from_str(AIR_PUBLIC_INPUT).unwrap();
+    let proof = Proof::<CairoVerifierClaim>::deserialize_compressed(PROOF).unwrap();
+    ProofBundle::new(cairo_verifier(), program, air_public_input, &proof).unwrap()
+}
+
+#[test]
+fn bundled_proof_verifies() {
+    let mut bytes = Vec::new();
+    array_sum_bundle().write(&mut bytes).unwrap();
+
+    let bundle = ProofBundle::read(&*bytes).unwrap();
+    let claim = CairoVerifierClaim::new(bundle.program().unwrap(), bundle.air_public_input.clone());
+    let proof = bundle.proof::<CairoVerifierClaim>().unwrap();
+
+    assert_eq!(bundle.header.layout, Layout::Recursive);
+    assert_eq!(bundle.header.claim, cairo_verifier());
+    claim.verify(proof, 80).unwrap();
+}
+
+#[test]
+fn bundle_without_magic_is_rejected() {
+    let mut bytes = Vec::new();
+    array_sum_bundle().write(&mut bytes).unwrap();
+    bytes[0] ^= 1;
+
+    let res = ProofBundle::read(&*bytes);
+
+    assert!(matches!(res, Err(BundleError::InvalidMagic)));
+}
+
+#[test]
+fn mismatched_program_is_rejected() {
+    let mut program: serde_json::Value = serde_json::from_str(PROGRAM).unwrap();
+    let parsed: CompiledProgram<Fp> = serde_json::from_value(program.clone()).unwrap();
+    let modified = BigUint::from(parsed.data[0] + Fp::ONE);
+    program["data"][0] = format!("{modified:#x}").into();
+    let air_public_input: AirPublicInput<Fp> = serde_json::from_str(AIR_PUBLIC_INPUT).unwrap();
+    let proof = Proof::<CairoVerifierClaim>::deserialize_compressed(PROOF).unwrap();
+
+    let res = ProofBundle::new(cairo_verifier(), program, air_public_input, &proof);
+
+    assert!(matches!(res, Err(BundleError::ProgramMismatch { .. })));
+}
+
+#[test]
+fn program_missing_from_public_memory_is_rejected() {
+    let program = serde_json::from_str(PROGRAM).unwrap();
+    let mut air_public_input: AirPublicInput<Fp> = serde_json::from_str(AIR_PUBLIC_INPUT).unwrap();
+    let begin_addr = air_public_input.memory_segments.program.begin_addr;
+    air_public_input
+        .public_memory
+        .retain(|entry| entry.address != begin_addr);
+    let proof = Proof::<CairoVerifierClaim>::deserialize_compressed(PROOF).unwrap();
+
+    let res = ProofBundle::new(cairo_verifier(), program, air_public_input, &proof);
+
+    assert!(matches!(res, Err(BundleError::ProgramSizeMismatch { .. })));
+}
+
+#[test]
+fn repeated_program_cell_is_counted_once() {
+    let program = serde_json::from_str(PROGRAM).unwrap();
+    let mut air_public_input: AirPublicInput<Fp> = serde_json::from_str(AIR_PUBLIC_INPUT).unwrap();
+    let begin_addr = air_public_input.memory_segments.program.begin_addr;
+    let public_memory = &mut air_public_input.public_memory;
+    let position = (public_memory.iter())
+        .position(|entry| entry.address == begin_addr)
+        .unwrap();
+    // replace the first program cell with a copy of the second
+    let second_cell = *(public_memory.iter())
+        .find(|entry| entry.address == begin_addr + 1)
+        .unwrap();
+    public_memory[position] = second_cell;
+    let proof = Proof::<CairoVerifierClaim>::deserialize_compressed(PROOF).unwrap();
+
+    let res = ProofBundle::new(cairo_verifier(), program, air_public_input, &proof);
+
+    assert!(matches!(res, Err(BundleError::ProgramSizeMismatch { .. })));
+}
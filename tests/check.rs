@@ -0,0 +1,98 @@
+//! Tests for the witness checks against the witness of
+//! `example/array-sum.cairo`
+
+use ark_ff::Field;
+use binary::AirPrivateInput;
+use binary::AirPublicInput;
+use binary::Memory;
+use binary::RangeCheckInstance;
+use binary::RegisterStates;
+use layouts::check::check_witness;
+use layouts::CairoWitness;
+use ministark_gpu::fields::p3618502788666131213697322783095070105623107215331596699973092056135872020481::ark::Fp;
+use ruint::aliases::U256;
+
+const AIR_PUBLIC_INPUT: &str = include_str!("../example/air-public-input.json");
+const AIR_PRIVATE_INPUT: &str = include_str!("../example/air-private-input.json");
+const TRACE: &[u8] = include_bytes!("../example/trace.bin");
+const MEMORY: &[u8] = include_bytes!("../example/memory.bin");
+
+fn array_sum_witness() -> CairoWitness<Fp> {
+    let air_private_input: AirPrivateInput = serde_json::from_str(AIR_PRIVATE_INPUT).unwrap();
+    let register_states = RegisterStates::from_reader(TRACE).unwrap();
+    let memory = Memory::from_reader(MEMORY).unwrap();
+    CairoWitness::new(air_private_input, register_states, memory)
+}
+
+#[test]
+fn array_sum_witness_is_valid() {
+    let air_public_input: AirPublicInput<Fp> = serde_json::from_str(AIR_PUBLIC_INPUT).unwrap();
+
+    let violations = check_witness(&air_public_input, &array_sum_witness());
+
+    assert!(violations.is_empty(), "{violations:?}");
+}
+
+#[test]
+fn reports_all_violations() {
+    let mut air_public_input: AirPublicInput<Fp> = serde_json::from_str(AIR_PUBLIC_INPUT).unwrap();
+    let last_entry = air_public_input.public_memory.last_mut().unwrap();
+    last_entry.value += Fp::ONE;
+    let last_address = last_entry.address as usize;
+    air_public_input.rc_max += 1;
+
+    let violations = check_witness(&air_public_input, &array_sum_witness());
+
+    assert_eq!(violations.len(), 2);
+    assert!(violations.iter().any(|v| v.address == Some(last_address)));
+}
+
+#[test]
+fn reports_missing_public_memory_padding() {
+    let mut air_public_input: AirPublicInput<Fp> = serde_json::from_str(AIR_PUBLIC_INPUT).unwrap();
+    air_public_input.public_memory.retain(|e| e.address != 1);
+
+    let violations = check_witness(&air_public_input, &array_sum_witness());
+
+    assert_eq!(violations.len(), 1, "{violations:?}");
+    assert_eq!(violations[0].address, Some(1));
+}
+
+#[test]
+fn reports_range_checks_that_do_not_fit_in_the_trace() {
+    let mut air_public_input: AirPublicInput<Fp> = serde_json::from_str(AIR_PUBLIC_INPUT).unwrap();
+    air_public_input.rc_min = 0;
+    air_public_input.rc_max = u16::MAX;
+
+    let violations = check_witness(&air_public_input, &array_sum_witness());
+
+    assert!(violations
+        .iter()
+        .any(|v| v.reason.starts_with("range check pool")));
+}
+
+#[test]
+fn reports_builtin_inputs_missing_from_memory() {
+    let mut air_public_input: AirPublicInput<Fp> = serde_json::from_str(AIR_PUBLIC_INPUT).unwrap();
+    let range_check_segment = air_public_input
+        .memory_segments
+        .range_check
+        .as_mut()
+        .unwrap();
+    range_check_segment.stop_ptr += 1;
+    let address = range_check_segment.begin_addr as usize;
+    let mut air_private_input: AirPrivateInput = serde_json::from_str(AIR_PRIVATE_INPUT).unwrap();
+    let value = U256::from(5);
+    air_private_input
+        .range_check
+        .push(RangeCheckInstance { index: 0, value });
+    let register_states = RegisterStates::from_reader(TRACE).unwrap();
+    let memory = Memory::from_reader(MEMORY).unwrap();
+    let witness = CairoWitness::new(air_private_input, register_states, memory);
+
+    let violations = check_witness(&air_public_input, &witness);
+
+    assert!(violations
+        .iter()
+        .any(|v| v.address == Some(address) && v.reason.contains("missing from memory")));
+}
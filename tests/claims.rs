@@ -0,0 +1,146 @@
+//! Tests for picking claims of `example/array-sum.cairo` with the claim
+//! registry
+
+use ark_serialize::CanonicalDeserialize;
+use binary::AirPrivateInput;
+use binary::AirPublicInput;
+use binary::CompiledProgram;
+use binary::Layout;
+use binary::Memory;
+use binary::RegisterStates;
+use layouts::constraints::ConstraintRegistry;
+use layouts::CairoWitness;
+use ministark::air::AirConfig;
+use ministark::stark::Stark;
+use ministark::Proof;
+use ministark::ProofOptions;
+use ministark_gpu::fields::p3618502788666131213697322783095070105623107215331596699973092056135872020481::ark::Fp;
+use sandstorm::claims::visit_claim;
+use sandstorm::claims::ClaimError;
+use sandstorm::claims::ClaimSelection;
+use sandstorm::claims::ClaimVisitor;
+use sandstorm::claims::CommitmentHash;
+use sandstorm::claims::Verifier;
+
+const PROGRAM: &str = include_str!("../example/array-sum.json");
+const AIR_PUBLIC_INPUT: &str = include_str!("../example/air-public-input.json");
+const AIR_PRIVATE_INPUT: &str = include_str!("../example/air-private-input.json");
+const TRACE: &[u8] = include_bytes!("../example/trace.bin");
+const MEMORY: &[u8] = include_bytes!("../example/memory.bin");
+const PROOF: &[u8] = include_bytes!("../example/array-sum.proof.saved");
+
+const REQUIRED_SECURITY_BITS: usize = 80;
+
+/// Verifies the saved proof
+struct VerifySaved;
+
+impl ClaimVisitor for VerifySaved {
+    type Output = bool;
+
+    fn visit<Claim>(self, claim: Claim) -> bool
+    where
+        Claim: Stark<Fp = Fp, Fq = Fp, Witness = CairoWitness<Fp>>,
+        Claim::AirConfig: ConstraintRegistry + AirConfig<PublicInputs = AirPublicInput<Fp>>,
+    {
+        let proof = Proof::<Claim>::deserialize_compressed(PROOF).unwrap();
+        claim.verify(proof, REQUIRED_SECURITY_BITS).is_ok()
+    }
+}
+
+/// Proves the array-sum run and verifies the proof
+struct ProveAndVerify;
+
+impl ClaimVisitor for ProveAndVerify {
+    type Output = bool;
+
+    fn visit<Claim>(self, claim: Claim) -> bool
+    where
+        Claim: Stark<Fp = Fp, Fq = Fp, Witness = CairoWitness<Fp>>,
+        Claim::AirConfig: ConstraintRegistry + AirConfig<PublicInputs = AirPublicInput<Fp>>,
+    {
+        let air_private_input: AirPrivateInput = serde_json::from_str(AIR_PRIVATE_INPUT).unwrap();
+        let register_states = RegisterStates::from_reader(TRACE).unwrap();
+        let memory = Memory::from_reader(MEMORY).unwrap();
+        let witness = CairoWitness::new(air_private_input, register_states, memory);
+        let options = ProofOptions::new(65, 2, 16, 8, 16);
+        let proof = pollster::block_on(claim.prove(options, witness)).unwrap();
+        claim.verify(proof, REQUIRED_SECURITY_BITS).is_ok()
+    }
+}
+
+fn visit_array_sum<V: ClaimVisitor>(
+    selection: ClaimSelection,
+    visitor: V,
+) -> Result<V::Output, ClaimError> {
+    let program: CompiledProgram<Fp> = serde_json::from_str(PROGRAM).unwrap();
+    let air_public_input: AirPublicInput<Fp> = serde_json::from_str(AIR_PUBLIC_INPUT).unwrap();
+    visit_claim(selection, program, air_public_input, visitor)
+}
+
+#[test]
+fn defaults_match_layout() {
+    let starknet = ClaimSelection::resolve(Layout::Starknet, None, None).unwrap();
+    let recursive = ClaimSelection::resolve(Layout::Recursive, None, None).unwrap();
+    let native = ClaimSelection::resolve(Layout::Recursive, Some(Verifier::Native), None).unwrap();
+
+    assert_eq!(starknet.verifier(), Verifier::Evm);
+    assert_eq!(starknet.hash(), CommitmentHash::Keccak);
+    assert_eq!(recursive.verifier(), Verifier::Cairo);
+    assert_eq!(recursive.hash(), CommitmentHash::Pedersen);
+    assert_eq!(native.hash(), CommitmentHash::Blake2s);
+}
+
+#[test]
+fn unsupported_hash_is_rejected() {
+    let res = ClaimSelection::new(Verifier::Evm, CommitmentHash::Pedersen);
+
+    assert!(matches!(
+        res,
+        Err(ClaimError::UnsupportedHash {
+            verifier: Verifier::Evm,
+            hash: CommitmentHash::Pedersen
+        })
+    ));
+}
+
+#[test]
+fn names_round_trip() {
+    for verifier in [Verifier::Evm, Verifier::Cairo, Verifier::Native] {
+        assert_eq!(verifier.to_string().parse(), Ok(verifier));
+        for &hash in verifier.hashes() {
+            assert_eq!(hash.to_string().parse(), Ok(hash));
+        }
+    }
+    assert!(matches!(
+        "sha256".parse::<CommitmentHash>(),
+        Err(ClaimError::UnknownHash(_))
+    ));
+}
+
+#[test]
+fn registry_picks_saved_proof_claim() {
+    let selection = ClaimSelection::resolve(Layout::Recursive, None, None).unwrap();
+
+    assert!(visit_array_sum(selection, VerifySaved).unwrap());
+}
+
+#[test]
+fn saved_proof_is_rejected_by_other_claims() {
+    let selection = ClaimSelection::new(Verifier::Cairo, CommitmentHash::Poseidon).unwrap();
+
+    assert!(!visit_array_sum(selection, VerifySaved).unwrap());
+}
+
+#[test]
+fn every_selection_proves_and_verifies() {
+    for verifier in [Verifier::Evm, Verifier::Cairo, Verifier::Native] {
+        for &hash in verifier.hashes() {
+            let selection = ClaimSelection::new(verifier, hash).unwrap();
+
+            assert!(
+                visit_array_sum(selection, ProveAndVerify).unwrap(),
+                "{selection}"
+            );
+        }
+    }
+}
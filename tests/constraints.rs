@@ -0,0 +1,42 @@
+//! Checks the constraint registry of each layout matches its constraints
+
+use layouts::constraints::ConstraintRegistry;
+use ministark::air::AirConfig;
+use ministark_gpu::fields::p3618502788666131213697322783095070105623107215331596699973092056135872020481::ark::Fp;
+use std::collections::HashSet;
+
+const TRACE_LEN: usize = 1 << 20;
+
+fn check_registry<A: ConstraintRegistry>() {
+    let constraints = A::constraints(TRACE_LEN);
+    assert_eq!(constraints.len(), A::CONSTRAINTS.len());
+
+    let names = A::CONSTRAINTS.iter().map(|info| info.name);
+    assert_eq!(names.collect::<HashSet<_>>().len(), A::CONSTRAINTS.len());
+}
+
+#[test]
+fn plain_registry_matches_constraints() {
+    check_registry::<layouts::plain::AirConfig<Fp, Fp>>();
+}
+
+#[test]
+fn recursive_registry_matches_constraints() {
+    check_registry::<layouts::recursive::AirConfig>();
+}
+
+#[test]
+fn starknet_registry_matches_constraints() {
+    check_registry::<layouts::starknet::AirConfig>();
+}
+
+#[test]
+fn flag_constraints_are_quadratic() {
+    type A = layouts::starknet::AirConfig;
+    let index = A::constraint_index("cpu_decode_opcode_rc_b").unwrap();
+
+    let degrees = A::constraint_degrees(TRACE_LEN);
+
+    assert_eq!(index, 0);
+    assert_eq!(degrees[index], 2);
+}
@@ -0,0 +1,98 @@
+//! Tests for the constraint debugger against the witness of
+//! `example/array-sum.cairo`
+
+use ark_ff::Field;
+use binary::AirPrivateInput;
+use binary::AirPublicInput;
+use binary::CompiledProgram;
+use binary::Memory;
+use binary::RegisterStates;
+use layouts::constraints::ConstraintRegistry;
+use layouts::debug::failing_constraints;
+use layouts::debug::ConstraintFailure;
+use layouts::recursive::air::Flag;
+use layouts::CairoWitness;
+use ministark::stark::Stark;
+use ministark::Air;
+use ministark::Matrix;
+use ministark::ProofOptions;
+use ministark::Trace;
+use ministark_gpu::fields::p3618502788666131213697322783095070105623107215331596699973092056135872020481::ark::Fp;
+use sandstorm::claims::recursive::CairoVerifierClaim;
+
+const PROGRAM: &str = include_str!("../example/array-sum.json");
+const AIR_PUBLIC_INPUT: &str = include_str!("../example/air-public-input.json");
+const AIR_PRIVATE_INPUT: &str = include_str!("../example/air-private-input.json");
+const TRACE: &[u8] = include_bytes!("../example/trace.bin");
+const MEMORY: &[u8] = include_bytes!("../example/memory.bin");
+
+type A = layouts::recursive::AirConfig;
+
+/// Generates the array-sum trace, applies `corrupt` to the base trace and
+/// evaluates the constraints of the AIR for `air_public_input`
+fn array_sum_failures(
+    air_public_input: AirPublicInput<Fp>,
+    corrupt: impl FnOnce(&mut Matrix<Fp>),
+) -> Vec<ConstraintFailure<Fp>> {
+    let program: CompiledProgram<Fp> = serde_json::from_str(PROGRAM).unwrap();
+    let air_private_input: AirPrivateInput = serde_json::from_str(AIR_PRIVATE_INPUT).unwrap();
+    let register_states = RegisterStates::from_reader(TRACE).unwrap();
+    let memory = Memory::from_reader(MEMORY).unwrap();
+    let witness = CairoWitness::new(air_private_input, register_states, memory);
+    let trace = CairoVerifierClaim::new(
+        program.clone(),
+        serde_json::from_str(AIR_PUBLIC_INPUT).unwrap(),
+    )
+    .generate_trace(witness);
+
+    let claim = CairoVerifierClaim::new(program, air_public_input);
+    let trace_len = trace.base_columns().num_rows();
+    let options = ProofOptions::new(65, 2, 16, 8, 16);
+    let air = Air::new(trace_len, claim.get_public_inputs(), options);
+    let mut public_coin = claim.gen_public_coin(&air);
+    let challenges = air.gen_challenges(&mut public_coin);
+    let hints = air.gen_hints(&challenges);
+    let extension_trace = trace.build_extension_columns(&challenges);
+    let mut base_trace = trace.base_columns().clone();
+    corrupt(&mut base_trace);
+
+    failing_constraints::<A>(&challenges, &hints, &base_trace, extension_trace.as_ref())
+}
+
+#[test]
+fn array_sum_constraints_hold() {
+    let air_public_input = serde_json::from_str(AIR_PUBLIC_INPUT).unwrap();
+
+    let failures = array_sum_failures(air_public_input, |_| {});
+
+    assert!(failures.is_empty(), "{failures:?}");
+}
+
+#[test]
+fn corrupted_flag_cell_is_reported() {
+    let air_public_input = serde_json::from_str(AIR_PUBLIC_INPUT).unwrap();
+    // the flags are in the first column and the zero flag is the last row of
+    // each cycle
+    let row = Flag::Zero as usize;
+
+    let failures = array_sum_failures(air_public_input, |base_trace| {
+        base_trace[0][row] += Fp::ONE;
+    });
+
+    assert!(failures
+        .iter()
+        .any(|f| f.name == "cpu_decode_opcode_rc_zero" && f.row == row));
+}
+
+#[test]
+fn wrong_initial_ap_fails_the_named_boundary_constraint() {
+    let mut air_public_input: AirPublicInput<Fp> = serde_json::from_str(AIR_PUBLIC_INPUT).unwrap();
+    air_public_input.memory_segments.execution.begin_addr += 1;
+
+    let failures = array_sum_failures(air_public_input, |_| {});
+
+    let index = A::constraint_index("initial_ap").unwrap();
+    let failure = failures.iter().find(|f| f.index == index).unwrap();
+    assert_eq!(failure.name, "initial_ap");
+    assert_eq!(failure.row, 0);
+}
@@ -0,0 +1,158 @@
+//! Tests for the calldata of StarkWare's Ethereum verifier
+
+use ark_ff::Field;
+use binary::AirPrivateInput;
+use binary::AirPublicInput;
+use binary::CompiledProgram;
+use binary::Memory;
+use binary::RegisterStates;
+use crypto::utils::to_montgomery;
+use layouts::CairoWitness;
+use ministark::stark::Stark;
+use ministark::ProofOptions;
+use ministark_gpu::fields::p3618502788666131213697322783095070105623107215331596699973092056135872020481::ark::Fp;
+use num_bigint::BigUint;
+use ruint::aliases::U256;
+use sandstorm::claims::recursive::EthVerifierClaim;
+use sandstorm::evm::fri_queue;
+use sandstorm::evm::merkle_queue;
+use sandstorm::evm::ExportError;
+use sandstorm::evm::SplitProof;
+use std::collections::BTreeMap;
+
+const PROGRAM: &str = include_str!("../example/array-sum.json");
+const AIR_PUBLIC_INPUT: &str = include_str!("../example/air-public-input.json");
+const AIR_PRIVATE_INPUT: &str = include_str!("../example/air-private-input.json");
+const TRACE: &[u8] = include_bytes!("../example/trace.bin");
+const MEMORY: &[u8] = include_bytes!("../example/memory.bin");
+/// Proof generated by stone-prover and the `verifyFRI` arguments for its first
+/// FRI layer
+const STONE_ANNOTATED_PROOF: &str = include_str!("../example/stone/annotated_proof.json");
+const STONE_VERIFY_FRI_ARGS: &str = include_str!("../example/stone/fri_0_contract_args.json");
+
+/// Function selectors of StarkWare's verifier contracts
+const VERIFY_MERKLE_SELECTOR: &str = "0x3fe317a6";
+const VERIFY_FRI_SELECTOR: &str = "0xe85a6a28";
+const VERIFY_PROOF_AND_REGISTER_SELECTOR: &str = "0x9b3b76cc";
+
+#[test]
+fn merkle_queue_uses_node_indices() {
+    let leaves = [[1; 32], [2; 32]];
+
+    let queue = merkle_queue(&[1, 6], &leaves, 3).unwrap();
+
+    let leaf = |v: u8| U256::from_be_bytes([v; 32]);
+    assert_eq!(queue, [U256::from(9), leaf(1), U256::from(14), leaf(2)]);
+}
+
+#[test]
+fn merkle_queue_rejects_missing_leaves() {
+    let res = merkle_queue(&[1, 6], &[[1; 32]], 3);
+
+    assert_eq!(
+        res,
+        Err(ExportError::LeafCountMismatch {
+            queries: 2,
+            leaves: 1
+        })
+    );
+}
+
+#[test]
+fn fri_queue_splits_queried_and_sibling_values() {
+    let (a, b) = (Fp::from(3u8), Fp::from(5u8));
+
+    let (queue, sibling_values) = fri_queue(&[0], &[a, b], 2, 4).unwrap();
+
+    // position 0 is node 4 and the first element of the domain so its inverse
+    // is one
+    let expected = [U256::from(4), to_montgomery(a), U256::from(1), U256::ZERO];
+    assert_eq!(queue, expected);
+    assert_eq!(sibling_values, [b]);
+}
+
+#[test]
+fn fri_queue_rejects_missing_rows() {
+    let res = fri_queue(&[0, 2], &[Fp::ONE, Fp::ONE], 2, 4);
+
+    assert_eq!(
+        res,
+        Err(ExportError::FriLayerSizeMismatch {
+            expected: 4,
+            actual: 2
+        })
+    );
+}
+
+#[test]
+fn fri_queue_matches_stone_verify_fri_arguments() {
+    let stone_proof: serde_json::Value = serde_json::from_str(STONE_ANNOTATED_PROOF).unwrap();
+    let args: serde_json::Value = serde_json::from_str(STONE_VERIFY_FRI_ARGS).unwrap();
+    let uints = |values: &serde_json::Value| -> Vec<U256> {
+        let values = values.as_array().unwrap();
+        values
+            .iter()
+            .map(|v| v.as_str().unwrap().parse().unwrap())
+            .collect()
+    };
+    // the proof has the sibling values of the first layer and the queried
+    // values are in the extra annotations
+    let annotations = stone_proof["annotations"].as_array().unwrap();
+    let extra_annotations = stone_proof["extra_annotations"].as_array().unwrap();
+    let mut positions = Vec::new();
+    let mut layer_values = BTreeMap::new();
+    for annotation in annotations.iter().chain(extra_annotations) {
+        let annotation = annotation.as_str().unwrap();
+        if let Some((_, query)) = annotation.split_once("FRI/QueryIndices: ") {
+            let (_, position) = query.split_once("Number(").unwrap();
+            positions.push(position.trim_end_matches(')').parse::<usize>().unwrap());
+        } else if let Some((_, value)) = annotation.split_once("FRI/Decommitment/Layer 1: Row ") {
+            let (row, value) = value.split_once(", Column ").unwrap();
+            let (column, value) = value.split_once(": Field Element(0x").unwrap();
+            let position = row.parse::<usize>().unwrap() * 8 + column.parse::<usize>().unwrap();
+            let value = BigUint::parse_bytes(value.trim_end_matches(')').as_bytes(), 16);
+            layer_values.insert(position, Fp::from(value.unwrap()));
+        }
+    }
+    positions.sort();
+    positions.dedup();
+    let flattened_rows = layer_values.into_values().collect::<Vec<Fp>>();
+
+    let (queue, sibling_values) = fri_queue(&positions, &flattened_rows, 8, 1 << 23).unwrap();
+
+    assert_eq!(queue, uints(&args["fri_queue"]));
+    // the sibling values are followed by the authentication nodes
+    let layer_proof = uints(&args["proof"]);
+    let sibling_words = sibling_values
+        .into_iter()
+        .map(to_montgomery)
+        .collect::<Vec<_>>();
+    assert_eq!(sibling_words, layer_proof[..231]);
+}
+
+#[test]
+fn array_sum_calldata_calls_the_verifier_contracts() {
+    let program: CompiledProgram<Fp> = serde_json::from_str(PROGRAM).unwrap();
+    let air_public_input: AirPublicInput<Fp> = serde_json::from_str(AIR_PUBLIC_INPUT).unwrap();
+    let air_private_input: AirPrivateInput = serde_json::from_str(AIR_PRIVATE_INPUT).unwrap();
+    let register_states = RegisterStates::from_reader(TRACE).unwrap();
+    let memory = Memory::from_reader(MEMORY).unwrap();
+    let witness = CairoWitness::new(air_private_input, register_states, memory);
+    let claim = EthVerifierClaim::new(program, air_public_input);
+    let options = ProofOptions::new(65, 2, 16, 8, 16);
+    let proof = pollster::block_on(claim.prove(options, witness)).unwrap();
+
+    let split_proof = SplitProof::new(&claim, &proof, &[], U256::ZERO).unwrap();
+
+    // base trace, extension trace and composition trace
+    assert_eq!(split_proof.verify_merkle.len(), 3);
+    assert_eq!(split_proof.verify_fri.len(), proof.fri_proof.layers.len());
+    for calldata in &split_proof.verify_merkle {
+        assert!(calldata.starts_with(VERIFY_MERKLE_SELECTOR));
+    }
+    for calldata in &split_proof.verify_fri {
+        assert!(calldata.starts_with(VERIFY_FRI_SELECTOR));
+    }
+    let verify_proof_and_register = &split_proof.verify_proof_and_register;
+    assert!(verify_proof_and_register.starts_with(VERIFY_PROOF_AND_REGISTER_SELECTOR));
+}
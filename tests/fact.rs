@@ -0,0 +1,93 @@
+//! Tests for the fact of `example/array-sum.cairo`
+
+use binary::AirPublicInput;
+use binary::CompiledProgram;
+use ministark_gpu::fields::p3618502788666131213697322783095070105623107215331596699973092056135872020481::ark::Fp;
+use num_bigint::BigUint;
+use ruint::aliases::U256;
+use ruint::uint;
+use sandstorm::fact::program_hash;
+use sandstorm::fact::program_output;
+use sandstorm::fact::Fact;
+use sandstorm::fact::FactError;
+use sandstorm::fact::ProgramHashFn;
+
+const PROGRAM: &str = include_str!("../example/array-sum.json");
+const AIR_PUBLIC_INPUT: &str = include_str!("../example/air-public-input.json");
+const OUTPUT_PROGRAM: &str = include_str!("../example/output/main_compiled.json");
+const OUTPUT_AIR_PUBLIC_INPUT: &str = include_str!("../example/output/air-public-input.json");
+
+#[test]
+fn array_sum_has_no_output() {
+    let program: CompiledProgram<Fp> = serde_json::from_str(PROGRAM).unwrap();
+    let air_public_input: AirPublicInput<Fp> = serde_json::from_str(AIR_PUBLIC_INPUT).unwrap();
+
+    let fact = Fact::from_run(&program, &air_public_input).unwrap();
+
+    assert!(program_output(&air_public_input).unwrap().is_empty());
+    // keccak of the empty string
+    let empty_hash = uint!(0xc5d2460186f7233c927e7db2dcc703c0e500b653ca82273b7bfad8045d85a470_U256);
+    assert_eq!(fact.output_hash, empty_hash);
+}
+
+#[test]
+fn program_hash_depends_on_hash_fn() {
+    let program: CompiledProgram<Fp> = serde_json::from_str(PROGRAM).unwrap();
+
+    let pedersen_hash = program_hash(&program, ProgramHashFn::Pedersen).unwrap();
+    let poseidon_hash = program_hash(&program, ProgramHashFn::Poseidon).unwrap();
+
+    assert_ne!(pedersen_hash, poseidon_hash);
+}
+
+#[test]
+fn array_sum_program_hash_matches_cairo_lang() {
+    let program: CompiledProgram<Fp> = serde_json::from_str(PROGRAM).unwrap();
+
+    let pedersen_hash = program_hash(&program, ProgramHashFn::Pedersen).unwrap();
+    let poseidon_hash = program_hash(&program, ProgramHashFn::Poseidon).unwrap();
+    let pedersen_hash = U256::from(BigUint::from(pedersen_hash));
+    let poseidon_hash = U256::from(BigUint::from(poseidon_hash));
+
+    // Pedersen hash computed with `compute_program_hash_chain` from cairo-vm.
+    // Poseidon hash computed with `poseidon_hash_many` from `starknet-crypto`
+    // over the same data chain (cairo-lang's `use_poseidon=True` path).
+    let expected_pedersen_hash =
+        uint!(0x05050f3abed24285e9cc3ed4272db4a34a437e358e490f38f6e8bace5cdb249d_U256);
+    let expected_poseidon_hash =
+        uint!(0x00e95ec11d9e901ec49d27813615ab9481adfca4abe57021a4827a244608818c_U256);
+    assert_eq!(pedersen_hash, expected_pedersen_hash);
+    assert_eq!(poseidon_hash, expected_poseidon_hash);
+}
+
+#[test]
+fn output_program_fact() {
+    let program: CompiledProgram<Fp> = serde_json::from_str(OUTPUT_PROGRAM).unwrap();
+    let air_public_input: AirPublicInput<Fp> =
+        serde_json::from_str(OUTPUT_AIR_PUBLIC_INPUT).unwrap();
+
+    let fact = Fact::from_run(&program, &air_public_input).unwrap();
+
+    // `example/output/main.cairo` outputs 9. The program hash is from cairo-vm
+    // and the hashes are keccak over 32 byte big-endian words.
+    let expected_program_hash =
+        uint!(0x060b9192c3fdc6c36e453509534f99c8edeb80ad75946fca140fe34e68ffceb0_U256);
+    let expected_output_hash =
+        uint!(0x6e1540171b6c0c960b71a7020d9f60077f6af931a8bbf590da0223dacf75c7af_U256);
+    let expected_fact =
+        uint!(0x46ce251ce8fb9cbc84a652cfa96e1df5c291f754fb48f2af554f23f71bdc2843_U256);
+    assert_eq!(fact.program_hash, expected_program_hash);
+    assert_eq!(fact.output, [U256::from(9)]);
+    assert_eq!(fact.output_hash, expected_output_hash);
+    assert_eq!(fact.fact, expected_fact);
+}
+
+#[test]
+fn program_without_main_is_rejected() {
+    let mut program: CompiledProgram<Fp> = serde_json::from_str(PROGRAM).unwrap();
+    program.identifiers.clear();
+
+    let res = program_hash(&program, ProgramHashFn::Pedersen);
+
+    assert!(matches!(res, Err(FactError::MissingMain(_))));
+}
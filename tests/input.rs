@@ -0,0 +1,110 @@
+//! Tests for the public input as it's committed to by StarkWare's verifiers
+
+use binary::AirPublicInput;
+use crypto::hash::pedersen::PedersenHashFn;
+use ministark_gpu::fields::p3618502788666131213697322783095070105623107215331596699973092056135872020481::ark::Fp;
+use ruint::aliases::U256;
+use sandstorm::input::CairoAuxInput;
+use serde_json::json;
+
+const AIR_PUBLIC_INPUT: &str = include_str!("../example/air-public-input.json");
+
+/// Number of values before the layout specific values
+const NUM_BASE_VALUES: usize = 14;
+
+/// Public input of `example/array-sum.cairo` moved to another layout. Adds a
+/// segment for each of `builtins` after the existing bitwise segment.
+fn public_input_with_layout(layout: &str, builtins: &[&str]) -> AirPublicInput<Fp> {
+    let mut json: serde_json::Value = serde_json::from_str(AIR_PUBLIC_INPUT).unwrap();
+    json["layout"] = json!(layout);
+    let segments = json["memory_segments"].as_object_mut().unwrap();
+    for (i, builtin) in builtins.iter().enumerate() {
+        let begin_addr = 3000 + 100 * i;
+        let segment = json!({ "begin_addr": begin_addr, "stop_ptr": begin_addr + 2 });
+        segments.insert(builtin.to_string(), segment);
+    }
+    serde_json::from_value(json).unwrap()
+}
+
+#[test]
+fn small_layout_encodes_ecdsa_segment() {
+    let air_public_input = public_input_with_layout("small", &["ecdsa"]);
+
+    let elements = CairoAuxInput(&air_public_input)
+        .public_input_elements::<PedersenHashFn>()
+        .unwrap();
+
+    // "small" as a big-endian ASCII string
+    assert_eq!(elements[3], U256::from(0x736d616c6c_u64));
+    let layout_values = &elements[NUM_BASE_VALUES..];
+    assert_eq!(layout_values[0], U256::from(3000));
+    assert_eq!(layout_values[1], U256::from(3002));
+    // a single main page
+    assert_eq!(layout_values[4], U256::from(1));
+}
+
+#[test]
+fn dex_layout_matches_small_layout_encoding() {
+    let small = public_input_with_layout("small", &["ecdsa"]);
+    let dex = public_input_with_layout("dex", &["ecdsa"]);
+
+    let small_elements = CairoAuxInput(&small).public_input_elements::<PedersenHashFn>();
+    let dex_elements = CairoAuxInput(&dex).public_input_elements::<PedersenHashFn>();
+
+    let (small_elements, mut dex_elements) = (small_elements.unwrap(), dex_elements.unwrap());
+    // "dex" as a big-endian ASCII string
+    assert_eq!(dex_elements[3], U256::from(0x646578));
+    dex_elements[3] = small_elements[3];
+    assert_eq!(dex_elements, small_elements);
+}
+
+#[test]
+fn recursive_large_output_layout_encodes_poseidon_segment() {
+    let air_public_input = public_input_with_layout("recursive_large_output", &["poseidon"]);
+
+    let elements = CairoAuxInput(&air_public_input)
+        .public_input_elements::<PedersenHashFn>()
+        .unwrap();
+
+    let layout_values = &elements[NUM_BASE_VALUES..];
+    // bitwise segment of the array-sum run followed by the poseidon segment
+    assert_eq!(layout_values[0], U256::from(2508));
+    assert_eq!(layout_values[1], U256::from(2508));
+    assert_eq!(layout_values[2], U256::from(3000));
+    assert_eq!(layout_values[3], U256::from(3002));
+    assert_eq!(layout_values[6], U256::from(1));
+}
+
+#[test]
+fn all_solidity_layout_encodes_ecdsa_bitwise_and_ec_op_segments() {
+    let air_public_input = public_input_with_layout("all_solidity", &["ecdsa", "ec_op"]);
+
+    let elements = CairoAuxInput(&air_public_input)
+        .public_input_elements::<PedersenHashFn>()
+        .unwrap();
+
+    let layout_values = &elements[NUM_BASE_VALUES..];
+    assert_eq!(
+        layout_values[..6],
+        [3000, 3002, 2508, 2508, 3100, 3102].map(U256::from)
+    );
+    assert_eq!(layout_values[8], U256::from(1));
+}
+
+#[test]
+fn missing_builtin_segment_is_not_encoded() {
+    let air_public_input = public_input_with_layout("small", &[]);
+
+    let elements = CairoAuxInput(&air_public_input).public_input_elements::<PedersenHashFn>();
+
+    assert_eq!(elements, None);
+}
+
+#[test]
+fn plain_layout_is_not_encoded() {
+    let air_public_input = public_input_with_layout("plain", &[]);
+
+    let elements = CairoAuxInput(&air_public_input).public_input_elements::<PedersenHashFn>();
+
+    assert_eq!(elements, None);
+}
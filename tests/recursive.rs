@@ -0,0 +1,167 @@
+//! Regression tests for the `recursive` layout against a known-good proof of
+//! `example/array-sum.cairo`
+
+use ark_ff::Field;
+use ark_serialize::CanonicalDeserialize;
+use binary::AirPrivateInput;
+use binary::AirPublicInput;
+use binary::CompiledProgram;
+use binary::Memory;
+use binary::RegisterStates;
+use crypto::hash::pedersen::PedersenHashFn;
+use layouts::CairoWitness;
+use ministark::stark::Stark;
+use ministark::Proof;
+use ministark::ProofOptions;
+use ministark_gpu::fields::p3618502788666131213697322783095070105623107215331596699973092056135872020481::ark::Fp;
+use ruint::aliases::U256;
+use ruint::uint;
+use sandstorm::cairo_verifier::CairoVerifierInput;
+use sandstorm::claims::recursive::CairoVerifierClaim;
+use sandstorm::input::CairoAuxInput;
+use sandstorm::VerifyOutput;
+
+const PROGRAM: &str = include_str!("../example/array-sum.json");
+const AIR_PUBLIC_INPUT: &str = include_str!("../example/air-public-input.json");
+const PROOF: &[u8] = include_bytes!("../example/array-sum.proof.saved");
+const AIR_PRIVATE_INPUT: &str = include_str!("../example/air-private-input.json");
+const TRACE: &[u8] = include_bytes!("../example/trace.bin");
+const MEMORY: &[u8] = include_bytes!("../example/memory.bin");
+const STONE_ANNOTATED_PROOF: &str = include_str!("../example/stone/annotated_proof.json");
+
+const REQUIRED_SECURITY_BITS: usize = 80;
+
+fn array_sum_claim(air_public_input: AirPublicInput<Fp>) -> CairoVerifierClaim {
+    let program: CompiledProgram<Fp> = serde_json::from_str(PROGRAM).unwrap();
+    CairoVerifierClaim::new(program, air_public_input)
+}
+
+fn array_sum_witness() -> CairoWitness<Fp> {
+    let air_private_input: AirPrivateInput = serde_json::from_str(AIR_PRIVATE_INPUT).unwrap();
+    let register_states = RegisterStates::from_reader(TRACE).unwrap();
+    let memory = Memory::from_reader(MEMORY).unwrap();
+    CairoWitness::new(air_private_input, register_states, memory)
+}
+
+/// Public input of the array-sum run with addresses `30..=42` and `43..=44`
+/// moved from the main page into two continuous pages
+fn array_sum_public_input_with_pages() -> AirPublicInput<Fp> {
+    let mut json: serde_json::Value = serde_json::from_str(AIR_PUBLIC_INPUT).unwrap();
+    for entry in json["public_memory"].as_array_mut().unwrap() {
+        let page = match entry["address"].as_u64().unwrap() {
+            30..=42 => 1,
+            43..=44 => 2,
+            _ => 0,
+        };
+        entry["page"] = page.into();
+    }
+    serde_json::from_value(json).unwrap()
+}
+
+#[test]
+fn saved_array_sum_proof_verifies() {
+    let air_public_input = serde_json::from_str(AIR_PUBLIC_INPUT).unwrap();
+    let claim = array_sum_claim(air_public_input);
+    let proof = Proof::<CairoVerifierClaim>::deserialize_compressed(PROOF).unwrap();
+
+    claim.verify(proof, REQUIRED_SECURITY_BITS).unwrap();
+}
+
+#[test]
+fn saved_array_sum_proof_verifies_empty_output() {
+    let air_public_input = serde_json::from_str(AIR_PUBLIC_INPUT).unwrap();
+    let claim = array_sum_claim(air_public_input);
+    let proof = Proof::<CairoVerifierClaim>::deserialize_compressed(PROOF).unwrap();
+
+    let output = claim.verify_output(proof, REQUIRED_SECURITY_BITS).unwrap();
+
+    assert!(output.is_empty());
+}
+
+#[test]
+fn saved_array_sum_proof_rejects_modified_public_memory() {
+    let mut air_public_input: AirPublicInput<Fp> = serde_json::from_str(AIR_PUBLIC_INPUT).unwrap();
+    air_public_input.public_memory.last_mut().unwrap().value += Fp::ONE;
+    let claim = array_sum_claim(air_public_input);
+    let proof = Proof::<CairoVerifierClaim>::deserialize_compressed(PROOF).unwrap();
+
+    assert!(claim.verify(proof, REQUIRED_SECURITY_BITS).is_err());
+}
+
+#[test]
+fn proof_with_continuous_pages_verifies() {
+    let air_public_input = array_sum_public_input_with_pages();
+    assert_eq!(air_public_input.public_memory_pages.len(), 3);
+    let claim = array_sum_claim(air_public_input);
+    let options = ProofOptions::new(65, 2, 16, 8, 16);
+
+    let proof = pollster::block_on(claim.prove(options, array_sum_witness())).unwrap();
+
+    claim.verify(proof, REQUIRED_SECURITY_BITS).unwrap();
+}
+
+#[test]
+fn proof_with_continuous_pages_rejects_single_page() {
+    let claim = array_sum_claim(array_sum_public_input_with_pages());
+    let options = ProofOptions::new(65, 2, 16, 8, 16);
+    let proof = pollster::block_on(claim.prove(options, array_sum_witness())).unwrap();
+
+    // same public memory but committed to as a single main page
+    let air_public_input = serde_json::from_str(AIR_PUBLIC_INPUT).unwrap();
+    let single_page_claim = array_sum_claim(air_public_input);
+
+    assert!(single_page_claim
+        .verify(proof, REQUIRED_SECURITY_BITS)
+        .is_err());
+}
+
+#[test]
+fn saved_array_sum_proof_cairo_verifier_input() {
+    let air_public_input = serde_json::from_str(AIR_PUBLIC_INPUT).unwrap();
+    let claim = array_sum_claim(air_public_input);
+    let proof = Proof::<CairoVerifierClaim>::deserialize_compressed(PROOF).unwrap();
+
+    let num_fri_layers = proof.fri_proof.layers.len();
+    let input = CairoVerifierInput::new(&claim, &proof);
+    let json = serde_json::to_value(&input).unwrap();
+
+    assert_eq!(json["proof"]["public_input"]["layout"], "recursive");
+    assert!(input.proof.proof_hex.starts_with("0x"));
+    let annotations = &input.proof.annotations;
+    assert!(annotations[0].starts_with("P->V[0:32]"));
+    assert!(annotations[1].contains("V->P: /cpu air/STARK/Interaction: Interaction element #0"));
+    let count = |description: &str| {
+        let matching = annotations.iter().filter(|a| a.contains(description));
+        matching.count()
+    };
+    assert_eq!(count("OODS values: Evaluation point"), 1);
+    assert_eq!(count("FRI/Commitment/Layer"), 2 * num_fri_layers);
+    assert!(count("FRI/QueryIndices") > 0);
+    // the Cairo verifier reads the same fields as stone-prover's proofs
+    let stone_proof: serde_json::Value = serde_json::from_str(STONE_ANNOTATED_PROOF).unwrap();
+    let stone_fields = stone_proof.as_object().unwrap();
+    let fields = json["proof"].as_object().unwrap();
+    assert!(fields.keys().all(|field| stone_fields.contains_key(field)));
+    let extra_annotations = &input.proof.extra_annotations;
+    let last_extra_annotation = extra_annotations.last().unwrap();
+    assert!(extra_annotations[0].contains("Layer 0/Virtual Oracle/Trace 0: For node"));
+    assert!(last_extra_annotation.contains("Last Layer: xInv for index"));
+}
+
+#[test]
+fn main_page_hash_matches_starkware_pedersen() {
+    let air_public_input: AirPublicInput<Fp> = serde_json::from_str(AIR_PUBLIC_INPUT).unwrap();
+
+    let elements = CairoAuxInput(&air_public_input)
+        .public_input_elements::<PedersenHashFn>()
+        .unwrap();
+
+    // Computed independently with `starknet-crypto` (StarkWare's pedersen hash)
+    // as `H(...H(H(0, address_0), value_0)..., 2 * n_entries)` over the public
+    // memory of `example/air-public-input.json`
+    let expected_page_hash =
+        uint!(0x056460a6375fe1286089847eb0880c547a392b6c752a6eb2ad97f7f314fe09af_U256);
+    let n = elements.len();
+    assert_eq!(elements[n - 2], U256::from(44));
+    assert_eq!(elements[n - 1], expected_page_hash);
+}
@@ -0,0 +1,198 @@
+//! Round trips a proof of `example/array-sum.cairo` through StarkWare's JSON
+//! proof format and imports a proof generated by stone-prover.
+//! `example/stone/annotated_proof.json` is a stone-prover proof (and its
+//! public input) of a bootloader run with the starknet layout.
+
+use binary::AirPrivateInput;
+use binary::AirPublicInput;
+use binary::CompiledProgram;
+use binary::Memory;
+use binary::RegisterStates;
+use crypto::merkle::Decommitment;
+use crypto::merkle::ReadDecommitment;
+use layouts::CairoWitness;
+use ministark::merkle::MerkleTree;
+use ministark::stark::Stark;
+use ministark::ProofOptions;
+use ministark_gpu::fields::p3618502788666131213697322783095070105623107215331596699973092056135872020481::ark::Fp;
+use sandstorm::annotated::AnnotatedProof;
+use sandstorm::claims::recursive::CairoVerifierClaim;
+use sandstorm::claims::recursive::EthVerifierClaim;
+use sandstorm::claims::starknet;
+use sandstorm::stone::ImportError;
+use sandstorm::stone::StoneProof;
+use std::collections::BTreeSet;
+
+const PROGRAM: &str = include_str!("../example/array-sum.json");
+const AIR_PUBLIC_INPUT: &str = include_str!("../example/air-public-input.json");
+const AIR_PRIVATE_INPUT: &str = include_str!("../example/air-private-input.json");
+const TRACE: &[u8] = include_bytes!("../example/trace.bin");
+const MEMORY: &[u8] = include_bytes!("../example/memory.bin");
+const STONE_ANNOTATED_PROOF: &str = include_str!("../example/stone/annotated_proof.json");
+const BOOTLOADER_PROGRAM: &str = include_str!("../example/bootloader/bootloader_compiled.json");
+
+const REQUIRED_SECURITY_BITS: usize = 80;
+
+fn array_sum_annotated_proof<Claim>(
+    new_claim: fn(CompiledProgram<Fp>, AirPublicInput<Fp>) -> Claim,
+) -> (Claim, AnnotatedProof)
+where
+    Claim: Stark<Fp = Fp, Fq = Fp, Witness = CairoWitness<Fp>>,
+    <Claim::MerkleTree as MerkleTree>::Proof: Decommitment,
+{
+    let program: CompiledProgram<Fp> = serde_json::from_str(PROGRAM).unwrap();
+    let air_public_input: AirPublicInput<Fp> = serde_json::from_str(AIR_PUBLIC_INPUT).unwrap();
+    let air_private_input: AirPrivateInput = serde_json::from_str(AIR_PRIVATE_INPUT).unwrap();
+    let register_states = RegisterStates::from_reader(TRACE).unwrap();
+    let memory = Memory::from_reader(MEMORY).unwrap();
+    let witness = CairoWitness::new(air_private_input, register_states, memory);
+    let claim = new_claim(program, air_public_input);
+    let options = ProofOptions::new(65, 2, 16, 8, 16);
+    let proof = pollster::block_on(claim.prove(options, witness)).unwrap();
+    let annotated_proof = AnnotatedProof::new(&claim, &proof);
+    (claim, annotated_proof)
+}
+
+fn array_sum_stone_proof<Claim>(
+    new_claim: fn(CompiledProgram<Fp>, AirPublicInput<Fp>) -> Claim,
+) -> (Claim, StoneProof)
+where
+    Claim: Stark<Fp = Fp, Fq = Fp, Witness = CairoWitness<Fp>>,
+    <Claim::MerkleTree as MerkleTree>::Proof: Decommitment,
+{
+    let (claim, annotated_proof) = array_sum_annotated_proof(new_claim);
+    let json = serde_json::to_string(&annotated_proof).unwrap();
+    (claim, serde_json::from_str(&json).unwrap())
+}
+
+/// Annotation of a message without its numbers and values e.g.
+/// `P->V[N:N]: /cpu air/STARK/FRI/Decommitment/Layer N: For node N: Hash(X)`.
+/// Lists of values are reduced to a single value.
+fn annotation_grammar(annotation: &str) -> String {
+    let mut grammar = String::new();
+    let mut chars = annotation.chars().peekable();
+    while let Some(c) = chars.next() {
+        if c == '0' && chars.next_if_eq(&'x').is_some() {
+            while chars.next_if(char::is_ascii_hexdigit).is_some() {}
+            grammar.push('X');
+        } else if c.is_ascii_digit() {
+            while chars.next_if(char::is_ascii_digit).is_some() {}
+            grammar.push('N');
+        } else {
+            grammar.push(c);
+        }
+    }
+    grammar.replace("X, ", "")
+}
+
+fn annotations_grammar(annotations: &[String]) -> BTreeSet<String> {
+    (annotations.iter())
+        .filter(|annotation| annotation.contains("->"))
+        .map(|annotation| annotation_grammar(annotation))
+        .collect()
+}
+
+fn assert_verifies_after_import<Claim>(claim: Claim, stone_proof: StoneProof)
+where
+    Claim: Stark<Fp = Fp, Fq = Fp>,
+    Claim::MerkleTree: ReadDecommitment<Root = Claim::Digest>,
+{
+    let proof = stone_proof.into_proof(&claim).unwrap();
+
+    claim.verify(proof, REQUIRED_SECURITY_BITS).unwrap();
+}
+
+#[test]
+fn exported_proof_verifies_after_import() {
+    let (claim, stone_proof) = array_sum_stone_proof(EthVerifierClaim::new);
+    assert_verifies_after_import(claim, stone_proof);
+}
+
+#[test]
+fn exported_cairo_verifier_proof_verifies_after_import() {
+    let (claim, stone_proof) = array_sum_stone_proof(CairoVerifierClaim::new);
+    assert_verifies_after_import(claim, stone_proof);
+}
+
+#[test]
+fn stone_prover_proof_verifies_after_import() {
+    let json: serde_json::Value = serde_json::from_str(STONE_ANNOTATED_PROOF).unwrap();
+    let air_public_input = serde_json::from_value(json["public_input"].clone()).unwrap();
+    // the program isn't needed to verify so any program will do
+    let program = serde_json::from_str(BOOTLOADER_PROGRAM).unwrap();
+    let claim = starknet::EthVerifierClaim::new(program, air_public_input);
+    let stone_proof = serde_json::from_value(json).unwrap();
+
+    assert_verifies_after_import(claim, stone_proof);
+}
+
+#[test]
+fn annotations_match_stone_prover() {
+    let stone_proof: serde_json::Value = serde_json::from_str(STONE_ANNOTATED_PROOF).unwrap();
+    let stone_annotations: Vec<String> =
+        serde_json::from_value(stone_proof["annotations"].clone()).unwrap();
+    let (_, annotated_proof) = array_sum_annotated_proof(EthVerifierClaim::new);
+
+    let grammar = annotations_grammar(&annotated_proof.annotations);
+
+    let stone_grammar = annotations_grammar(&stone_annotations);
+    let unknown = grammar.difference(&stone_grammar).collect::<Vec<_>>();
+    assert!(
+        unknown.is_empty(),
+        "not in stone's annotations: {unknown:?}"
+    );
+    // the random elements of the composition polynomials aren't annotated and
+    // the array-sum proof has no single column trace (which is packaged)
+    let missing = stone_grammar.difference(&grammar).collect::<Vec<_>>();
+    assert!(
+        missing.iter().all(|annotation| {
+            annotation.contains("Constraint polynomial random element")
+                || annotation.contains("To complete packages")
+        }),
+        "missing stone's annotations: {missing:?}"
+    );
+}
+
+#[test]
+fn extra_annotations_match_stone_prover() {
+    let stone_proof: serde_json::Value = serde_json::from_str(STONE_ANNOTATED_PROOF).unwrap();
+    let stone_extra_annotations: Vec<String> =
+        serde_json::from_value(stone_proof["extra_annotations"].clone()).unwrap();
+    let (_, annotated_proof) = array_sum_annotated_proof(EthVerifierClaim::new);
+    let grammar = |annotations: &[String]| {
+        (annotations.iter())
+            .map(|annotation| annotation_grammar(annotation))
+            .collect::<Vec<String>>()
+    };
+
+    let extra_grammar = grammar(&annotated_proof.extra_annotations);
+
+    let stone_extra_grammar = grammar(&stone_extra_annotations);
+    assert_eq!(
+        extra_grammar.iter().collect::<BTreeSet<_>>(),
+        stone_extra_grammar.iter().collect::<BTreeSet<_>>()
+    );
+    assert_eq!(extra_grammar.first(), stone_extra_grammar.first());
+    assert_eq!(extra_grammar.last(), stone_extra_grammar.last());
+}
+
+#[test]
+fn import_rejects_non_ascii_hex() {
+    let (claim, mut stone_proof) = array_sum_stone_proof(EthVerifierClaim::new);
+    stone_proof.proof_hex.replace_range(2..4, "é");
+
+    let res = stone_proof.into_proof(&claim);
+
+    assert!(matches!(res, Err(ImportError::InvalidHex)));
+}
+
+#[test]
+fn import_rejects_truncated_proof() {
+    let (claim, mut stone_proof) = array_sum_stone_proof(EthVerifierClaim::new);
+    let truncated_len = stone_proof.proof_hex.len() - 64;
+    stone_proof.proof_hex.truncate(truncated_len);
+
+    let res = stone_proof.into_proof(&claim);
+
+    assert!(matches!(res, Err(ImportError::UnexpectedEnd)));
+}
@@ -0,0 +1,71 @@
+//! Runs `example/array-sum.cairo` with sandstorm's Cairo VM and compares the
+//! execution against the output of `cairo-run --proof_mode`
+
+use binary::vm::run;
+use binary::AirPublicInput;
+use binary::CompiledProgram;
+use binary::Memory;
+use binary::RegisterStates;
+use binary::VmError;
+use layouts::recursive::VM_PARAMS;
+use ministark_gpu::fields::p3618502788666131213697322783095070105623107215331596699973092056135872020481::ark::Fp;
+
+const PROGRAM: &str = include_str!("../example/array-sum.json");
+const AIR_PUBLIC_INPUT: &str = include_str!("../example/air-public-input.json");
+const TRACE: &[u8] = include_bytes!("../example/trace.bin");
+const MEMORY: &[u8] = include_bytes!("../example/memory.bin");
+
+const MAX_STEPS: usize = 1 << 20;
+
+#[test]
+fn array_sum_execution_matches_cairo_run() {
+    let program: CompiledProgram<Fp> = serde_json::from_str(PROGRAM).unwrap();
+    let expected_public_input: AirPublicInput<Fp> = serde_json::from_str(AIR_PUBLIC_INPUT).unwrap();
+    let expected_trace = RegisterStates::from_reader(TRACE).unwrap();
+    let expected_memory = Memory::<Fp>::from_reader(MEMORY).unwrap();
+
+    let execution = run(&program, &VM_PARAMS, MAX_STEPS).unwrap();
+
+    assert_eq!(*execution.register_states, *expected_trace);
+    let memory = execution.memory.iter().map(|(a, w)| (a, w.0));
+    let expected_memory = expected_memory.iter().map(|(a, w)| (a, w.0));
+    assert!(memory.eq(expected_memory));
+    let public_input = execution.air_public_input;
+    assert_eq!(public_input.n_steps, expected_public_input.n_steps);
+    assert_eq!(public_input.rc_min, expected_public_input.rc_min);
+    assert_eq!(public_input.rc_max, expected_public_input.rc_max);
+    assert_eq!(
+        public_input.memory_segments,
+        expected_public_input.memory_segments
+    );
+    assert_eq!(
+        public_input.public_memory,
+        expected_public_input.public_memory
+    );
+    assert_eq!(
+        public_input.public_memory_pages,
+        expected_public_input.public_memory_pages
+    );
+}
+
+#[test]
+fn unsupported_hint_is_rejected() {
+    let mut program: CompiledProgram<Fp> = serde_json::from_str(PROGRAM).unwrap();
+    for hint in program.hints.values_mut().flatten() {
+        hint.code = "memory[ap] = 1".to_string();
+    }
+
+    let err = run(&program, &VM_PARAMS, MAX_STEPS).unwrap_err();
+
+    assert!(matches!(err, VmError::UnsupportedHint { .. }));
+}
+
+#[test]
+fn unsupported_builtin_is_rejected() {
+    let mut program: CompiledProgram<Fp> = serde_json::from_str(PROGRAM).unwrap();
+    program.builtins.push("pedersen".to_string());
+
+    let err = run(&program, &VM_PARAMS, MAX_STEPS).unwrap_err();
+
+    assert!(matches!(err, VmError::UnsupportedBuiltin(name) if name == "pedersen"));
+}